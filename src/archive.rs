@@ -0,0 +1,98 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+// Archive suffixes that can be scanned; tar detects the compression itself
+const ARCHIVE_SUFFIXES: &[&str] = &[
+    ".zip", ".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tar.xz", ".txz", ".tar",
+];
+
+// Above this size extraction is confirmed first (bytes)
+pub const LARGE_ARCHIVE: u64 = 200 * 1024 * 1024;
+
+pub fn is_archive(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    ARCHIVE_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+// External tool unpacking `archive`
+pub fn extract_tool(archive: &Path) -> &'static str {
+    let zip = archive
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("zip"));
+    if zip {
+        "unzip"
+    } else {
+        "tar"
+    }
+}
+
+// Unpack `archive` into `dest` on a worker thread; `dest` is removed
+// again if that fails
+pub fn spawn_extract(archive: PathBuf, dest: PathBuf) -> Receiver<Result<(), String>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let result = extract(&archive, &dest);
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&dest);
+        }
+        let _ = tx.send(result);
+    });
+    rx
+}
+
+fn extract(archive: &Path, dest: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let tool = extract_tool(archive);
+    let mut cmd = Command::new(tool);
+    if tool == "unzip" {
+        cmd.arg("-q").arg(archive).arg("-d").arg(dest);
+    } else {
+        cmd.arg("-xf").arg(archive).arg("-C").arg(dest);
+    }
+    let out = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", tool, e))?;
+    if !out.status.success() {
+        return Err(format!(
+            "Failed to extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+// An unpacked archive; findings under `root` are shown with paths inside
+// the archive, e.g. "foo-1.0.tar.gz/src/main.c". The files are deleted
+// when this is dropped
+pub struct Extracted {
+    pub root: PathBuf,
+    pub name: String,
+}
+
+impl Extracted {
+    pub fn display_path(&self, path: &str) -> String {
+        match Path::new(path).strip_prefix(&self.root) {
+            Ok(rest) => format!("{}/{}", self.name, rest.display()),
+            Err(_) => path.to_string(),
+        }
+    }
+
+    // The extracted file behind a path from display_path
+    pub fn real_path(&self, shown: &str) -> Option<String> {
+        let rest = shown.strip_prefix(&self.name)?.strip_prefix('/')?;
+        Some(self.root.join(rest).to_string_lossy().to_string())
+    }
+}
+
+impl Drop for Extracted {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::findings::Finding;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// (file, line) of every finding on a line changed less than `days` ago,
+// from git blame on a worker thread. Files are taken relative to `dir`,
+// the directory cppcheck ran in; outside a git work tree nothing is sent
+// but an empty set
+pub fn spawn_recent(
+    dir: PathBuf,
+    findings: &[Finding],
+    days: u32,
+) -> Receiver<HashSet<(String, u32)>> {
+    let files: BTreeSet<String> = findings
+        .iter()
+        .filter(|f| f.line > 0 && f.file != "nofile")
+        .map(|f| f.file.clone())
+        .collect();
+    let lines: HashSet<(String, u32)> = findings.iter().map(|f| (f.file.clone(), f.line)).collect();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut recent = HashSet::new();
+        if in_work_tree(&dir) {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            let since = now - i64::from(days) * 24 * 60 * 60;
+            for file in files {
+                for line in changed_since(&dir, &file, since) {
+                    if lines.contains(&(file.clone(), line)) {
+                        recent.insert((file.clone(), line));
+                    }
+                }
+            }
+        }
+        let _ = tx.send(recent);
+    });
+    rx
+}
+
+fn in_work_tree(dir: &Path) -> bool {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .is_ok_and(|out| out.status.success())
+}
+
+// 1-based lines of `file` last changed at or after the unix time `since`;
+// uncommitted lines count as changed now. Empty when git can't blame it
+fn changed_since(dir: &Path, file: &str, since: i64) -> Vec<u32> {
+    let Ok(out) = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "--line-porcelain", "--", file])
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !out.status.success() {
+        return Vec::new();
+    }
+    parse_line_porcelain(&String::from_utf8_lossy(&out.stdout), since)
+}
+
+// Every line of --line-porcelain output starts with a header
+// "<commit> <original line> <final line> [<count>]" followed by the commit
+// details, author-time among them, and the line itself after a tab
+fn parse_line_porcelain(output: &str, since: i64) -> Vec<u32> {
+    let mut changed = Vec::new();
+    let mut line = None;
+    for text in output.lines() {
+        if text.starts_with('\t') {
+            line = None;
+            continue;
+        }
+        if let Some(time) = text.strip_prefix("author-time ") {
+            if let (Some(line), Ok(time)) = (line, time.trim().parse::<i64>()) {
+                if time >= since {
+                    changed.push(line);
+                }
+            }
+            continue;
+        }
+        let mut fields = text.split(' ');
+        let is_header = fields
+            .next()
+            .is_some_and(|c| c.len() >= 40 && c.chars().all(|c| c.is_ascii_hexdigit()));
+        if is_header {
+            line = fields.nth(1).and_then(|l| l.parse().ok());
+        }
+    }
+    changed
+}
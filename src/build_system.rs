@@ -0,0 +1,80 @@
+use crate::config::ProjectSettings;
+use std::fs;
+use std::path::Path;
+
+// Where CMake and Meson builds usually leave their compile database,
+// relative to the project
+const COMPILE_DATABASES: &[&str] = &["compile_commands.json", "build/compile_commands.json"];
+
+// A setting the build files of a project point to
+pub struct Suggestion {
+    pub text: String,
+    pub action: Option<Action>,
+}
+
+pub enum Action {
+    // Scan the files of this compile database, relative to the project
+    UseCompileDatabase(String),
+    AddLibrary(&'static str),
+}
+
+impl Action {
+    pub fn label(&self) -> String {
+        match self {
+            Action::UseCompileDatabase(_) => "Use compile database".to_string(),
+            Action::AddLibrary(library) => format!("Add {} library", library),
+        }
+    }
+}
+
+// Suggestions for the project at `root` from its build files, leaving out
+// what `settings` already has
+pub fn suggest(root: &Path, settings: &ProjectSettings) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    let database = COMPILE_DATABASES
+        .iter()
+        .find(|db| root.join(db).is_file())
+        .map(|db| db.to_string());
+    match database {
+        Some(db) if settings.compile_database.is_empty() => suggestions.push(Suggestion {
+            text: format!(
+                "{} found: scanning the files it lists, with their include paths \
+                 and defines, gives more exact results",
+                db
+            ),
+            action: Some(Action::UseCompileDatabase(db)),
+        }),
+        Some(_) => {}
+        None if root.join("CMakeLists.txt").is_file() => suggestions.push(Suggestion {
+            text: "CMake project: configure it with -DCMAKE_EXPORT_COMPILE_COMMANDS=ON \
+                   to get a compile_commands.json with its include paths and defines"
+                .to_string(),
+            action: None,
+        }),
+        None if root.join("Makefile").is_file() => suggestions.push(Suggestion {
+            text: "Makefile project: a compile_commands.json, e.g. from \"bear -- make\", \
+                   gives cppcheck its include paths and defines"
+                .to_string(),
+            action: None,
+        }),
+        None => {}
+    }
+    if has_qmake_project(root) && !settings.libraries.iter().any(|l| l == "qt") {
+        suggestions.push(Suggestion {
+            text: "Qt project (.pro file): the qt library teaches cppcheck Qt's \
+                   macros and types"
+                .to_string(),
+            action: Some(Action::AddLibrary("qt")),
+        });
+    }
+    suggestions
+}
+
+fn has_qmake_project(root: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(root) else {
+        return false;
+    };
+    entries
+        .flatten()
+        .any(|e| e.path().extension().is_some_and(|x| x == "pro"))
+}
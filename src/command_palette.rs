@@ -0,0 +1,145 @@
+use crate::i18n::tr;
+use gtk4::prelude::*;
+use gtk4::{
+    ApplicationWindow, Box as GtkBox, Button, Label, ListBox, Orientation, ScrolledWindow,
+    SearchEntry, Window,
+};
+use std::rc::Rc;
+
+// Something the palette can do, listed while `enabled` says so
+pub struct Action {
+    pub label: String,
+    enabled: Box<dyn Fn() -> bool>,
+    run: Box<dyn Fn()>,
+}
+
+impl Action {
+    pub fn new<E: Fn() -> bool + 'static, R: Fn() + 'static>(
+        label: &str,
+        enabled: E,
+        run: R,
+    ) -> Action {
+        Action {
+            label: label.to_string(),
+            enabled: Box::new(enabled),
+            run: Box::new(run),
+        }
+    }
+
+    // Clicks `button`, under its own label, while it is sensitive
+    pub fn button(button: &Button) -> Action {
+        let label = button.label().map(|l| l.to_string()).unwrap_or_default();
+        let enabled = button.clone();
+        let run = button.clone();
+        Action::new(
+            label.trim_end_matches('…'),
+            move || enabled.is_sensitive(),
+            move || run.emit_clicked(),
+        )
+    }
+}
+
+// Every word of `query` appears in `label`, ignoring case
+fn matches(label: &str, query: &str) -> bool {
+    let label = label.to_lowercase();
+    query
+        .to_lowercase()
+        .split_whitespace()
+        .all(|word| label.contains(word))
+}
+
+// Searchable list of the enabled actions; Enter runs the selected or first
+// matching one, Escape closes
+pub fn show(parent: &ApplicationWindow, actions: &Rc<Vec<Action>>) {
+    let window = Window::builder()
+        .title(tr("Command Palette"))
+        .transient_for(parent)
+        .modal(true)
+        .default_width(420)
+        .default_height(360)
+        .build();
+    let enabled: Vec<usize> = (0..actions.len())
+        .filter(|&i| (actions[i].enabled)())
+        .collect();
+    let enabled = Rc::new(enabled);
+
+    let search = SearchEntry::new();
+    search.set_placeholder_text(Some("Type to find an action"));
+    search.set_key_capture_widget(Some(&window));
+    let list = ListBox::new();
+    for &i in enabled.iter() {
+        let label = Label::new(Some(&actions[i].label));
+        label.set_xalign(0.0);
+        label.set_margin_top(4);
+        label.set_margin_bottom(4);
+        list.append(&label);
+    }
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&list));
+
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+    vbox.append(&search);
+    vbox.append(&scrolled);
+    window.set_child(Some(&vbox));
+
+    {
+        let actions = actions.clone();
+        let enabled = enabled.clone();
+        let search = search.clone();
+        list.set_filter_func(move |row| {
+            let label = &actions[enabled[row.index() as usize]].label;
+            matches(label, &search.text())
+        });
+    }
+    // Keep the first match selected so Enter always has a target
+    let select_first = {
+        let actions = actions.clone();
+        let enabled = enabled.clone();
+        let list = list.clone();
+        let search = search.clone();
+        move || {
+            let query = search.text();
+            let first = enabled
+                .iter()
+                .position(|&i| matches(&actions[i].label, &query));
+            list.select_row(first.and_then(|i| list.row_at_index(i as i32)).as_ref());
+        }
+    };
+    select_first();
+    let run = {
+        let actions = actions.clone();
+        let enabled = enabled.clone();
+        let window = window.clone();
+        Rc::new(move |index: usize| {
+            window.close();
+            (actions[enabled[index]].run)();
+        })
+    };
+    {
+        let list_clone = list.clone();
+        search.connect_search_changed(move |_| {
+            list_clone.invalidate_filter();
+            select_first();
+        });
+    }
+    {
+        let list_clone = list.clone();
+        let run = run.clone();
+        search.connect_activate(move |_| {
+            if let Some(row) = list_clone.selected_row() {
+                run(row.index() as usize);
+            }
+        });
+    }
+    {
+        let window = window.clone();
+        search.connect_stop_search(move |_| window.close());
+    }
+    list.connect_row_activated(move |_, row| run(row.index() as usize));
+    window.present();
+}
@@ -0,0 +1,346 @@
+use crate::append_text;
+use crate::export;
+use crate::findings::Finding;
+use crate::i18n::tr;
+use gtk4::prelude::*;
+use gtk4::{
+    Align, ApplicationWindow, Box as GtkBox, Button, CellRendererText, ComboBoxText,
+    FileChooserAction, FileChooserDialog, FileFilter, Label, ListStore, Orientation, ResponseType,
+    ScrolledWindow, TextBuffer, TreeView, TreeViewColumn, Window,
+};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::rc::Rc;
+
+// Findings of two scans matched up by Finding::review_key
+pub struct ScanDiff {
+    pub added: Vec<Finding>,
+    pub removed: Vec<Finding>,
+    pub unchanged: Vec<Finding>,
+}
+
+// Values of cppcheck's --std
+pub const STANDARDS: &[&str] = &[
+    "c89", "c99", "c11", "c17", "c++03", "c++11", "c++14", "c++17", "c++20",
+];
+
+// Duplicate keys are matched one to one, so a finding that appears twice
+// before and once after counts as one unchanged and one removed
+pub fn diff(before: &[Finding], after: &[Finding]) -> ScanDiff {
+    let mut remaining: BTreeMap<String, Vec<&Finding>> = BTreeMap::new();
+    for f in before {
+        remaining.entry(f.review_key()).or_default().push(f);
+    }
+    let mut added = Vec::new();
+    let mut unchanged = Vec::new();
+    for f in after {
+        match remaining.get_mut(&f.review_key()).and_then(|v| v.pop()) {
+            Some(_) => unchanged.push(f.clone()),
+            None => added.push(f.clone()),
+        }
+    }
+    let removed = before
+        .iter()
+        .filter(|f| {
+            remaining
+                .get_mut(&f.review_key())
+                .is_some_and(|v| v.pop().is_some())
+        })
+        .cloned()
+        .collect();
+    ScanDiff {
+        added,
+        removed,
+        unchanged,
+    }
+}
+
+// One side of the comparison: a loaded results file
+#[derive(Clone)]
+struct Side {
+    findings: Rc<RefCell<Option<Vec<Finding>>>>,
+    label: Label,
+}
+
+// Window comparing two exported JSON result files
+pub fn show(parent: &ApplicationWindow, log: &TextBuffer) {
+    let window = Window::builder()
+        .title(tr("Compare Scans"))
+        .transient_for(parent)
+        .default_width(1000)
+        .default_height(500)
+        .build();
+
+    let (columns, lists) = diff_columns();
+
+    let before = Side {
+        findings: Rc::new(RefCell::new(None)),
+        label: Label::new(Some(tr("No file"))),
+    };
+    let after = Side {
+        findings: Rc::new(RefCell::new(None)),
+        label: Label::new(Some(tr("No file"))),
+    };
+    let update = {
+        let before = before.clone();
+        let after = after.clone();
+        let lists = lists.clone();
+        Rc::new(move || {
+            let (Some(b), Some(a)) = (&*before.findings.borrow(), &*after.findings.borrow()) else {
+                return;
+            };
+            set_diff(&lists, b, a);
+        })
+    };
+
+    let header = GtkBox::new(Orientation::Horizontal, 8);
+    for (title, side) in [("Before…", &before), ("After…", &after)] {
+        let btn = Button::with_label(title);
+        side.label.set_xalign(0.0);
+        side.label.set_hexpand(true);
+        header.append(&btn);
+        header.append(&side.label);
+        let side = side.clone();
+        let update = update.clone();
+        let log = log.clone();
+        let win_clone = window.clone();
+        btn.connect_clicked(move |_| {
+            let side = side.clone();
+            let update = update.clone();
+            let log = log.clone();
+            choose_results_file(&win_clone, move |path| {
+                match fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| export::from_json(&text))
+                {
+                    Ok(findings) => {
+                        side.label.set_text(path);
+                        *side.findings.borrow_mut() = Some(findings);
+                        update();
+                    }
+                    Err(e) => append_text(&log, &format!("Failed to load {}: {}\n", path, e)),
+                }
+            });
+        });
+    }
+
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.append(&Label::new(Some(
+        "Load two results files exported as JSON to see what changed between them.",
+    )));
+    vbox.append(&header);
+    vbox.append(&columns);
+    present(&window, &vbox);
+}
+
+// Window with the diff of two finished scans, each labelled with what
+// set it apart, e.g. the standard it was run with
+pub fn show_diff(
+    parent: &ApplicationWindow,
+    title: &str,
+    before: (&str, &[Finding]),
+    after: (&str, &[Finding]),
+) {
+    let window = Window::builder()
+        .title(title)
+        .transient_for(parent)
+        .default_width(1000)
+        .default_height(500)
+        .build();
+    let (columns, lists) = diff_columns();
+    set_diff(&lists, before.1, after.1);
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.append(&Label::new(Some(&format!(
+        "Findings of {} ({}) compared with {} ({}): added and removed are \
+         relative to {}.",
+        after.0,
+        after.1.len(),
+        before.0,
+        before.1.len(),
+        before.0
+    ))));
+    vbox.append(&columns);
+    present(&window, &vbox);
+}
+
+// Ask for two standards to compare, the older one first
+pub fn choose_standards<F: Fn(&str, &str) + 'static>(parent: &ApplicationWindow, on_chosen: F) {
+    let window = Window::builder()
+        .title(tr("Compare Standards"))
+        .transient_for(parent)
+        .modal(true)
+        .build();
+    let combos = ["c++11", "c++17"].map(|selected| {
+        let combo = ComboBoxText::new();
+        for std in STANDARDS {
+            combo.append(Some(std), std);
+        }
+        combo.set_active_id(Some(selected));
+        combo
+    });
+    let row = GtkBox::new(Orientation::Horizontal, 8);
+    for (label, combo) in ["From:", "To:"].iter().zip(&combos) {
+        row.append(&Label::new(Some(label)));
+        row.append(combo);
+    }
+    let compare_btn = Button::with_label(tr("Compare"));
+    compare_btn.set_halign(Align::End);
+    {
+        let window = window.clone();
+        let combos = combos.clone();
+        compare_btn.connect_clicked(move |_| {
+            let [from, to] = &combos;
+            if let (Some(from), Some(to)) = (from.active_id(), to.active_id()) {
+                window.close();
+                on_chosen(&from, &to);
+            }
+        });
+    }
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+    vbox.append(&Label::new(Some(
+        "Scan the project with each standard and list how the findings differ.",
+    )));
+    vbox.append(&row);
+    vbox.append(&compare_btn);
+    window.set_child(Some(&vbox));
+    window.present();
+}
+
+// Added, removed and unchanged lists side by side
+fn diff_columns() -> (GtkBox, Rc<[DiffList; 3]>) {
+    let lists = [
+        ("Added", "#c01c28"),
+        ("Removed", "#26a269"),
+        ("Unchanged", "#77767b"),
+    ]
+    .map(|(title, color)| DiffList::new(title, color));
+    let columns = GtkBox::new(Orientation::Horizontal, 8);
+    columns.set_homogeneous(true);
+    columns.set_vexpand(true);
+    for list in lists.iter() {
+        columns.append(&list.widget);
+    }
+    (columns, Rc::new(lists))
+}
+
+fn set_diff(lists: &[DiffList; 3], before: &[Finding], after: &[Finding]) {
+    let d = diff(before, after);
+    lists[0].set(&d.added);
+    lists[1].set(&d.removed);
+    lists[2].set(&d.unchanged);
+}
+
+// Show `content` in `window` with margins and a Close button
+fn present(window: &Window, content: &GtkBox) {
+    let close_btn = Button::with_label(tr("Close"));
+    close_btn.set_halign(Align::End);
+    {
+        let win_clone = window.clone();
+        close_btn.connect_clicked(move |_| win_clone.close());
+    }
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.append(&close_btn);
+    window.set_child(Some(content));
+    window.present();
+}
+
+fn choose_results_file<F: Fn(&str) + 'static>(parent: &Window, on_chosen: F) {
+    let dialog = FileChooserDialog::builder()
+        .title(tr("Open Results"))
+        .action(FileChooserAction::Open)
+        .modal(true)
+        .transient_for(parent)
+        .build();
+    let filter = FileFilter::new();
+    filter.set_name(Some("JSON results (*.json)"));
+    filter.add_pattern("*.json");
+    dialog.add_filter(&filter);
+    dialog.add_buttons(&[
+        (tr("Cancel"), ResponseType::Cancel),
+        (tr("Open"), ResponseType::Accept),
+    ]);
+    dialog.connect_response(move |d, r| {
+        if r == ResponseType::Accept {
+            if let Some(path) = d.file().and_then(|f| f.path()) {
+                on_chosen(&path.to_string_lossy());
+            }
+        }
+        d.close();
+    });
+    dialog.show();
+}
+
+// Titled list of findings in one color
+struct DiffList {
+    widget: GtkBox,
+    title: &'static str,
+    heading: Label,
+    store: ListStore,
+}
+
+impl DiffList {
+    fn new(title: &'static str, color: &'static str) -> Self {
+        let store = ListStore::new(&[
+            String::static_type(),
+            String::static_type(),
+            u32::static_type(),
+            String::static_type(),
+            String::static_type(),
+        ]);
+        let tree = TreeView::with_model(&store);
+        for (i, name) in ["Severity", "File", "Line", "Id", "Message"]
+            .iter()
+            .enumerate()
+        {
+            let cell = CellRendererText::new();
+            cell.set_foreground(Some(color));
+            let column = TreeViewColumn::new();
+            column.set_title(name);
+            column.pack_start(&cell, true);
+            column.add_attribute(&cell, "text", i as i32);
+            column.set_sort_column_id(i as i32);
+            column.set_resizable(true);
+            tree.append_column(&column);
+        }
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&tree));
+        let heading = Label::new(Some(title));
+        heading.set_xalign(0.0);
+        let widget = GtkBox::new(Orientation::Vertical, 4);
+        widget.append(&heading);
+        widget.append(&scrolled);
+        DiffList {
+            widget,
+            title,
+            heading,
+            store,
+        }
+    }
+
+    fn set(&self, findings: &[Finding]) {
+        self.heading
+            .set_text(&format!("{} ({})", self.title, findings.len()));
+        self.store.clear();
+        for f in findings {
+            self.store.set(
+                &self.store.append(),
+                &[
+                    (0, &f.severity.as_str()),
+                    (1, &f.file),
+                    (2, &f.line),
+                    (3, &f.id),
+                    (4, &f.message),
+                ],
+            );
+        }
+    }
+}
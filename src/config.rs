@@ -0,0 +1,733 @@
+use crate::findings::{Severity, SeverityMap};
+use crate::i18n;
+use glib::{KeyFile, KeyFileFlags};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const GENERAL: &str = "General";
+const PROJECT_PREFIX: &str = "Project ";
+const HISTORY_PREFIX: &str = "History ";
+const FILTER_VIEW_PREFIX: &str = "View ";
+const REVIEWED: &str = "Reviewed";
+const NOTES: &str = "Notes";
+const USAGE: &str = "Usage";
+const MAX_RECENT: usize = 10;
+// Days of usage kept for the per-day list, the oldest dropped first
+const MAX_USAGE_DAYS: usize = 90;
+const MAX_HISTORY: usize = 20;
+// cppcheck's own --max-ctu-depth default
+pub const DEFAULT_MAX_CTU_DEPTH: u32 = 2;
+// Lines kept in the log view before the oldest are dropped
+pub const DEFAULT_MAX_LOG_LINES: u32 = 50_000;
+// export::Format name used for auto-saved results
+pub const DEFAULT_AUTO_SAVE_FORMAT: &str = "JSON";
+// Headless browsers sometimes exit without writing the PDF
+pub const DEFAULT_PDF_ATTEMPTS: u32 = 2;
+// --enable groups ticked at startup unless the config says otherwise
+const DEFAULT_ENABLES: &[&str] = &["warning"];
+// Groups the default enables may name
+pub const ENABLE_GROUPS: &[&str] = &[
+    "warning",
+    "style",
+    "performance",
+    "portability",
+    "information",
+    "unusedFunction",
+];
+// Groups a raw --enable expression may name besides ENABLE_GROUPS
+const EXPRESSION_ONLY_GROUPS: &[&str] = &["missingInclude", "all"];
+// Where system and toolchain headers usually live
+const DEFAULT_SYSTEM_DIRS: &[&str] = &["/usr/include", "/usr/local/include"];
+
+// How much the GUI logs about what it does itself
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    // Errors only
+    Quiet,
+    #[default]
+    Normal,
+    // Also every spawned command and its working directory
+    Debug,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 3] = [LogLevel::Quiet, LogLevel::Normal, LogLevel::Debug];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Quiet => "quiet",
+            LogLevel::Normal => "normal",
+            LogLevel::Debug => "debug",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<LogLevel> {
+        LogLevel::ALL.into_iter().find(|l| l.as_str() == s)
+    }
+}
+
+// Scan settings remembered for each project directory
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProjectSettings {
+    // --enable groups, e.g. "warning", "style"
+    pub enables: Vec<String>,
+    // Name of a findings::TEMPLATE_PRESETS entry, or "custom"
+    pub template: String,
+    pub custom_template: String,
+    pub clang: bool,
+    pub bug_hunting: bool,
+    // Pass headers as files of their own instead of only via #include
+    pub check_headers: bool,
+    // --enable=all --inconclusive in place of the enable groups
+    pub aggressive: bool,
+    // XML files passed as --rule-file
+    pub rule_files: Vec<String>,
+    // Files passed as -i, left out of the scan
+    pub excludes: Vec<String>,
+    // Directory cppcheck runs in, relative to the project; empty for the
+    // project itself
+    pub working_dir: String,
+    // --cppcheck-build-dir, relative to the project; empty for none
+    pub build_dir: String,
+    // Addons passed as --addon, e.g. "misra", "cert"
+    pub addons: Vec<String>,
+    // Library configurations passed as --library, e.g. "qt", "posix"
+    pub libraries: Vec<String>,
+    // compile_commands.json passed as --project instead of scanning the
+    // directory, relative to the project; empty for none
+    pub compile_database: String,
+    // Report paths relative to relative_base (--relative-paths), e.g. for
+    // reports viewed on another machine; an empty base lets cppcheck use
+    // the scanned paths
+    pub relative_paths: bool,
+    // Relative to the project
+    pub relative_base: String,
+    // Raw --enable value, e.g. "warning,style"; when valid the scan uses it
+    // instead of `enables`, which keeps the checkbox and threshold groups
+    pub enable_expression: String,
+}
+
+// A cppcheck invocation as it was run, newest first in `Config::history`
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoryEntry {
+    pub time: String,
+    // Program followed by its arguments
+    pub args: Vec<String>,
+}
+
+// Completed scans counted on this machine; only ever stored in the config
+// file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsageStats {
+    pub scans: u64,
+    pub findings: u64,
+    // Time the counted scans ran, in seconds
+    pub seconds: u64,
+    // (scans, findings) by local date, "YYYY-MM-DD"
+    pub days: BTreeMap<String, (u32, u32)>,
+}
+
+// Results filters saved under a name, reapplied from the views dropdown
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilterView {
+    pub name: String,
+    pub threshold: Severity,
+    // Glob the file of a shown finding must match; empty for all files
+    pub file_glob: String,
+    pub hidden_ids: Vec<String>,
+}
+
+// Persistent application state, stored as a key file in the user config dir
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub recent_projects: Vec<String>,
+    // Code of the UI language, one of i18n::locales()
+    pub language: String,
+    // Run the scan right away when a recent project is opened
+    pub auto_run_recent: bool,
+    // Keep cppcheck.xml in the project after building the HTML report
+    pub keep_xml: bool,
+    // Open sources in the default application instead of the built-in viewer
+    pub external_viewer: bool,
+    // Editor sources open in ahead of both viewers, with {file}, {line}
+    // and {column} filled in, e.g. ["code", "-g", "{file}:{line}:{column}"]
+    pub editor_command: Vec<String>,
+    // Pass the finding's column to the editor; without it jumps go to
+    // the start of the line
+    pub editor_columns: bool,
+    // Print the percentage done on the progress bar
+    pub progress_percent: bool,
+    pub log_level: LogLevel,
+    // Whole-program analysis depth, passed when it differs from the default
+    pub max_ctu_depth: u32,
+    // Older lines are trimmed from the log view; Save Log has them all
+    pub max_log_lines: u32,
+    // Titles of the results columns the user has hidden
+    pub hidden_columns: Vec<String>,
+    // Write the findings of every completed scan to a timestamped file in
+    // auto_save_dir, as export::Format auto_save_format
+    pub auto_save: bool,
+    pub auto_save_dir: String,
+    pub auto_save_format: String,
+    // Suppress findings in files under system_dirs
+    pub ignore_system_headers: bool,
+    pub system_dirs: Vec<String>,
+    // Enable groups the checkboxes start with, e.g. a team that always
+    // checks style
+    pub default_enables: Vec<String>,
+    // Times the PDF export is tried before giving up
+    pub pdf_attempts: u32,
+    // Pass scan::DEBUG_FLAG and show its output in a panel of its own
+    pub cppcheck_debug: bool,
+    // Wrapper cppcheck runs under, e.g. ["docker", "run", ...]; empty runs
+    // it directly
+    pub command_prefix: Vec<String>,
+    // "KEY=value" variables set for cppcheck
+    pub cppcheck_env: Vec<String>,
+    // Virtual memory cppcheck may use in MB; 0 for no limit
+    pub memory_limit_mb: u32,
+    // Run cppcheck at reduced CPU priority to keep the desktop responsive
+    pub low_priority: bool,
+    // Findings on lines changed within this many days are shown in bold,
+    // per git blame; 0 turns it off
+    pub recent_days: u32,
+    // Count findings per severity next to the progress bar as they arrive
+    pub live_counts: bool,
+    // Re-run the scan shortly after a scan setting changes
+    pub rescan_on_change: bool,
+    // Launch generated HTML and PDF reports in the default application
+    pub open_reports: bool,
+    // List the results errors first instead of in cppcheck's order
+    pub sort_by_severity: bool,
+    // Interpreter cppcheck runs addons with (--addon-python); empty leaves
+    // it to cppcheck
+    pub addon_python: String,
+    // Severities findings are shown with instead of cppcheck's own
+    pub severity_map: SeverityMap,
+    pub history: Vec<HistoryEntry>,
+    // In the order they were saved
+    pub filter_views: Vec<FilterView>,
+    // Finding::review_key of every finding marked as reviewed
+    pub reviewed: BTreeSet<String>,
+    // Triage notes by Finding::review_key
+    pub notes: BTreeMap<String, String>,
+    pub usage: UsageStats,
+    projects: BTreeMap<String, ProjectSettings>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            recent_projects: Vec::new(),
+            language: i18n::DEFAULT_LANGUAGE.to_string(),
+            auto_run_recent: false,
+            keep_xml: false,
+            external_viewer: false,
+            editor_command: Vec::new(),
+            editor_columns: true,
+            progress_percent: false,
+            log_level: LogLevel::default(),
+            max_ctu_depth: DEFAULT_MAX_CTU_DEPTH,
+            max_log_lines: DEFAULT_MAX_LOG_LINES,
+            hidden_columns: Vec::new(),
+            auto_save: false,
+            auto_save_dir: String::new(),
+            auto_save_format: DEFAULT_AUTO_SAVE_FORMAT.to_string(),
+            ignore_system_headers: false,
+            system_dirs: default_system_dirs(),
+            default_enables: default_enables(),
+            pdf_attempts: DEFAULT_PDF_ATTEMPTS,
+            cppcheck_debug: false,
+            command_prefix: Vec::new(),
+            cppcheck_env: Vec::new(),
+            memory_limit_mb: 0,
+            low_priority: false,
+            recent_days: 0,
+            live_counts: false,
+            rescan_on_change: false,
+            open_reports: true,
+            sort_by_severity: false,
+            addon_python: String::new(),
+            severity_map: SeverityMap::default(),
+            history: Vec::new(),
+            filter_views: Vec::new(),
+            reviewed: BTreeSet::new(),
+            notes: BTreeMap::new(),
+            usage: UsageStats::default(),
+            projects: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    // Load the config file; a missing or unreadable file yields defaults
+    pub fn load() -> Config {
+        let key_file = KeyFile::new();
+        if key_file
+            .load_from_file(config_path(), KeyFileFlags::NONE)
+            .is_err()
+        {
+            return Config::default();
+        }
+        let mut config = Config {
+            recent_projects: string_list(&key_file, GENERAL, "recent_projects"),
+            language: key_file
+                .string(GENERAL, "language")
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| i18n::DEFAULT_LANGUAGE.to_string()),
+            auto_run_recent: key_file
+                .boolean(GENERAL, "auto_run_recent")
+                .unwrap_or(false),
+            keep_xml: key_file.boolean(GENERAL, "keep_xml").unwrap_or(false),
+            external_viewer: key_file
+                .boolean(GENERAL, "external_viewer")
+                .unwrap_or(false),
+            progress_percent: key_file
+                .boolean(GENERAL, "progress_percent")
+                .unwrap_or(false),
+            log_level: key_file
+                .string(GENERAL, "log_level")
+                .ok()
+                .and_then(|l| LogLevel::parse(&l))
+                .unwrap_or_default(),
+            max_ctu_depth: key_file
+                .integer(GENERAL, "max_ctu_depth")
+                .ok()
+                .and_then(|d| u32::try_from(d).ok())
+                .unwrap_or(DEFAULT_MAX_CTU_DEPTH),
+            max_log_lines: key_file
+                .integer(GENERAL, "max_log_lines")
+                .ok()
+                .and_then(|n| u32::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_MAX_LOG_LINES),
+            hidden_columns: string_list(&key_file, GENERAL, "hidden_columns"),
+            auto_save: key_file.boolean(GENERAL, "auto_save").unwrap_or(false),
+            auto_save_dir: key_file
+                .string(GENERAL, "auto_save_dir")
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            auto_save_format: key_file
+                .string(GENERAL, "auto_save_format")
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| DEFAULT_AUTO_SAVE_FORMAT.to_string()),
+            ignore_system_headers: key_file
+                .boolean(GENERAL, "ignore_system_headers")
+                .unwrap_or(false),
+            system_dirs: if key_file.has_key(GENERAL, "system_dirs").unwrap_or(false) {
+                string_list(&key_file, GENERAL, "system_dirs")
+            } else {
+                default_system_dirs()
+            },
+            default_enables: if key_file
+                .has_key(GENERAL, "default_enables")
+                .unwrap_or(false)
+            {
+                string_list(&key_file, GENERAL, "default_enables")
+            } else {
+                default_enables()
+            },
+            pdf_attempts: key_file
+                .integer(GENERAL, "pdf_attempts")
+                .ok()
+                .and_then(|n| u32::try_from(n).ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(DEFAULT_PDF_ATTEMPTS),
+            cppcheck_debug: key_file.boolean(GENERAL, "cppcheck_debug").unwrap_or(false),
+            command_prefix: string_list(&key_file, GENERAL, "command_prefix"),
+            editor_command: string_list(&key_file, GENERAL, "editor_command"),
+            editor_columns: key_file.boolean(GENERAL, "editor_columns").unwrap_or(true),
+            cppcheck_env: string_list(&key_file, GENERAL, "cppcheck_env"),
+            live_counts: key_file.boolean(GENERAL, "live_counts").unwrap_or(false),
+            memory_limit_mb: key_file
+                .integer(GENERAL, "memory_limit_mb")
+                .ok()
+                .and_then(|n| u32::try_from(n).ok())
+                .unwrap_or(0),
+            low_priority: key_file.boolean(GENERAL, "low_priority").unwrap_or(false),
+            recent_days: key_file
+                .integer(GENERAL, "recent_days")
+                .ok()
+                .and_then(|n| u32::try_from(n).ok())
+                .unwrap_or(0),
+            rescan_on_change: key_file
+                .boolean(GENERAL, "rescan_on_change")
+                .unwrap_or(false),
+            open_reports: key_file.boolean(GENERAL, "open_reports").unwrap_or(true),
+            sort_by_severity: key_file
+                .boolean(GENERAL, "sort_by_severity")
+                .unwrap_or(false),
+            addon_python: key_file
+                .string(GENERAL, "addon_python")
+                .map(|s| s.to_string())
+                .unwrap_or_default(),
+            // A broken hand edit drops the whole map rather than half of it
+            severity_map: SeverityMap::from_rules(
+                string_list(&key_file, GENERAL, "severity_map")
+                    .iter()
+                    .map(String::as_str),
+            )
+            .unwrap_or_default(),
+            history: Vec::new(),
+            filter_views: Vec::new(),
+            reviewed: string_list(&key_file, REVIEWED, "findings")
+                .into_iter()
+                .collect(),
+            // Two lists of the same length: the keys, then their notes
+            notes: string_list(&key_file, NOTES, "findings")
+                .into_iter()
+                .zip(string_list(&key_file, NOTES, "notes"))
+                .collect(),
+            usage: UsageStats {
+                scans: key_file.uint64(USAGE, "scans").unwrap_or(0),
+                findings: key_file.uint64(USAGE, "findings").unwrap_or(0),
+                seconds: key_file.uint64(USAGE, "seconds").unwrap_or(0),
+                // Parallel lists like the notes
+                days: string_list(&key_file, USAGE, "days")
+                    .into_iter()
+                    .zip(
+                        integer_list(&key_file, USAGE, "day_scans")
+                            .into_iter()
+                            .zip(integer_list(&key_file, USAGE, "day_findings")),
+                    )
+                    .collect(),
+            },
+            projects: BTreeMap::new(),
+        };
+        let mut history = BTreeMap::new();
+        let mut filter_views = BTreeMap::new();
+        for group in key_file.groups().iter() {
+            if let Some(index) = group.as_str().strip_prefix(FILTER_VIEW_PREFIX) {
+                let group = group.as_str();
+                let view = FilterView {
+                    name: key_file
+                        .string(group, "name")
+                        .map(|s| s.to_string())
+                        .unwrap_or_default(),
+                    threshold: key_file
+                        .string(group, "threshold")
+                        .ok()
+                        .and_then(|s| Severity::parse(&s))
+                        .unwrap_or(Severity::Information),
+                    file_glob: key_file
+                        .string(group, "file_glob")
+                        .map(|s| s.to_string())
+                        .unwrap_or_default(),
+                    hidden_ids: string_list(&key_file, group, "hidden_ids"),
+                };
+                if let (Ok(index), false) = (index.parse::<usize>(), view.name.is_empty()) {
+                    filter_views.insert(index, view);
+                }
+                continue;
+            }
+            if let Some(index) = group.as_str().strip_prefix(HISTORY_PREFIX) {
+                let entry = HistoryEntry {
+                    time: key_file
+                        .string(group.as_str(), "time")
+                        .map(|s| s.to_string())
+                        .unwrap_or_default(),
+                    args: string_list(&key_file, group.as_str(), "args"),
+                };
+                if let Ok(index) = index.parse::<usize>() {
+                    history.insert(index, entry);
+                }
+                continue;
+            }
+            let Some(path) = group.as_str().strip_prefix(PROJECT_PREFIX) else {
+                continue;
+            };
+            let group = group.as_str();
+            let settings = ProjectSettings {
+                enables: string_list(&key_file, group, "enables"),
+                template: key_file
+                    .string(group, "template")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                custom_template: key_file
+                    .string(group, "custom_template")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                clang: key_file.boolean(group, "clang").unwrap_or(false),
+                bug_hunting: key_file.boolean(group, "bug_hunting").unwrap_or(false),
+                check_headers: key_file.boolean(group, "check_headers").unwrap_or(false),
+                aggressive: key_file.boolean(group, "aggressive").unwrap_or(false),
+                rule_files: string_list(&key_file, group, "rule_files"),
+                excludes: string_list(&key_file, group, "excludes"),
+                working_dir: key_file
+                    .string(group, "working_dir")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                build_dir: key_file
+                    .string(group, "build_dir")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                addons: string_list(&key_file, group, "addons"),
+                libraries: string_list(&key_file, group, "libraries"),
+                compile_database: key_file
+                    .string(group, "compile_database")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                relative_paths: key_file.boolean(group, "relative_paths").unwrap_or(false),
+                relative_base: key_file
+                    .string(group, "relative_base")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+                enable_expression: key_file
+                    .string(group, "enable_expression")
+                    .map(|s| s.to_string())
+                    .unwrap_or_default(),
+            };
+            config.projects.insert(path.to_string(), settings);
+        }
+        config.history = history.into_values().collect();
+        config.filter_views = filter_views.into_values().collect();
+        config
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let key_file = KeyFile::new();
+        set_string_list(&key_file, GENERAL, "recent_projects", &self.recent_projects);
+        key_file.set_string(GENERAL, "language", &self.language);
+        key_file.set_boolean(GENERAL, "auto_run_recent", self.auto_run_recent);
+        key_file.set_boolean(GENERAL, "keep_xml", self.keep_xml);
+        key_file.set_boolean(GENERAL, "external_viewer", self.external_viewer);
+        key_file.set_boolean(GENERAL, "progress_percent", self.progress_percent);
+        key_file.set_string(GENERAL, "log_level", self.log_level.as_str());
+        key_file.set_integer(GENERAL, "max_ctu_depth", self.max_ctu_depth as i32);
+        key_file.set_integer(GENERAL, "max_log_lines", self.max_log_lines as i32);
+        set_string_list(&key_file, GENERAL, "hidden_columns", &self.hidden_columns);
+        key_file.set_boolean(GENERAL, "auto_save", self.auto_save);
+        key_file.set_string(GENERAL, "auto_save_dir", &self.auto_save_dir);
+        key_file.set_string(GENERAL, "auto_save_format", &self.auto_save_format);
+        key_file.set_boolean(GENERAL, "ignore_system_headers", self.ignore_system_headers);
+        set_string_list(&key_file, GENERAL, "system_dirs", &self.system_dirs);
+        set_string_list(&key_file, GENERAL, "default_enables", &self.default_enables);
+        key_file.set_integer(GENERAL, "pdf_attempts", self.pdf_attempts as i32);
+        key_file.set_boolean(GENERAL, "cppcheck_debug", self.cppcheck_debug);
+        set_string_list(&key_file, GENERAL, "command_prefix", &self.command_prefix);
+        set_string_list(&key_file, GENERAL, "editor_command", &self.editor_command);
+        key_file.set_boolean(GENERAL, "editor_columns", self.editor_columns);
+        set_string_list(&key_file, GENERAL, "cppcheck_env", &self.cppcheck_env);
+        key_file.set_boolean(GENERAL, "live_counts", self.live_counts);
+        key_file.set_integer(GENERAL, "memory_limit_mb", self.memory_limit_mb as i32);
+        key_file.set_boolean(GENERAL, "low_priority", self.low_priority);
+        key_file.set_integer(GENERAL, "recent_days", self.recent_days as i32);
+        key_file.set_boolean(GENERAL, "rescan_on_change", self.rescan_on_change);
+        key_file.set_boolean(GENERAL, "open_reports", self.open_reports);
+        key_file.set_boolean(GENERAL, "sort_by_severity", self.sort_by_severity);
+        key_file.set_string(GENERAL, "addon_python", &self.addon_python);
+        set_string_list(
+            &key_file,
+            GENERAL,
+            "severity_map",
+            &self.severity_map.rules(),
+        );
+        let reviewed: Vec<String> = self.reviewed.iter().cloned().collect();
+        set_string_list(&key_file, REVIEWED, "findings", &reviewed);
+        let (keys, notes): (Vec<String>, Vec<String>) = self
+            .notes
+            .iter()
+            .map(|(k, n)| (k.clone(), n.clone()))
+            .unzip();
+        set_string_list(&key_file, NOTES, "findings", &keys);
+        set_string_list(&key_file, NOTES, "notes", &notes);
+        key_file.set_uint64(USAGE, "scans", self.usage.scans);
+        key_file.set_uint64(USAGE, "findings", self.usage.findings);
+        key_file.set_uint64(USAGE, "seconds", self.usage.seconds);
+        let days: Vec<String> = self.usage.days.keys().cloned().collect();
+        set_string_list(&key_file, USAGE, "days", &days);
+        // The glib bindings have no set_integer_list, so the per-day counts
+        // are written as a string list; key file values are plain text and
+        // integer_list reads them back as numbers, clamped to its i32
+        let (day_scans, day_findings): (Vec<String>, Vec<String>) = self
+            .usage
+            .days
+            .values()
+            .map(|&(scans, findings)| (scans.min(i32::MAX as u32), findings.min(i32::MAX as u32)))
+            .map(|(scans, findings)| (scans.to_string(), findings.to_string()))
+            .unzip();
+        set_string_list(&key_file, USAGE, "day_scans", &day_scans);
+        set_string_list(&key_file, USAGE, "day_findings", &day_findings);
+        for (path, settings) in &self.projects {
+            let group = format!("{}{}", PROJECT_PREFIX, path);
+            set_string_list(&key_file, &group, "enables", &settings.enables);
+            key_file.set_string(&group, "template", &settings.template);
+            key_file.set_string(&group, "custom_template", &settings.custom_template);
+            key_file.set_boolean(&group, "clang", settings.clang);
+            key_file.set_boolean(&group, "bug_hunting", settings.bug_hunting);
+            key_file.set_boolean(&group, "check_headers", settings.check_headers);
+            key_file.set_boolean(&group, "aggressive", settings.aggressive);
+            set_string_list(&key_file, &group, "rule_files", &settings.rule_files);
+            set_string_list(&key_file, &group, "excludes", &settings.excludes);
+            key_file.set_string(&group, "working_dir", &settings.working_dir);
+            key_file.set_string(&group, "build_dir", &settings.build_dir);
+            set_string_list(&key_file, &group, "addons", &settings.addons);
+            set_string_list(&key_file, &group, "libraries", &settings.libraries);
+            key_file.set_string(&group, "compile_database", &settings.compile_database);
+            key_file.set_boolean(&group, "relative_paths", settings.relative_paths);
+            key_file.set_string(&group, "relative_base", &settings.relative_base);
+            key_file.set_string(&group, "enable_expression", &settings.enable_expression);
+        }
+        for (i, entry) in self.history.iter().enumerate() {
+            let group = format!("{}{}", HISTORY_PREFIX, i);
+            key_file.set_string(&group, "time", &entry.time);
+            set_string_list(&key_file, &group, "args", &entry.args);
+        }
+        for (i, view) in self.filter_views.iter().enumerate() {
+            let group = format!("{}{}", FILTER_VIEW_PREFIX, i);
+            key_file.set_string(&group, "name", &view.name);
+            key_file.set_string(&group, "threshold", view.threshold.as_str());
+            key_file.set_string(&group, "file_glob", &view.file_glob);
+            set_string_list(&key_file, &group, "hidden_ids", &view.hidden_ids);
+        }
+        let path = config_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        key_file
+            .save_to_file(&path)
+            .map_err(|e| io::Error::other(e.to_string()))
+    }
+
+    pub fn project(&self, path: &str) -> Option<&ProjectSettings> {
+        self.projects.get(path)
+    }
+
+    pub fn set_project(&mut self, path: &str, settings: ProjectSettings) {
+        self.projects.insert(path.to_string(), settings);
+    }
+
+    // Move `path` to the front of the recent list
+    pub fn add_recent(&mut self, path: &str) {
+        self.recent_projects.retain(|p| p != path);
+        self.recent_projects.insert(0, path.to_string());
+        self.recent_projects.truncate(MAX_RECENT);
+    }
+
+    // Directories whose findings are suppressed; empty when the option is off
+    pub fn suppressed_dirs(&self) -> Vec<String> {
+        if self.ignore_system_headers {
+            self.system_dirs.clone()
+        } else {
+            Vec::new()
+        }
+    }
+
+    // Count a completed scan that reported `findings` in `seconds`
+    pub fn record_scan(&mut self, findings: usize, seconds: u64) {
+        let usage = &mut self.usage;
+        usage.scans += 1;
+        usage.findings += findings as u64;
+        usage.seconds += seconds;
+        let today = glib::DateTime::now_local()
+            .and_then(|t| t.format("%Y-%m-%d"))
+            .map(|t| t.to_string())
+            .unwrap_or_default();
+        let day = usage.days.entry(today).or_default();
+        day.0 = day.0.saturating_add(1);
+        day.1 = day
+            .1
+            .saturating_add(findings.try_into().unwrap_or(u32::MAX));
+        while usage.days.len() > MAX_USAGE_DAYS {
+            usage.days.pop_first();
+        }
+    }
+
+    // Record a command at the front of the history, stamped with local time
+    pub fn add_history(&mut self, args: &[String]) {
+        let time = glib::DateTime::now_local()
+            .and_then(|t| t.format("%Y-%m-%d %H:%M:%S"))
+            .map(|t| t.to_string())
+            .unwrap_or_default();
+        self.history.insert(
+            0,
+            HistoryEntry {
+                time,
+                args: args.to_vec(),
+            },
+        );
+        self.history.truncate(MAX_HISTORY);
+    }
+}
+
+fn default_system_dirs() -> Vec<String> {
+    DEFAULT_SYSTEM_DIRS.iter().map(|d| d.to_string()).collect()
+}
+
+fn default_enables() -> Vec<String> {
+    DEFAULT_ENABLES.iter().map(|g| g.to_string()).collect()
+}
+
+// Comma-separated enable groups, each one of ENABLE_GROUPS
+pub fn parse_enables(text: &str) -> Result<Vec<String>, String> {
+    parse_groups(text, ENABLE_GROUPS)
+}
+
+// A raw --enable value: ENABLE_GROUPS plus those only cppcheck itself
+// knows what to do with
+pub fn parse_enable_expression(text: &str) -> Result<Vec<String>, String> {
+    let groups: Vec<&str> = ENABLE_GROUPS
+        .iter()
+        .chain(EXPRESSION_ONLY_GROUPS)
+        .copied()
+        .collect();
+    parse_groups(text, &groups)
+}
+
+fn parse_groups(text: &str, groups: &[&str]) -> Result<Vec<String>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .map(|g| match groups.contains(&g) {
+            true => Ok(g.to_string()),
+            false => Err(format!(
+                "unknown group \"{}\", expected one of {}",
+                g,
+                groups.join(", ")
+            )),
+        })
+        .collect()
+}
+
+fn config_path() -> PathBuf {
+    glib::user_config_dir()
+        .join("cppcheck_gui")
+        .join("config.ini")
+}
+
+fn string_list(key_file: &KeyFile, group: &str, key: &str) -> Vec<String> {
+    key_file
+        .string_list(group, key)
+        .map(|list| list.iter().map(|s| s.to_string()).collect())
+        .unwrap_or_default()
+}
+
+// Negative values, which the config never holds, read as 0
+fn integer_list(key_file: &KeyFile, group: &str, key: &str) -> Vec<u32> {
+    key_file
+        .integer_list(group, key)
+        .map(|list| list.iter().map(|&n| n.max(0) as u32).collect())
+        .unwrap_or_default()
+}
+
+// glib-rs has no set_string_list binding, so write the value in GKeyFile's
+// list syntax: ';'-terminated elements with separators escaped
+fn set_string_list(key_file: &KeyFile, group: &str, key: &str, list: &[String]) {
+    let mut value = String::new();
+    for item in list {
+        for c in item.chars() {
+            match c {
+                '\\' => value.push_str("\\\\"),
+                ';' => value.push_str("\\;"),
+                '\n' => value.push_str("\\n"),
+                '\t' => value.push_str("\\t"),
+                '\r' => value.push_str("\\r"),
+                c => value.push(c),
+            }
+        }
+        value.push(';');
+    }
+    key_file.set_value(group, key, &value);
+}
@@ -0,0 +1,128 @@
+use crate::config::ProjectSettings;
+use crate::suppress::Suppression;
+use std::path::Path;
+
+// Project file (.cppcheck) of the official Cppcheck GUI for the scan
+// settings that have a counterpart there. Paths are written absolute so
+// the file works wherever it is saved
+pub fn to_xml(
+    project: &str,
+    settings: &ProjectSettings,
+    max_ctu_depth: u32,
+    suppressions: &[Suppression],
+) -> String {
+    let absolute = |path: &str| Path::new(project).join(path).to_string_lossy().to_string();
+    let name = Path::new(project)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<project version=\"1\">\n");
+    xml.push_str(&format!(
+        "    <project-name>{}</project-name>\n",
+        escape(&name)
+    ));
+    if !settings.build_dir.is_empty() {
+        xml.push_str(&format!(
+            "    <builddir>{}</builddir>\n",
+            escape(&absolute(&settings.build_dir))
+        ));
+    }
+    if settings.clang {
+        xml.push_str("    <parser>clang</parser>\n");
+    }
+    if settings.compile_database.is_empty() {
+        xml.push_str(&format!(
+            "    <paths>\n        <dir name=\"{}\"/>\n    </paths>\n",
+            escape(project)
+        ));
+    } else {
+        xml.push_str(&format!(
+            "    <importproject>{}</importproject>\n",
+            escape(&absolute(&settings.compile_database))
+        ));
+    }
+    if !settings.libraries.is_empty() {
+        xml.push_str("    <libraries>\n");
+        for library in &settings.libraries {
+            xml.push_str(&format!("        <library>{}</library>\n", escape(library)));
+        }
+        xml.push_str("    </libraries>\n");
+    }
+    if !settings.excludes.is_empty() {
+        xml.push_str("    <exclude>\n");
+        for file in &settings.excludes {
+            xml.push_str(&format!(
+                "        <path name=\"{}\"/>\n",
+                escape(&absolute(file))
+            ));
+        }
+        xml.push_str("    </exclude>\n");
+    }
+    // No <check-headers>: cppcheck's element limits how much of the headers is
+    // checked, while check_headers here adds the headers as scanned files
+    xml.push_str(&format!(
+        "    <max-ctu-depth>{}</max-ctu-depth>\n",
+        max_ctu_depth
+    ));
+    if !settings.addons.is_empty() {
+        xml.push_str("    <addons>\n");
+        for addon in &settings.addons {
+            xml.push_str(&format!("        <addon>{}</addon>\n", escape(addon)));
+        }
+        xml.push_str("    </addons>\n");
+    }
+    if !suppressions.is_empty() {
+        xml.push_str("    <suppressions>\n");
+        for s in suppressions {
+            let mut attributes = String::new();
+            if let Some(ref file) = s.file {
+                attributes.push_str(&format!(" fileName=\"{}\"", escape(file)));
+                if let Some(line) = s.line {
+                    attributes.push_str(&format!(" lineNumber=\"{}\"", line));
+                }
+            }
+            xml.push_str(&format!(
+                "        <suppression{}>{}</suppression>\n",
+                attributes,
+                escape(&s.id)
+            ));
+        }
+        xml.push_str("    </suppressions>\n");
+    }
+    xml.push_str("</project>\n");
+    xml
+}
+
+// Settings without a project file element, for telling the user
+pub fn unsupported(settings: &ProjectSettings) -> Vec<&'static str> {
+    let mut skipped = Vec::new();
+    if !settings.enables.is_empty() || !settings.enable_expression.is_empty() || settings.aggressive
+    {
+        skipped.push("enabled checks");
+    }
+    if !settings.rule_files.is_empty() {
+        skipped.push("rule files");
+    }
+    if !settings.working_dir.is_empty() {
+        skipped.push("working directory");
+    }
+    if settings.relative_paths {
+        skipped.push("relative paths");
+    }
+    skipped
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
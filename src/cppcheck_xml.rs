@@ -0,0 +1,238 @@
+use crate::findings::{Finding, Severity};
+
+// Findings of a cppcheck `--xml --xml-version=2` report. The first
+// <location> of an <error> is where it is reported; errors without one
+// (e.g. missingIncludeSystem) get "nofile" like the text output
+pub fn parse_results(text: &str) -> Result<Vec<Finding>, String> {
+    let mut findings = Vec::new();
+    let mut current: Option<Finding> = None;
+    let mut seen_results = false;
+    for tag in Tags::new(text) {
+        let tag = tag?;
+        match (tag.name.as_str(), tag.closing) {
+            ("results", false) => {
+                if tag.attr("version").is_some_and(|v| v != "2") {
+                    return Err("only XML version 2 is supported".to_string());
+                }
+                seen_results = true;
+            }
+            ("error", false) => {
+                let Some(finding) = error_finding(&tag) else {
+                    continue;
+                };
+                if tag.self_closing {
+                    findings.push(with_file(finding));
+                } else {
+                    current = Some(finding);
+                }
+            }
+            ("location", false) => {
+                if let Some(ref mut f) = current {
+                    if f.file.is_empty() {
+                        f.file = tag.attr("file").unwrap_or_default().to_string();
+                        f.line = tag.attr("line").and_then(|l| l.parse().ok()).unwrap_or(0);
+                        f.column = tag.attr("column").and_then(|c| c.parse().ok()).unwrap_or(0);
+                    }
+                }
+            }
+            ("error", true) => findings.extend(current.take().map(with_file)),
+            _ => {}
+        }
+    }
+    if !seen_results {
+        return Err("not a cppcheck XML report".to_string());
+    }
+    Ok(findings)
+}
+
+// Debug and other severities the results list doesn't know are skipped
+fn error_finding(tag: &Tag) -> Option<Finding> {
+    let severity = Severity::parse(tag.attr("severity")?)?;
+    let message = tag.attr("msg").unwrap_or_default().to_string();
+    Some(Finding {
+        file: String::new(),
+        line: 0,
+        column: 0,
+        severity,
+        id: tag.attr("id").unwrap_or_default().to_string(),
+        verbose: tag
+            .attr("verbose")
+            .filter(|v| *v != message)
+            .map(String::from),
+        message,
+        cwe: tag
+            .attr("cwe")
+            .and_then(|c| c.parse().ok())
+            .filter(|c| *c > 0),
+        inconclusive: tag.attr("inconclusive") == Some("true"),
+        note: None,
+    })
+}
+
+fn with_file(mut finding: Finding) -> Finding {
+    if finding.file.is_empty() {
+        finding.file = "nofile".to_string();
+    }
+    finding
+}
+
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    closing: bool,
+    self_closing: bool,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+// Markup that carries no elements, as (opening, closing) delimiters
+const SKIPPED: &[(&str, &str)] = &[
+    ("<!--", "-->"),
+    ("<![CDATA[", "]]>"),
+    ("<?", "?>"),
+    ("<!", ">"),
+];
+
+// Element tags of an XML document; text, comments, declarations and
+// processing instructions are skipped
+struct Tags<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tags<'a> {
+    fn new(text: &'a str) -> Self {
+        Tags { rest: text }
+    }
+}
+
+impl Iterator for Tags<'_> {
+    type Item = Result<Tag, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.rest.find('<')?;
+            self.rest = &self.rest[start..];
+            let rest = self.rest;
+            if let Some((open, close)) = SKIPPED.iter().find(|(open, _)| rest.starts_with(open)) {
+                let Some(end) = rest.find(close) else {
+                    self.rest = "";
+                    return Some(Err(format!("unterminated {}", open)));
+                };
+                self.rest = &rest[end + close.len()..];
+                continue;
+            }
+            let Some(end) = tag_end(self.rest) else {
+                self.rest = "";
+                return Some(Err("unterminated tag".to_string()));
+            };
+            let body = &self.rest[1..end];
+            self.rest = &self.rest[end + 1..];
+            return Some(parse_tag(body));
+        }
+    }
+}
+
+// Index of the '>' closing the tag at the start of `s`, skipping quoted
+// attribute values
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_tag(body: &str) -> Result<Tag, String> {
+    let (closing, body) = match body.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let (self_closing, body) = match body.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, body),
+    };
+    let body = body.trim();
+    let name_end = body.find(char::is_whitespace).unwrap_or(body.len());
+    let name = body[..name_end].to_string();
+    if name.is_empty() {
+        return Err("tag without a name".to_string());
+    }
+    let mut attrs = Vec::new();
+    let mut rest = body[name_end..].trim_start();
+    while !rest.is_empty() {
+        let Some(eq) = rest.find('=') else {
+            return Err(format!("malformed attribute in <{}>", name));
+        };
+        let key = rest[..eq].trim().to_string();
+        let value_part = rest[eq + 1..].trim_start();
+        let Some(q) = value_part
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+        else {
+            return Err(format!("unquoted attribute {} in <{}>", key, name));
+        };
+        let Some(close) = value_part[1..].find(q) else {
+            return Err(format!("unterminated attribute {} in <{}>", key, name));
+        };
+        attrs.push((key, unescape(&value_part[1..1 + close])));
+        rest = value_part[close + 2..].trim_start();
+    }
+    Ok(Tag {
+        name,
+        attrs,
+        closing,
+        self_closing,
+    })
+}
+
+// Resolve the predefined entities and numeric character references
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,87 @@
+use crate::i18n::tr;
+use gtk4::prelude::*;
+use gtk4::{Box as GtkBox, Button, Label, Orientation};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Files left out of scans with `-i`, added from the results list
+#[derive(Clone)]
+pub struct ExcludedFiles {
+    pub widget: GtkBox,
+    files: Rc<RefCell<Vec<String>>>,
+    summary: Label,
+    clear_btn: Button,
+    cleared_handler: Rc<RefCell<Option<ClearedHandler>>>,
+}
+
+type ClearedHandler = Box<dyn Fn()>;
+
+impl ExcludedFiles {
+    pub fn new() -> Self {
+        let summary = Label::new(None);
+        summary.set_hexpand(true);
+        summary.set_xalign(0.0);
+        let clear_btn = Button::with_label(tr("Clear"));
+        let widget = GtkBox::new(Orientation::Horizontal, 4);
+        widget.append(&Label::new(Some(tr("Excluded files:"))));
+        widget.append(&summary);
+        widget.append(&clear_btn);
+
+        let excluded = ExcludedFiles {
+            widget,
+            files: Rc::new(RefCell::new(Vec::new())),
+            summary,
+            clear_btn,
+            cleared_handler: Rc::new(RefCell::new(None)),
+        };
+        {
+            let excluded_clone = excluded.clone();
+            excluded.clear_btn.connect_clicked(move |_| {
+                excluded_clone.set_files(&[]);
+                if let Some(ref handler) = *excluded_clone.cleared_handler.borrow() {
+                    handler();
+                }
+            });
+        }
+        excluded.update_summary();
+        excluded
+    }
+
+    // Called after Clear has emptied the list
+    pub fn connect_cleared<F: Fn() + 'static>(&self, f: F) {
+        *self.cleared_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    pub fn files(&self) -> Vec<String> {
+        self.files.borrow().clone()
+    }
+
+    pub fn set_files(&self, files: &[String]) {
+        *self.files.borrow_mut() = files.to_vec();
+        self.update_summary();
+    }
+
+    // False when `file` was already excluded
+    pub fn add(&self, file: &str) -> bool {
+        {
+            let mut files = self.files.borrow_mut();
+            if files.iter().any(|f| f == file) {
+                return false;
+            }
+            files.push(file.to_string());
+        }
+        self.update_summary();
+        true
+    }
+
+    fn update_summary(&self) {
+        let files = self.files.borrow();
+        self.summary.set_text(&match files.len() {
+            0 => "none".to_string(),
+            1 => files[0].clone(),
+            n => format!("{} files", n),
+        });
+        self.summary.set_tooltip_text(Some(&files.join("\n")));
+        self.clear_btn.set_sensitive(!files.is_empty());
+    }
+}
@@ -0,0 +1,391 @@
+use crate::findings::{Finding, Severity};
+use crate::json;
+use std::collections::HashMap;
+use std::path::Path;
+
+// What anonymize writes for the project directory and for directories
+// outside it
+pub const PROJECT_PLACEHOLDER: &str = "PROJECT/";
+pub const EXTERNAL_PLACEHOLDER: &str = "EXTERNAL/";
+
+// Rows of the top files and top ids tables of the summary
+const SUMMARY_TOP: usize = 10;
+
+// File formats the results list can be exported to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+    Sarif,
+    Html,
+    Summary,
+}
+
+impl Format {
+    pub const ALL: [Format; 5] = [
+        Format::Json,
+        Format::Csv,
+        Format::Sarif,
+        Format::Html,
+        Format::Summary,
+    ];
+
+    pub fn parse(name: &str) -> Option<Format> {
+        Format::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Csv => "CSV",
+            Format::Sarif => "SARIF",
+            Format::Html => "HTML",
+            Format::Summary => "Summary",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Csv => "csv",
+            Format::Sarif => "sarif",
+            Format::Html => "html",
+            Format::Summary => "md",
+        }
+    }
+
+    pub fn render(&self, findings: &[Finding]) -> String {
+        match self {
+            Format::Json => to_json(findings),
+            Format::Csv => to_csv(findings),
+            Format::Sarif => to_sarif(findings),
+            Format::Html => to_html(findings),
+            Format::Summary => to_summary(findings),
+        }
+    }
+}
+
+// `findings` without the local directory layout: the project directory
+// `root` becomes PROJECT_PLACEHOLDER in paths and messages, and other
+// absolute paths keep only their file name. Without a root every absolute
+// path is reduced to its file name. Relative paths are left alone
+pub fn anonymize(findings: &[Finding], root: Option<&str>) -> Vec<Finding> {
+    let hide = |text: &str| match root {
+        Some(root) => {
+            let root = root.trim_end_matches(['/', '\\']);
+            [format!("{}/", root), format!("{}\\", root)]
+                .iter()
+                .fold(text.to_string(), |text, prefix| {
+                    text.replace(prefix.as_str(), PROJECT_PLACEHOLDER)
+                })
+        }
+        None => text.to_string(),
+    };
+    findings
+        .iter()
+        .map(|f| {
+            let mut f = f.clone();
+            f.file = hide(&f.file);
+            if Path::new(&f.file).is_absolute() {
+                let name = Path::new(&f.file)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                f.file = format!("{}{}", EXTERNAL_PLACEHOLDER, name);
+            }
+            f.message = hide(&f.message);
+            f.verbose = f.verbose.as_deref().map(hide);
+            f
+        })
+        .collect()
+}
+
+// Array of objects with the Finding fields
+pub fn to_json(findings: &[Finding]) -> String {
+    let items: Vec<String> = findings
+        .iter()
+        .map(|f| {
+            let mut details = String::new();
+            if let Some(ref verbose) = f.verbose {
+                details.push_str(&format!(", \"verbose\": {}", json::quote(verbose)));
+            }
+            if let Some(cwe) = f.cwe {
+                details.push_str(&format!(", \"cwe\": {}", cwe));
+            }
+            if f.inconclusive {
+                details.push_str(", \"inconclusive\": true");
+            }
+            if let Some(ref note) = f.note {
+                details.push_str(&format!(", \"note\": {}", json::quote(note)));
+            }
+            format!(
+                "  {{\"file\": {}, \"line\": {}, \"column\": {}, \"severity\": {}, \
+                 \"id\": {}, \"message\": {}{}}}",
+                json::quote(&f.file),
+                f.line,
+                f.column,
+                json::quote(f.severity.as_str()),
+                json::quote(&f.id),
+                json::quote(&f.message),
+                details
+            )
+        })
+        .collect();
+    if items.is_empty() {
+        return "[]\n".to_string();
+    }
+    format!("[\n{}\n]\n", items.join(",\n"))
+}
+
+// Findings from a file written by `to_json`
+pub fn from_json(text: &str) -> Result<Vec<Finding>, String> {
+    let value = json::parse(text)?;
+    let items = value
+        .as_array()
+        .ok_or_else(|| "expected an array of findings".to_string())?;
+    items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let field = |key: &str| {
+                item.get(key)
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+                    .ok_or_else(|| format!("finding {}: missing \"{}\"", i + 1, key))
+            };
+            let severity = field("severity")?;
+            Ok(Finding {
+                file: field("file")?,
+                line: item.get("line").and_then(|v| v.as_u32()).unwrap_or(0),
+                column: item.get("column").and_then(|v| v.as_u32()).unwrap_or(0),
+                severity: Severity::parse(&severity)
+                    .ok_or_else(|| format!("finding {}: unknown severity {}", i + 1, severity))?,
+                id: field("id")?,
+                message: field("message")?,
+                verbose: item
+                    .get("verbose")
+                    .and_then(|v| v.as_str())
+                    .map(String::from),
+                cwe: item.get("cwe").and_then(|v| v.as_u32()),
+                inconclusive: item
+                    .get("inconclusive")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                note: item.get("note").and_then(|v| v.as_str()).map(String::from),
+            })
+        })
+        .collect()
+}
+
+// RFC 4180 CSV with a header row
+pub fn to_csv(findings: &[Finding]) -> String {
+    let mut out = String::from("severity,file,line,column,id,message,note\r\n");
+    for f in findings {
+        let fields = [
+            f.severity.as_str().to_string(),
+            f.file.clone(),
+            f.line.to_string(),
+            f.column.to_string(),
+            f.id.clone(),
+            f.message.clone(),
+            f.note.clone().unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|s| csv_field(s)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+// SARIF 2.1.0 log with a single cppcheck run
+pub fn to_sarif(findings: &[Finding]) -> String {
+    let results: Vec<String> = findings
+        .iter()
+        .map(|f| {
+            // SARIF regions are 1-based; cppcheck uses 0 for "unknown"
+            let mut region = Vec::new();
+            if f.line > 0 {
+                region.push(format!("\"startLine\": {}", f.line));
+            }
+            if f.column > 0 {
+                region.push(format!("\"startColumn\": {}", f.column));
+            }
+            let region = if region.is_empty() {
+                String::new()
+            } else {
+                format!(", \"region\": {{{}}}", region.join(", "))
+            };
+            // SARIF has no note field; property bags take anything
+            let properties = match f.note {
+                Some(ref note) => format!(", \"properties\": {{\"note\": {}}}", json::quote(note)),
+                None => String::new(),
+            };
+            format!(
+                "        {{\"ruleId\": {}, \"level\": {}, \"message\": {{\"text\": {}}}, \
+                 \"locations\": [{{\"physicalLocation\": {{\"artifactLocation\": \
+                 {{\"uri\": {}}}{}}}}}]{}}}",
+                json::quote(&f.id),
+                json::quote(sarif_level(f.severity)),
+                json::quote(&f.message),
+                json::quote(&f.file),
+                region,
+                properties
+            )
+        })
+        .collect();
+    format!(
+        "{{\n  \"version\": \"2.1.0\",\n  \
+         \"$schema\": \"https://json.schemastore.org/sarif-2.1.0.json\",\n  \
+         \"runs\": [\n    {{\n      \
+         \"tool\": {{\"driver\": {{\"name\": \"cppcheck\", \
+         \"informationUri\": \"https://cppcheck.sourceforge.io\"}}}},\n      \
+         \"results\": [{}{}{}]\n    }}\n  ]\n}}\n",
+        if results.is_empty() { "" } else { "\n" },
+        results.join(",\n"),
+        if results.is_empty() { "" } else { "\n      " }
+    )
+}
+
+// Standalone page with a summary and a table of the findings, for sharing
+// where cppcheck-htmlreport is not installed; needs no other files
+pub fn to_html(findings: &[Finding]) -> String {
+    let mut summary = String::new();
+    for severity in Severity::ALL {
+        let count = findings.iter().filter(|f| f.severity == severity).count();
+        if count > 0 {
+            summary.push_str(&format!(
+                "<span class=\"count\" style=\"color: {}\">{}: {}</span>\n",
+                severity.color(),
+                severity,
+                count
+            ));
+        }
+    }
+    // A notes column only when there is something to show in it
+    let has_notes = findings.iter().any(|f| f.note.is_some());
+    let mut rows = String::new();
+    for f in findings {
+        let note = if has_notes {
+            format!("<td>{}</td>", html_escape(f.note.as_deref().unwrap_or("")))
+        } else {
+            String::new()
+        };
+        let mut severity = f.severity.to_string();
+        if f.inconclusive {
+            severity.push_str(" (inconclusive)");
+        }
+        let id = match f.cwe {
+            Some(cwe) => format!("{} (CWE-{})", html_escape(&f.id), cwe),
+            None => html_escape(&f.id),
+        };
+        rows.push_str(&format!(
+            "<tr><td style=\"color: {}\">{}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td><td title=\"{}\">{}</td>{}</tr>\n",
+            f.severity.color(),
+            severity,
+            html_escape(&f.file),
+            f.line,
+            f.column,
+            id,
+            html_escape(f.verbose.as_deref().unwrap_or(&f.message)),
+            html_escape(&f.message),
+            note
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Cppcheck results</title>\n<style>\n\
+         body {{ font-family: sans-serif; margin: 1em 2em; }}\n\
+         .count {{ margin-right: 1.5em; font-weight: bold; }}\n\
+         table {{ border-collapse: collapse; margin-top: 1em; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; \
+         vertical-align: top; }}\n\
+         th {{ background: #eee; }}\n\
+         td:nth-child(3), td:nth-child(4) {{ text-align: right; }}\n\
+         </style>\n</head>\n<body>\n<h1>Cppcheck results</h1>\n\
+         <p>{} finding(s)</p>\n<p>\n{}</p>\n<table>\n\
+         <tr><th>Severity</th><th>File</th><th>Line</th><th>Column</th><th>Id</th>\
+         <th>Message</th>{}</tr>\n{}</table>\n</body>\n</html>\n",
+        findings.len(),
+        summary,
+        if has_notes { "<th>Note</th>" } else { "" },
+        rows
+    )
+}
+
+// One-page Markdown overview for status updates: counts per severity and
+// the files and ids with the most findings
+pub fn to_summary(findings: &[Finding]) -> String {
+    let mut out = format!("# Cppcheck summary\n\n{} finding(s)\n", findings.len());
+    if findings.is_empty() {
+        return out;
+    }
+    out.push_str("\n| Severity | Findings |\n|---|---:|\n");
+    for severity in Severity::ALL {
+        let count = findings.iter().filter(|f| f.severity == severity).count();
+        if count > 0 {
+            out.push_str(&format!("| {} | {} |\n", severity, count));
+        }
+    }
+    for (title, key) in [
+        (
+            "File",
+            (|f: &Finding| f.file.as_str()) as fn(&Finding) -> &str,
+        ),
+        ("Id", |f: &Finding| f.id.as_str()),
+    ] {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for f in findings {
+            *counts.entry(key(f)).or_default() += 1;
+        }
+        let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        out.push_str(&format!(
+            "\n## Top {}s\n\n| {} | Findings |\n|---|---:|\n",
+            title.to_lowercase(),
+            title
+        ));
+        for (name, count) in counts.iter().take(SUMMARY_TOP) {
+            out.push_str(&format!("| {} | {} |\n", markdown_cell(name), count));
+        }
+        if counts.len() > SUMMARY_TOP {
+            out.push_str(&format!("\n…and {} more\n", counts.len() - SUMMARY_TOP));
+        }
+    }
+    out
+}
+
+// Text safe inside a Markdown table cell
+fn markdown_cell(s: &str) -> String {
+    format!("`{}`", s.replace('|', "\\|").replace('`', "'"))
+}
+
+fn html_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        _ => "note",
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
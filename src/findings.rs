@@ -0,0 +1,385 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Style,
+    Performance,
+    Portability,
+    Information,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 6] = [
+        Severity::Error,
+        Severity::Warning,
+        Severity::Style,
+        Severity::Performance,
+        Severity::Portability,
+        Severity::Information,
+    ];
+
+    // Strictness levels offered by the threshold control, strictest first
+    pub const THRESHOLDS: [Severity; 5] = [
+        Severity::Error,
+        Severity::Warning,
+        Severity::Style,
+        Severity::Performance,
+        Severity::Information,
+    ];
+
+    // Position in THRESHOLDS; portability ranks with performance
+    pub fn rank(&self) -> usize {
+        match self {
+            Severity::Error => 0,
+            Severity::Warning => 1,
+            Severity::Style => 2,
+            Severity::Performance | Severity::Portability => 3,
+            Severity::Information => 4,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Severity> {
+        match s.trim() {
+            "error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "style" => Some(Severity::Style),
+            "performance" => Some(Severity::Performance),
+            "portability" => Some(Severity::Portability),
+            "information" => Some(Severity::Information),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Style => "style",
+            Severity::Performance => "performance",
+            Severity::Portability => "portability",
+            Severity::Information => "information",
+        }
+    }
+
+    // Color used for result rows and the legend
+    pub fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => "#c01c28",
+            Severity::Warning => "#e66100",
+            Severity::Style => "#1c71d8",
+            Severity::Performance => "#813d9c",
+            Severity::Portability => "#26a269",
+            Severity::Information => "#77767b",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// A team's policy for how findings are shown, e.g. variableScope as an
+// error or all style findings as warnings. Keys are check ids or severity
+// names; a rule for the id wins over one for the severity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SeverityMap(BTreeMap<String, Severity>);
+
+impl SeverityMap {
+    // Rules of the form "key=severity", as saved in the config
+    pub fn from_rules<'a>(rules: impl IntoIterator<Item = &'a str>) -> Result<SeverityMap, String> {
+        let mut map = BTreeMap::new();
+        for rule in rules {
+            let (key, severity) = rule
+                .split_once('=')
+                .ok_or_else(|| format!("\"{}\" is not of the form id=severity", rule.trim()))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!(
+                    "\"{}\" has no id or severity to remap",
+                    rule.trim()
+                ));
+            }
+            let severity = Severity::parse(severity)
+                .ok_or_else(|| format!("unknown severity \"{}\"", severity.trim()))?;
+            map.insert(key.to_string(), severity);
+        }
+        Ok(SeverityMap(map))
+    }
+
+    // Comma-separated rules as typed in Settings, e.g.
+    // "variableScope=error, style=warning"
+    pub fn parse(text: &str) -> Result<SeverityMap, String> {
+        SeverityMap::from_rules(text.split(',').filter(|r| !r.trim().is_empty()))
+    }
+
+    pub fn rules(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|(key, s)| format!("{}={}", key, s))
+            .collect()
+    }
+
+    pub fn apply(&self, finding: &mut Finding) {
+        let rule = self
+            .0
+            .get(&finding.id)
+            .or_else(|| self.0.get(finding.severity.as_str()));
+        if let Some(&severity) = rule {
+            finding.severity = severity;
+        }
+    }
+}
+
+impl fmt::Display for SeverityMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.rules().join(", "))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+    pub severity: Severity,
+    pub id: String,
+    pub message: String,
+    // Longer explanation and CWE number, from XML reports or a template
+    // with {verbose} and {cwe}
+    pub verbose: Option<String>,
+    pub cwe: Option<u32>,
+    // Reported under --inconclusive, so lower confidence
+    pub inconclusive: bool,
+    // Triage note from the results list, filled in for exports
+    pub note: Option<String>,
+}
+
+impl Finding {
+    // Identifies the same finding across runs; the line is left out since
+    // edits elsewhere in the file move it
+    pub fn review_key(&self) -> String {
+        format!("{}\t{}\t{}", self.file, self.id, self.message)
+    }
+}
+
+// First line of cppcheck's built-in output format
+pub const DEFAULT_TEMPLATE: &str = "{file}:{line}:{column}: {severity}: {message} [{id}]";
+
+// cppcheck's built-in format plus the CWE number and the long message,
+// passed in its place so the results list gets them without an XML run
+pub const DETAILED_TEMPLATE: &str =
+    "{file}:{line}:{column}: {severity}:{inconclusive:inconclusive:} \
+     {message} [{id}] CWE-{cwe}: {verbose}\\n{code}";
+
+// Placeholder printing INCONCLUSIVE_PREFIX for inconclusive findings, for
+// templates that would otherwise not show it
+pub const INCONCLUSIVE_MARK: &str = "{inconclusive:inconclusive: }";
+const INCONCLUSIVE_PREFIX: &str = "inconclusive: ";
+
+// Named `--template` presets; `None` is cppcheck's own format, run as
+// DETAILED_TEMPLATE
+pub const TEMPLATE_PRESETS: &[(&str, Option<&str>)] = &[
+    ("cppcheck default", None),
+    (
+        "gcc-style",
+        Some("{file}:{line}:{column}: {severity}: {message} [{id}]"),
+    ),
+    (
+        "vs-style",
+        Some("{file}({line}): {severity}: {message} [{id}]"),
+    ),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Field {
+    File,
+    Line,
+    Column,
+    Severity,
+    Id,
+    Message,
+    Cwe,
+    Verbose,
+    // {inconclusive:...}, empty for conclusive findings
+    Inconclusive,
+    // Any other placeholder, e.g. {code}
+    Other,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Field(Field),
+}
+
+// A cppcheck `--template` string compiled into literals and fields, used to
+// pull findings back out of the output it produces
+#[derive(Clone, Debug)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Template {
+        // Only the first line carries the location; later lines hold {code}
+        let first = template
+            .split("\\n")
+            .next()
+            .unwrap_or_default()
+            .lines()
+            .next()
+            .unwrap_or_default();
+        let mut parts = Vec::new();
+        let mut rest = first;
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}').map(|c| open + c) else {
+                break;
+            };
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_string()));
+            }
+            let field = match &rest[open + 1..close] {
+                "file" => Field::File,
+                "line" => Field::Line,
+                "column" => Field::Column,
+                "severity" => Field::Severity,
+                "id" => Field::Id,
+                "message" => Field::Message,
+                "cwe" => Field::Cwe,
+                "verbose" => Field::Verbose,
+                name if name.starts_with("inconclusive:") => Field::Inconclusive,
+                _ => Field::Other,
+            };
+            parts.push(Part::Field(field));
+            rest = &rest[close + 1..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+        Template { parts }
+    }
+
+    // Templates without a file or severity cannot yield findings
+    pub fn is_parseable(&self) -> bool {
+        self.parts.contains(&Part::Field(Field::File))
+            && self.parts.contains(&Part::Field(Field::Severity))
+    }
+
+    pub fn parse_line(&self, line: &str) -> Option<Finding> {
+        if !self.is_parseable() {
+            return None;
+        }
+        let mut captures = Vec::new();
+        // Only the line break goes: an empty trailing field such as
+        // {verbose} leaves the separator's space at the end
+        if !match_parts(
+            &self.parts,
+            line.trim_end_matches(['\r', '\n']),
+            &mut captures,
+        ) {
+            return None;
+        }
+        let mut finding = Finding {
+            file: String::new(),
+            line: 0,
+            column: 0,
+            severity: Severity::Information,
+            id: String::new(),
+            message: String::new(),
+            verbose: None,
+            cwe: None,
+            inconclusive: false,
+            note: None,
+        };
+        for (field, value) in captures {
+            match field {
+                Field::File => finding.file = value.to_string(),
+                Field::Line => finding.line = value.parse().ok()?,
+                Field::Column => finding.column = value.parse().ok()?,
+                Field::Severity => {
+                    finding.severity = parse_severity(value)?;
+                    finding.inconclusive |= value.ends_with(":inconclusive");
+                }
+                Field::Id => finding.id = value.to_string(),
+                Field::Message => finding.message = value.to_string(),
+                // cppcheck prints 0 when no CWE is known
+                Field::Cwe => finding.cwe = value.parse().ok().filter(|&cwe| cwe > 0),
+                Field::Verbose => {
+                    finding.verbose = Some(value.to_string()).filter(|v| !v.is_empty())
+                }
+                Field::Inconclusive => finding.inconclusive |= !value.is_empty(),
+                Field::Other => {}
+            }
+        }
+        // cppcheck's default format and INCONCLUSIVE_MARK put this before
+        // the message
+        if let Some(message) = finding.message.strip_prefix(INCONCLUSIVE_PREFIX) {
+            finding.message = message.to_string();
+            finding.inconclusive = true;
+        }
+        Some(finding)
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Template::parse(DEFAULT_TEMPLATE)
+    }
+}
+
+// The built-in format appends ":inconclusive" to the severity
+fn parse_severity(s: &str) -> Option<Severity> {
+    Severity::parse(s.trim_end_matches(":inconclusive"))
+}
+
+fn field_accepts(field: Field, value: &str) -> bool {
+    match field {
+        Field::File => !value.is_empty(),
+        Field::Line | Field::Column | Field::Cwe => {
+            !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit())
+        }
+        Field::Severity => parse_severity(value).is_some(),
+        Field::Id => !value.is_empty() && !value.contains(char::is_whitespace),
+        Field::Message | Field::Verbose | Field::Inconclusive | Field::Other => true,
+    }
+}
+
+// Match `text` against the template parts, backtracking over every possible
+// end of each field so that e.g. file names containing ':' still work
+fn match_parts<'a>(parts: &[Part], text: &'a str, captures: &mut Vec<(Field, &'a str)>) -> bool {
+    let Some((first, rest)) = parts.split_first() else {
+        return text.is_empty();
+    };
+    match first {
+        Part::Literal(lit) => match text.strip_prefix(lit.as_str()) {
+            Some(remaining) => match_parts(rest, remaining, captures),
+            None => false,
+        },
+        Part::Field(field) => {
+            let ends: Vec<usize> = match rest.first() {
+                Some(Part::Literal(next)) => {
+                    text.match_indices(next.as_str()).map(|(i, _)| i).collect()
+                }
+                // Adjacent fields cannot be told apart; the last one takes the rest
+                _ => vec![text.len()],
+            };
+            for end in ends {
+                let value = &text[..end];
+                if !field_accepts(*field, value) {
+                    continue;
+                }
+                captures.push((*field, value));
+                if match_parts(rest, &text[end..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+    }
+}
@@ -0,0 +1,50 @@
+// Shell-style path patterns: '*' matches within one path component, '**'
+// across components, '?' one character other than '/'
+pub fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    match_from(&pattern, &text)
+}
+
+// Like `matches`, but a relative pattern may also match the path from any
+// component on, so "src/net/*" finds /home/me/project/src/net/socket.c
+pub fn matches_path(pattern: &str, path: &str) -> bool {
+    if matches(pattern, path) {
+        return true;
+    }
+    if pattern.starts_with('/') {
+        return false;
+    }
+    path.match_indices('/')
+        .any(|(i, _)| matches(pattern, &path[i + 1..]))
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            // "**/" also matches no directories at all
+            let rest = &pattern[2..];
+            if rest.first() == Some(&'/') && match_from(&rest[1..], text) {
+                return true;
+            }
+            (0..=text.len()).any(|i| match_from(rest, &text[i..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for i in 0..=text.len() {
+                if match_from(rest, &text[i..]) {
+                    return true;
+                }
+                if text.get(i) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => {
+            text.first().is_some_and(|c| *c != '/') && match_from(&pattern[1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && match_from(&pattern[1..], &text[1..]),
+    }
+}
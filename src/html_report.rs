@@ -0,0 +1,253 @@
+use crate::cppcheck_xml;
+use crate::findings::Finding;
+use crate::scan;
+use crate::suppress;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+// Everything needed to build one HTML report away from the UI thread
+pub struct Job {
+    // Sources the report shows, and what cppcheck scans for the XML
+    pub source_dir: String,
+    // Existing XML report to use instead of scanning
+    pub opened_xml: Option<String>,
+    // XML just written by the scan, in a temp dir of its own; used instead
+    // of scanning again and removed afterwards
+    pub scanned_xml: Option<PathBuf>,
+    // Where the XML is written; removed afterwards unless `keep_xml`
+    pub xml_dir: PathBuf,
+    pub keep_xml: bool,
+    pub report_dir: PathBuf,
+    pub suppressions_list: Option<String>,
+    // Findings under these directories are suppressed
+    pub suppressed_dirs: Vec<String>,
+    // Wrapper cppcheck runs under, see scan::cppcheck_command
+    pub command_prefix: Vec<String>,
+    // "KEY=value" variables for cppcheck, see scan::set_child_env
+    pub env: Vec<String>,
+    // Memory cap in MB, 0 for none, see scan::cppcheck_command
+    pub memory_limit_mb: u32,
+    // Run under nice, see scan::cppcheck_command
+    pub low_priority: bool,
+    // --relative-paths argument for the XML run, if any
+    pub relative_paths: Option<String>,
+    pub title: String,
+    // Log the commands being run
+    pub debug: bool,
+}
+
+// Beyond either limit the report is only opened in the browser after asking
+const LARGE_REPORT_BYTES: u64 = 100 * 1024 * 1024;
+const LARGE_REPORT_FILES: usize = 5000;
+
+pub enum Event {
+    // Step now running, for the status line
+    Stage(&'static str),
+    Log(String),
+    // Findings of the fresh XML, carrying CWE ids and verbose messages
+    Details(Vec<Finding>),
+    Finished(Result<Size, Error>),
+}
+
+// Total size and file count of a finished report
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Size {
+    pub bytes: u64,
+    pub files: usize,
+}
+
+impl Size {
+    pub fn is_large(&self) -> bool {
+        self.bytes > LARGE_REPORT_BYTES || self.files > LARGE_REPORT_FILES
+    }
+
+    fn measure(dir: &Path) -> Size {
+        let mut size = Size::default();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return size;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                let inner = Size::measure(&entry.path());
+                size.bytes += inner.bytes;
+                size.files += inner.files;
+            } else {
+                size.bytes += meta.len();
+                size.files += 1;
+            }
+        }
+        size
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} files, {:.1} MB",
+            self.files,
+            self.bytes as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+pub enum Error {
+    // Writing `path` failed; permission problems can be retried elsewhere
+    Write(PathBuf, io::Error),
+    // Already explained in a Log event
+    Failed,
+}
+
+// Build the report on a worker thread; the pipeline stops at the first
+// failing step and always ends with `Finished`
+pub fn spawn(job: Job) -> Receiver<Event> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let result = run(&job, &tx);
+        // An opened report is the user's file and stays put
+        if job.opened_xml.is_none() && !job.keep_xml {
+            let _ = fs::remove_dir_all(&job.xml_dir);
+        }
+        if let Some(dir) = job.scanned_xml.as_ref().and_then(|xml| xml.parent()) {
+            let _ = fs::remove_dir_all(dir);
+        }
+        let _ = tx.send(Event::Finished(result));
+    });
+    rx
+}
+
+fn run(job: &Job, tx: &Sender<Event>) -> Result<Size, Error> {
+    let log = |text: String| {
+        let _ = tx.send(Event::Log(text));
+    };
+    let log_command = |cmd: &Command| {
+        if job.debug {
+            log(format!("$ {}\n", scan::describe(cmd)));
+        }
+    };
+    let xml_file = match (&job.opened_xml, &job.scanned_xml) {
+        (Some(xml), _) => {
+            log(format!("Using results from {}\n", xml));
+            xml.clone()
+        }
+        (None, Some(scanned)) => {
+            if !job.keep_xml {
+                scanned.to_string_lossy().to_string()
+            } else {
+                fs::create_dir_all(&job.xml_dir)
+                    .map_err(|e| Error::Write(job.xml_dir.clone(), e))?;
+                let xml_file = job.xml_dir.join("cppcheck.xml");
+                fs::copy(scanned, &xml_file).map_err(|e| Error::Write(xml_file.clone(), e))?;
+                log(format!("XML results kept at {}\n", xml_file.display()));
+                xml_file.to_string_lossy().to_string()
+            }
+        }
+        (None, None) => {
+            let _ = tx.send(Event::Stage("Generating XML results…"));
+            fs::create_dir_all(&job.xml_dir).map_err(|e| Error::Write(job.xml_dir.clone(), e))?;
+            let xml_file = job.xml_dir.join("cppcheck.xml");
+            // cppcheck writes the XML itself, so nothing else printed to
+            // stderr can end up in it
+            let mut cmd = scan::cppcheck_command(
+                &job.command_prefix,
+                &job.env,
+                job.memory_limit_mb,
+                job.low_priority,
+            );
+            cmd.args(["--xml", "--xml-version=2"])
+                .arg(format!("--output-file={}", xml_file.display()));
+            if let Some(ref file) = job.suppressions_list {
+                cmd.arg(format!("--suppressions-list={}", file));
+            }
+            cmd.args(suppress::directory_suppressions(&job.suppressed_dirs));
+            if let Some(ref arg) = job.relative_paths {
+                cmd.arg(arg);
+            }
+            cmd.arg(&job.source_dir);
+            log_command(&cmd);
+            // An XML file left by an earlier report would pass for this run's
+            let _ = fs::remove_file(&xml_file);
+            let out = cmd.output().map_err(|e| {
+                log(format!("Error running cppcheck --xml: {}\n", e));
+                Error::Failed
+            })?;
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            // What cppcheck said, and whether the memory limit stopped it
+            let log_stderr = || {
+                log(stderr.to_string());
+                if job.memory_limit_mb > 0 && stderr.lines().any(scan::out_of_memory) {
+                    log(format!(
+                        "cppcheck stopped at the memory limit of {} MB\n",
+                        job.memory_limit_mb
+                    ));
+                }
+            };
+            if !out.status.success() {
+                log_stderr();
+                log(format!("cppcheck --xml failed: {}\n", out.status));
+                return Err(Error::Failed);
+            }
+            let text = match fs::read_to_string(&xml_file) {
+                Ok(text) => text,
+                // Versions or wrappers that ignore --output-file print the
+                // report on stderr, among progress and other messages
+                Err(e) => match scan::xml_document(&stderr) {
+                    Some(document) => {
+                        fs::write(&xml_file, document)
+                            .map_err(|e| Error::Write(xml_file.clone(), e))?;
+                        document.to_string()
+                    }
+                    None => {
+                        log_stderr();
+                        log(format!(
+                            "cppcheck wrote no results to {}: {}\n",
+                            xml_file.display(),
+                            e
+                        ));
+                        return Err(Error::Failed);
+                    }
+                },
+            };
+            if let Ok(details) = cppcheck_xml::parse_results(&text) {
+                let _ = tx.send(Event::Details(details));
+            }
+            if job.keep_xml {
+                log(format!("XML results kept at {}\n", xml_file.display()));
+            }
+            xml_file.to_string_lossy().to_string()
+        }
+    };
+
+    let _ = tx.send(Event::Stage("Building HTML report…"));
+    let report_dir = job.report_dir.to_string_lossy().to_string();
+    let mut cmd = Command::new("cppcheck-htmlreport");
+    cmd.args([
+        "--file",
+        &xml_file,
+        "--report-dir",
+        &report_dir,
+        "--source-dir",
+        &job.source_dir,
+        "--title",
+        &job.title,
+    ]);
+    log_command(&cmd);
+    let out = cmd.output().map_err(|e| {
+        log(format!("Error running cppcheck-htmlreport: {}\n", e));
+        Error::Failed
+    })?;
+    if !out.status.success() {
+        log(String::from_utf8_lossy(&out.stderr).to_string());
+        log("Error generating HTML report\n".to_string());
+        return Err(Error::Failed);
+    }
+    Ok(Size::measure(&job.report_dir))
+}
@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// UI languages as (code, name, table). A table has one "English<TAB>
+// translation" line per string; English needs none since the source
+// strings are English. Adding a language only takes a table and a line here
+const LOCALES: &[(&str, &str, &str)] = &[
+    ("en", "English", ""),
+    ("ru", "Русский", include_str!("../data/i18n/ru.tsv")),
+    ("de", "Deutsch", include_str!("../data/i18n/de.tsv")),
+];
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+thread_local! {
+    static CURRENT: RefCell<HashMap<&'static str, &'static str>> = RefCell::new(HashMap::new());
+}
+
+// (code, name) of every available language
+pub fn locales() -> impl Iterator<Item = (&'static str, &'static str)> {
+    LOCALES.iter().map(|(code, name, _)| (*code, *name))
+}
+
+// Translate from now on into `code`; unknown codes fall back to English
+pub fn set_language(code: &str) {
+    let table = LOCALES
+        .iter()
+        .find(|(c, _, _)| *c == code)
+        .map(|(_, _, table)| parse_table(table))
+        .unwrap_or_default();
+    CURRENT.with(|current| *current.borrow_mut() = table);
+}
+
+// `text` in the current language, or unchanged if it has no translation
+pub fn tr(text: &'static str) -> &'static str {
+    CURRENT.with(|current| current.borrow().get(text).copied().unwrap_or(text))
+}
+
+fn parse_table(data: &'static str) -> HashMap<&'static str, &'static str> {
+    data.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('\t'))
+        .collect()
+}
@@ -0,0 +1,138 @@
+use crate::i18n::tr;
+use gtk4::prelude::*;
+use gtk4::{
+    Align, ApplicationWindow, Box as GtkBox, Button, Entry, FileChooserAction, FileChooserDialog,
+    Label, Orientation, ResponseType, SpinButton, Window,
+};
+use std::path::Path;
+
+// Lines `start..=end` of one file, for showing only the findings there
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineRange {
+    // Absolute path of the file
+    pub file: String,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl LineRange {
+    // `file` is as cppcheck printed it: absolute, or relative to wherever
+    // it ran, so a relative path matches by its trailing components
+    pub fn contains(&self, file: &str, line: u32) -> bool {
+        let path = Path::new(file);
+        let same_file = if path.is_absolute() {
+            path == Path::new(&self.file)
+        } else {
+            !file.is_empty() && Path::new(&self.file).ends_with(path)
+        };
+        same_file && (self.start..=self.end).contains(&line)
+    }
+
+    pub fn describe(&self) -> String {
+        let name = Path::new(&self.file)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.file.clone());
+        format!("{}, lines {}–{}", name, self.start, self.end)
+    }
+}
+
+// Ask for a file of `project` and a line range in it
+pub fn choose<F: Fn(LineRange) + 'static>(parent: &ApplicationWindow, project: &str, on_chosen: F) {
+    let window = Window::builder()
+        .title(tr("Check Line Range"))
+        .transient_for(parent)
+        .modal(true)
+        .default_width(500)
+        .build();
+
+    let file_entry = Entry::new();
+    file_entry.set_hexpand(true);
+    file_entry.set_placeholder_text(Some("Source file"));
+    let browse_btn = Button::with_label(tr("Choose…"));
+    {
+        let window = window.clone();
+        let entry = file_entry.clone();
+        let project = project.to_string();
+        browse_btn.connect_clicked(move |_| {
+            let dialog = FileChooserDialog::builder()
+                .title(tr("Select Source File"))
+                .action(FileChooserAction::Open)
+                .transient_for(&window)
+                .modal(true)
+                .build();
+            dialog.add_buttons(&[
+                (tr("Cancel"), ResponseType::Cancel),
+                (tr("Select"), ResponseType::Accept),
+            ]);
+            let _ = dialog.set_current_folder(Some(&gio::File::for_path(&project)));
+            let entry = entry.clone();
+            dialog.connect_response(move |d, r| {
+                if r == ResponseType::Accept {
+                    if let Some(path) = d.file().and_then(|f| f.path()) {
+                        entry.set_text(&path.to_string_lossy());
+                    }
+                }
+                d.close();
+            });
+            dialog.show();
+        });
+    }
+    let file_row = GtkBox::new(Orientation::Horizontal, 8);
+    file_row.append(&Label::new(Some(tr("File:"))));
+    file_row.append(&file_entry);
+    file_row.append(&browse_btn);
+
+    let start_spin = SpinButton::with_range(1.0, 10_000_000.0, 1.0);
+    let end_spin = SpinButton::with_range(1.0, 10_000_000.0, 1.0);
+    end_spin.set_value(100.0);
+    let lines_row = GtkBox::new(Orientation::Horizontal, 8);
+    lines_row.append(&Label::new(Some(tr("From line:"))));
+    lines_row.append(&start_spin);
+    lines_row.append(&Label::new(Some(tr("to:"))));
+    lines_row.append(&end_spin);
+
+    let error_label = Label::new(None);
+    error_label.set_xalign(0.0);
+    let check_btn = Button::with_label(tr("Check"));
+    check_btn.set_halign(Align::End);
+    {
+        let window = window.clone();
+        let error_label = error_label.clone();
+        let project = project.to_string();
+        check_btn.connect_clicked(move |_| {
+            let text = file_entry.text().trim().to_string();
+            // join keeps an absolute path as it is
+            let file = Path::new(&project).join(&text);
+            let (start, end) = (start_spin.value_as_int(), end_spin.value_as_int());
+            if text.is_empty() || !file.is_file() {
+                error_label.set_text("Choose an existing source file");
+            } else if end < start {
+                error_label.set_text("The last line comes before the first");
+            } else {
+                window.close();
+                on_chosen(LineRange {
+                    file: file.to_string_lossy().to_string(),
+                    start: start as u32,
+                    end: end as u32,
+                });
+            }
+        });
+    }
+
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+    vbox.append(&Label::new(Some(
+        "cppcheck scans the whole file; the results then show only the findings \
+         in these lines.",
+    )));
+    vbox.append(&file_row);
+    vbox.append(&lines_row);
+    vbox.append(&error_label);
+    vbox.append(&check_btn);
+    window.set_child(Some(&vbox));
+    window.present();
+}
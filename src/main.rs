@@ -1,31 +1,116 @@
+use config::{Config, LogLevel, ProjectSettings};
+use excluded_files::ExcludedFiles;
+use findings::{Finding, Severity, Template};
 use gio::AppInfo;
+use glib::ControlFlow;
 use gtk4::prelude::*;
 use gtk4::{
-    Application, ApplicationWindow, Box as GtkBox, Button, CheckButton, ComboBoxText,
-    FileChooserAction, FileChooserDialog, Orientation, ProgressBar, ResponseType, ScrolledWindow,
-    TextBuffer, TextView,
+    Align, Application, ApplicationWindow, Box as GtkBox, Button, ButtonsType, CallbackAction,
+    CheckButton, ComboBoxText, DialogFlags, Entry, Expander, FileChooserAction, FileChooserDialog,
+    FileFilter, Frame, HeaderBar, InfoBar, Label, MenuButton, MessageDialog, MessageType,
+    Orientation, Paned, Popover, PositionType, ProgressBar, ResponseType, Scale, ScrolledWindow,
+    SearchEntry, Shortcut, ShortcutController, ShortcutTrigger, TextBuffer, TextView, ToggleButton,
 };
-use std::cell::RefCell;
+use i18n::tr;
+use results::ResultsView;
+use rule_files::RuleFiles;
+use scan::{Output, ScanEvent, ScanHandle};
+use scan_queue::ScanQueue;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::env;
 use std::fs;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::rc::Rc;
+use std::sync::mpsc::{self, TryRecvError};
+use std::time::Duration;
+use suppress_panel::SuppressionsPanel;
+
+mod archive;
+mod blame;
+mod build_system;
+mod command_palette;
+mod compare;
+mod config;
+mod cppcheck_project;
+mod cppcheck_xml;
+mod excluded_files;
+mod export;
+mod findings;
+mod glob;
+mod html_report;
+mod i18n;
+mod json;
+mod line_range;
+mod profile;
+mod results;
+mod rule_files;
+mod scan;
+mod scan_queue;
+mod settings_dialog;
+mod source_viewer;
+mod suppress;
+mod suppress_panel;
+mod tools;
+mod usage_stats;
+
+// --enable group for unused function detection
+const UNUSED_FUNCTION: &str = "unusedFunction";
+// Status of a scan that completed without findings
+const NO_ISSUES_COLOR: &str = "#26a269";
+// Quiet time after a settings change before Config::rescan_on_change scans
+const RESCAN_DELAY: Duration = Duration::from_millis(1500);
+// Widget name of the show_no_sources banner, to find it again
+const NO_SOURCES_BANNER: &str = "no-sources";
 
 fn main() {
     // Disable GIO proxy modules to avoid Snap-related errors
-    env::set_var("GIO_USE_PROXY", "none");
+    scan::disable_gio_proxy();
 
-    // Initialize GTK application
+    // Initialize GTK application; a project path may be given as argument
     let app = Application::builder()
         .application_id("com.example.CppcheckGui")
+        .flags(gio::ApplicationFlags::HANDLES_COMMAND_LINE)
         .build();
 
-    app.connect_activate(build_ui);
+    app.connect_activate(|app| build_ui(app, None, &[]));
+    app.connect_command_line(|app, cmdline| {
+        // Arguments may be paths relative to the caller's directory or
+        // file:// URIs from a file manager's "Open with"
+        let (projects, missing): (Vec<PathBuf>, Vec<PathBuf>) = cmdline
+            .arguments()
+            .iter()
+            .skip(1)
+            .filter_map(|arg| cmdline.create_file_for_arg(arg).path())
+            .partition(|path| path.exists());
+        // Paths that don't exist are reported in the first window's log
+        if projects.is_empty() {
+            build_ui(app, None, &missing);
+        }
+        // One window per folder when several are opened at once
+        for (i, project) in projects.into_iter().enumerate() {
+            let missing: &[PathBuf] = if i == 0 { &missing } else { &[] };
+            build_ui(app, Some(project.to_string_lossy().to_string()), missing);
+        }
+        0
+    });
+    // Reports, header lists and log copies are only needed while running
+    app.connect_shutdown(|_| {
+        let _ = fs::remove_dir_all(temp_dir());
+    });
     app.run();
 }
 
-fn build_ui(app: &Application) {
+// One main window, opening `initial_project` if given; `not_found` are
+// command-line paths that don't exist, reported in its log
+fn build_ui(app: &Application, initial_project: Option<String>, not_found: &[PathBuf]) {
+    let config = Rc::new(RefCell::new(Config::load()));
+    i18n::set_language(&config.borrow().language);
+    set_log_level(config.borrow().log_level);
+    set_max_log_lines(config.borrow().max_log_lines);
+
     // Main window
     let window = ApplicationWindow::builder()
         .application(app)
@@ -34,74 +119,447 @@ fn build_ui(app: &Application) {
         .default_height(600)
         .build();
 
+    // Jump to a file's findings from anywhere in the window
+    let search_entry = SearchEntry::new();
+    search_entry.set_placeholder_text(Some(tr("Find file in results")));
+    search_entry.set_key_capture_widget(Some(&window));
+    let header = HeaderBar::new();
+    header.pack_end(&search_entry);
+    window.set_titlebar(Some(&header));
+
     // State: selected project path
     let project_path = Rc::new(RefCell::new(None::<String>));
+    // XML report opened instead of scanning; reports are built from it
+    let opened_xml = Rc::new(RefCell::new(None::<String>));
+    // State: the scan currently running, if any
+    let active_scan = Rc::new(RefCell::new(None::<ScanHandle>));
+    // State: the last unpacked source archive, removed with the window
+    let extracted_archive = Rc::new(RefCell::new(None::<archive::Extracted>));
+    {
+        let archive_clone = extracted_archive.clone();
+        window.connect_destroy(move |_| {
+            archive_clone.borrow_mut().take();
+        });
+    }
 
     // Layout container
     let vbox = GtkBox::new(Orientation::Vertical, 8);
 
-    // Language selector
+    // Language selector listing every available translation
     let lang_combo = ComboBoxText::new();
-    lang_combo.append_text("en");
-    lang_combo.append_text("ru");
-    lang_combo.set_active(Some(0));
+    for (code, name) in i18n::locales() {
+        lang_combo.append(Some(code), name);
+    }
+    if !lang_combo.set_active_id(Some(&config.borrow().language)) {
+        lang_combo.set_active_id(Some(i18n::DEFAULT_LANGUAGE));
+    }
     vbox.append(&lang_combo);
 
-    // Directory chooser button
-    let select_btn = Button::with_label("Select Project Directory");
-    vbox.append(&select_btn);
+    // Directory chooser button and recent projects
+    let select_btn = Button::with_label(tr("Select Project Directory"));
+    select_btn.set_hexpand(true);
+    select_btn.set_tooltip_text(Some(tr(
+        "Choose a directory, or press Ctrl+V here to open a copied path",
+    )));
+    let recent_btn = MenuButton::new();
+    recent_btn.set_label(tr("Recent"));
+    recent_btn.set_popover(Some(&Popover::new()));
+    let hbox_project = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_project.append(&select_btn);
+    hbox_project.append(&recent_btn);
+    let btn_open_xml = Button::with_label(tr("Open XML Results"));
+    btn_open_xml.set_tooltip_text(Some(
+        "View a cppcheck XML v2 report produced elsewhere, e.g. in CI",
+    ));
+    hbox_project.append(&btn_open_xml);
+    let btn_archive = Button::with_label(tr("Scan Archive…"));
+    btn_archive.set_tooltip_text(Some(
+        "Unpack a .zip or .tar.* source archive to a temporary directory and scan it",
+    ));
+    hbox_project.append(&btn_archive);
+    vbox.append(&hbox_project);
+    // Settings suggested by the build files of the project just opened
+    let suggestions_slot = GtkBox::new(Orientation::Vertical, 0);
+    vbox.append(&suggestions_slot);
 
     // Severity filters
-    let chk_error = CheckButton::with_label("Error");
+    let chk_error = CheckButton::with_label(tr("Error"));
     chk_error.set_active(true);
-    let chk_warning = CheckButton::with_label("Warning");
+    // Not an --enable group: cppcheck always reports errors
+    chk_error.set_sensitive(false);
+    chk_error.set_tooltip_text(Some(tr("cppcheck always checks for errors")));
+    let chk_warning = CheckButton::with_label(tr("Warning"));
     chk_warning.set_active(true);
-    let chk_style = CheckButton::with_label("Style");
+    let chk_style = CheckButton::with_label(tr("Style"));
     chk_style.set_active(false);
-    let chk_performance = CheckButton::with_label("Performance");
+    let chk_performance = CheckButton::with_label(tr("Performance"));
     chk_performance.set_active(false);
     let hbox_checks = GtkBox::new(Orientation::Horizontal, 4);
     hbox_checks.append(&chk_error);
     hbox_checks.append(&chk_warning);
     hbox_checks.append(&chk_style);
     hbox_checks.append(&chk_performance);
-    vbox.append(&hbox_checks);
+    let btn_select_all = Button::with_label(tr("Select all"));
+    let btn_select_none = Button::with_label(tr("Select none"));
+    btn_select_all.set_hexpand(true);
+    btn_select_all.set_halign(Align::End);
+    hbox_checks.append(&btn_select_all);
+    hbox_checks.append(&btn_select_none);
+    for (button, active) in [(&btn_select_all, true), (&btn_select_none, false)] {
+        let checks = [
+            chk_warning.clone(),
+            chk_style.clone(),
+            chk_performance.clone(),
+        ];
+        button.connect_clicked(move |_| {
+            for chk in &checks {
+                chk.set_active(active);
+            }
+        });
+    }
+    hbox_checks.set_margin_start(6);
+    hbox_checks.set_margin_end(6);
+    hbox_checks.set_margin_bottom(6);
+    let frame_checks = Frame::new(Some(tr("Severities")));
+    frame_checks.set_child(Some(&hbox_checks));
+    vbox.append(&frame_checks);
 
-    // Control buttons
-    let btn_run = Button::with_label("Run Cppcheck");
-    let btn_html = Button::with_label("Generate HTML");
-    let btn_pdf = Button::with_label("Generate PDF");
-    btn_html.set_sensitive(false);
-    btn_pdf.set_sensitive(false);
-    // Check utilities availability
-    let html_ok = Command::new("which")
-        .arg("cppcheck-htmlreport")
-        .output()
-        .is_ok();
-    let pdf_tool: Option<String> = if Command::new("which").arg("google-chrome").output().is_ok() {
-        Some("google-chrome".into())
-    } else if Command::new("which")
-        .arg("chromium-browser")
-        .output()
-        .is_ok()
-    {
-        Some("chromium-browser".into())
+    // Severity threshold: one strictness level instead of separate checkboxes
+    let threshold = Scale::with_range(
+        Orientation::Horizontal,
+        0.0,
+        (Severity::THRESHOLDS.len() - 1) as f64,
+        1.0,
+    );
+    threshold.set_round_digits(0);
+    threshold.set_draw_value(false);
+    threshold.set_hexpand(true);
+    for (i, severity) in Severity::THRESHOLDS.iter().enumerate() {
+        threshold.add_mark(i as f64, PositionType::Bottom, Some(severity.as_str()));
+    }
+    threshold.set_value(Severity::Warning.rank() as f64);
+    let hbox_threshold = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_threshold.append(&Label::new(Some(tr("Severity threshold:"))));
+    hbox_threshold.append(&threshold);
+    vbox.append(&hbox_threshold);
+
+    // Raw --enable value for those who know the groups by name
+    let enable_entry = Entry::new();
+    enable_entry.set_hexpand(true);
+    enable_entry.set_placeholder_text(Some(tr("e.g. warning,style,information")));
+    enable_entry.set_tooltip_text(Some(tr(
+        "Passed as --enable= in place of the severity checkboxes; the threshold \
+         still filters the results. Leave empty to use the checkboxes",
+    )));
+    let enable_error = Label::new(None);
+    enable_error.set_visible(false);
+    let hbox_enable = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_enable.append(&Label::new(Some(tr("Advanced --enable:"))));
+    hbox_enable.append(&enable_entry);
+    hbox_enable.append(&enable_error);
+    vbox.append(&hbox_enable);
+
+    // Analysis options depending on the installed toolchain
+    let cppcheck_version = tools::cppcheck_version();
+    let chk_clang = CheckButton::with_label(tr("Use clang parser (--clang)"));
+    if tools::clang_supported(cppcheck_version) {
+        chk_clang.set_tooltip_text(Some(
+            "Let clang preprocess and parse the sources; helps with complex modern C++",
+        ));
     } else {
-        None
-    };
-    if !html_ok {
-        btn_html.set_sensitive(false);
+        chk_clang.set_sensitive(false);
+        chk_clang.set_tooltip_text(Some("Requires clang on PATH and cppcheck 2.0 or newer"));
+    }
+    let chk_unused = CheckButton::with_label(tr("Unused functions (--enable=unusedFunction)"));
+    chk_unused.set_tooltip_text(Some(
+        "Needs the whole program in a single cppcheck process; \
+         cppcheck skips this check in parallel (-j) runs, so scans run with -j1",
+    ));
+    let chk_bug_hunting = CheckButton::with_label(tr("Bug hunting (--bug-hunting)"));
+    if tools::bug_hunting_supported(cppcheck_version) {
+        chk_bug_hunting.set_tooltip_text(Some(
+            "Experimental soundy analysis that reports more potential defects; \
+             very slow and prone to false positives",
+        ));
+    } else {
+        chk_bug_hunting.set_sensitive(false);
+        chk_bug_hunting.set_tooltip_text(Some(
+            "Only available in cppcheck 2.0 to 2.6; later versions removed it",
+        ));
+    }
+    let chk_headers = CheckButton::with_label(tr("Check header files too"));
+    chk_headers.set_tooltip_text(Some(
+        "cppcheck normally reads headers only where they are #included; this passes \
+         every header as a file of its own, so header-only code gets checked too",
+    ));
+    let chk_aggressive = CheckButton::with_label(tr("Aggressive (--enable=all --inconclusive)"));
+    chk_aggressive.set_tooltip_text(Some(
+        "Run every check and also report findings cppcheck is unsure about. \
+         Finds the most, but is slower and much noisier; inconclusive findings \
+         are marked in the results",
+    ));
+    let hbox_options = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_options.append(&chk_clang);
+    hbox_options.append(&chk_unused);
+    hbox_options.append(&chk_bug_hunting);
+    hbox_options.append(&chk_headers);
+    hbox_options.append(&chk_aggressive);
+    vbox.append(&hbox_options);
+    // A valid expression leaves the checks it replaces without effect
+    {
+        let frame_checks = frame_checks.clone();
+        let chk_unused = chk_unused.clone();
+        enable_entry.connect_changed(move |entry| {
+            let parsed = config::parse_enable_expression(&entry.text());
+            match parsed {
+                Err(ref e) => {
+                    enable_error.set_text(e);
+                    entry.add_css_class("error");
+                }
+                Ok(_) => entry.remove_css_class("error"),
+            }
+            enable_error.set_visible(parsed.is_err());
+            let overridden = parsed.is_ok_and(|groups| !groups.is_empty());
+            for widget in [
+                frame_checks.upcast_ref::<gtk4::Widget>(),
+                chk_unused.upcast_ref(),
+            ] {
+                widget.set_sensitive(!overridden);
+            }
+        });
+    }
+
+    // Project-specific checks written as cppcheck rule files
+    let rule_files = RuleFiles::new();
+    vbox.append(&rule_files.widget);
+
+    // Files dropped from scope while triaging results
+    let excluded = ExcludedFiles::new();
+    vbox.append(&excluded.widget);
+
+    // Output template presets, plus a free-form custom template
+    let template_combo = ComboBoxText::new();
+    for (name, _) in findings::TEMPLATE_PRESETS {
+        template_combo.append_text(name);
     }
-    if pdf_tool.is_none() {
-        btn_pdf.set_sensitive(false);
+    template_combo.append_text("custom");
+    template_combo.set_active(Some(0));
+    let template_entry = Entry::new();
+    template_entry.set_placeholder_text(Some(findings::DEFAULT_TEMPLATE));
+    template_entry.set_hexpand(true);
+    template_entry.set_sensitive(false);
+    {
+        let entry_clone = template_entry.clone();
+        template_combo.connect_changed(move |c| {
+            let custom = c.active() == Some(findings::TEMPLATE_PRESETS.len() as u32);
+            entry_clone.set_sensitive(custom);
+        });
+    }
+    let hbox_template = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_template.append(&Label::new(Some(tr("Output template:"))));
+    hbox_template.append(&template_combo);
+    hbox_template.append(&template_entry);
+    vbox.append(&hbox_template);
+
+    // Directory cppcheck runs in; relative includes and paths resolve from it
+    let workdir_entry = Entry::new();
+    workdir_entry.set_placeholder_text(Some(tr("Project directory")));
+    workdir_entry.set_hexpand(true);
+    let workdir_btn = folder_button(&window, &workdir_entry, "Select Working Directory");
+    let hbox_workdir = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_workdir.append(&Label::new(Some(tr("Working directory:"))));
+    hbox_workdir.append(&workdir_entry);
+    hbox_workdir.append(&workdir_btn);
+    vbox.append(&hbox_workdir);
+
+    // cppcheck keeps per-file analysis results here and skips unchanged
+    // files on the next scan
+    let build_dir_entry = Entry::new();
+    build_dir_entry.set_placeholder_text(Some(tr("None (no caching)")));
+    build_dir_entry.set_hexpand(true);
+    build_dir_entry.set_tooltip_text(Some(
+        "Passed as --cppcheck-build-dir; relative paths are inside the project. \
+         Created if missing",
+    ));
+    let build_dir_btn = folder_button(&window, &build_dir_entry, "Select Cache Directory");
+    let hbox_build_dir = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_build_dir.append(&Label::new(Some(tr("Cache directory:"))));
+    hbox_build_dir.append(&build_dir_entry);
+    hbox_build_dir.append(&build_dir_btn);
+    vbox.append(&hbox_build_dir);
+
+    // Python addons such as MISRA and CERT checks
+    let addons_entry = Entry::new();
+    addons_entry.set_placeholder_text(Some(tr("e.g. misra, cert")));
+    addons_entry.set_hexpand(true);
+    addons_entry.set_tooltip_text(Some(
+        "Comma-separated addon names or .py/.json files, each passed as --addon. \
+         The Python interpreter they run with is chosen in Settings",
+    ));
+    let hbox_addons = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_addons.append(&Label::new(Some(tr("Addons:"))));
+    hbox_addons.append(&addons_entry);
+    // Library configurations for the APIs the code uses
+    let libraries_entry = Entry::new();
+    libraries_entry.set_placeholder_text(Some(tr("e.g. qt, posix")));
+    libraries_entry.set_hexpand(true);
+    libraries_entry.set_tooltip_text(Some(
+        "Comma-separated cppcheck library configurations, each passed as --library",
+    ));
+    hbox_addons.append(&Label::new(Some(tr("Libraries:"))));
+    hbox_addons.append(&libraries_entry);
+    vbox.append(&hbox_addons);
+
+    let compile_db_entry = Entry::new();
+    compile_db_entry.set_placeholder_text(Some(tr("e.g. build/compile_commands.json")));
+    compile_db_entry.set_hexpand(true);
+    compile_db_entry.set_tooltip_text(Some(
+        "Scan the files listed in this compile_commands.json, relative to the \
+         project, with their include paths and defines (--project)",
+    ));
+    let hbox_compile_db = GtkBox::new(Orientation::Horizontal, 4);
+    hbox_compile_db.append(&Label::new(Some(tr("Compile database:"))));
+    hbox_compile_db.append(&compile_db_entry);
+    // Paths in the output relative to a base, for portable reports
+    let chk_relative = CheckButton::with_label(tr("Relative paths"));
+    chk_relative.set_tooltip_text(Some(
+        "Report file paths relative to the base directory (--relative-paths) in \
+         scans and XML reports, so they can be viewed on another machine. \
+         Opening, previewing and suppressing findings here joins the paths onto \
+         that base, so keep it pointing at these sources",
+    ));
+    let relative_base_entry = Entry::new();
+    relative_base_entry.set_placeholder_text(Some(tr("Base: the scanned paths")));
+    relative_base_entry.set_tooltip_text(Some(
+        "Directory the paths are made relative to, itself relative to the project",
+    ));
+    relative_base_entry.set_sensitive(false);
+    {
+        let entry_clone = relative_base_entry.clone();
+        chk_relative.connect_toggled(move |chk| entry_clone.set_sensitive(chk.is_active()));
     }
+    hbox_compile_db.append(&chk_relative);
+    hbox_compile_db.append(&relative_base_entry);
+    vbox.append(&hbox_compile_db);
+
+    let controls = ScanControls {
+        chk_warning: chk_warning.clone(),
+        chk_style: chk_style.clone(),
+        chk_performance: chk_performance.clone(),
+        template_combo: template_combo.clone(),
+        template_entry: template_entry.clone(),
+        chk_clang: chk_clang.clone(),
+        chk_unused: chk_unused.clone(),
+        chk_bug_hunting: chk_bug_hunting.clone(),
+        chk_headers: chk_headers.clone(),
+        chk_aggressive: chk_aggressive.clone(),
+        rule_files: rule_files.clone(),
+        excluded: excluded.clone(),
+        workdir_entry: workdir_entry.clone(),
+        build_dir_entry: build_dir_entry.clone(),
+        addons_entry: addons_entry.clone(),
+        libraries_entry: libraries_entry.clone(),
+        compile_db_entry: compile_db_entry.clone(),
+        chk_relative: chk_relative.clone(),
+        relative_base_entry: relative_base_entry.clone(),
+        enable_entry: enable_entry.clone(),
+        threshold: threshold.clone(),
+    };
+
+    // Control buttons
+    let btn_run = Button::with_label(tr("Run Cppcheck"));
+    let btn_html = Button::with_label(tr("Generate HTML"));
+    let btn_pdf = Button::with_label(tr("Generate PDF"));
+    let btn_full = Button::with_label(tr("Full Report"));
+    btn_full.set_tooltip_text(Some(
+        "Scan, generate the XML and HTML report, and open it, stopping at the first failure",
+    ));
+    let btn_quick = Button::with_label(tr("Quick check (errors only)"));
+    btn_quick.set_tooltip_text(Some(
+        "Fast pass with cppcheck's default error checks only, whatever is ticked above",
+    ));
+    let btn_cancel = Button::with_label(tr("Cancel"));
+    btn_cancel.set_sensitive(false);
+    let btn_restart = Button::with_label(tr("Restart"));
+    btn_restart.set_tooltip_text(Some(
+        "Stop the running scan and start it again; a scan started with Run \
+         picks up the current settings",
+    ));
+    btn_restart.set_sensitive(false);
+    let btn_rerun_errors = Button::with_label(tr("Re-run Errored Files"));
+    btn_rerun_errors.set_tooltip_text(Some(
+        "Scan again only the files that had error findings in the last results",
+    ));
+    let btn_diagnose = Button::with_label(tr("Diagnose Configuration"));
+    btn_diagnose.set_tooltip_text(Some(
+        "Run cppcheck --check-config to find missing includes and other setup problems",
+    ));
+    let btn_settings = Button::with_label(tr("Settings"));
+    let btn_usage = Button::with_label(tr("Usage Statistics…"));
+    let btn_compare = Button::with_label(tr("Compare Scans"));
+    let btn_line_range = Button::with_label(tr("Check Line Range…"));
+    btn_line_range.set_tooltip_text(Some(tr(
+        "Scan one file and show only the findings in a range of its lines",
+    )));
+    let btn_compare_std = Button::with_label(tr("Compare Standards…"));
+    btn_compare_std.set_tooltip_text(Some(tr(
+        "Scan the project with two C/C++ standards and list how the findings differ",
+    )));
+    let btn_queue = Button::with_label(tr("Queue…"));
+    btn_queue.set_tooltip_text(Some(tr("Scan several projects one after another")));
+    let history_btn = MenuButton::new();
+    history_btn.set_label(tr("History"));
+    history_btn.set_popover(Some(&Popover::new()));
+    // The report generated last, for when reports don't open by themselves
+    let btn_open_report = Button::with_label(tr("Open Report"));
+    btn_open_report.set_sensitive(false);
+    let last_report: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    // Locations of the generated reports, for scripts and teammates
+    let copy_path_btn = MenuButton::new();
+    copy_path_btn.set_label(tr("Copy Path"));
+    copy_path_btn.set_popover(Some(&Popover::new()));
+    // Scan settings shared as a file, e.g. a team's standard configuration
+    let profile_btn = MenuButton::new();
+    profile_btn.set_label(tr("Profile"));
+    let btn_export_profile = Button::with_label(tr("Export Profile…"));
+    btn_export_profile.set_has_frame(false);
+    let btn_import_profile = Button::with_label(tr("Import Profile…"));
+    btn_import_profile.set_has_frame(false);
+    // For opening the same configuration in the official Cppcheck GUI
+    let btn_export_project = Button::with_label(tr("Export cppcheck Project…"));
+    btn_export_project.set_has_frame(false);
+    let profile_menu = GtkBox::new(Orientation::Vertical, 4);
+    profile_menu.append(&btn_export_profile);
+    profile_menu.append(&btn_import_profile);
+    profile_menu.append(&btn_export_project);
+    let profile_popover = Popover::new();
+    profile_popover.set_child(Some(&profile_menu));
+    profile_btn.set_popover(Some(&profile_popover));
+    btn_html.set_sensitive(false);
+    btn_pdf.set_sensitive(false);
     let hbox_btns = GtkBox::new(Orientation::Horizontal, 4);
     hbox_btns.append(&btn_run);
+    hbox_btns.append(&btn_quick);
+    hbox_btns.append(&btn_full);
+    hbox_btns.append(&btn_cancel);
+    hbox_btns.append(&btn_restart);
+    hbox_btns.append(&btn_rerun_errors);
     hbox_btns.append(&btn_html);
     hbox_btns.append(&btn_pdf);
+    hbox_btns.append(&btn_open_report);
+    hbox_btns.append(&copy_path_btn);
+    hbox_btns.append(&btn_diagnose);
+    hbox_btns.append(&btn_line_range);
+    hbox_btns.append(&history_btn);
+    hbox_btns.append(&btn_queue);
+    hbox_btns.append(&profile_btn);
+    hbox_btns.append(&btn_compare);
+    hbox_btns.append(&btn_compare_std);
+    hbox_btns.append(&btn_settings);
+    hbox_btns.append(&btn_usage);
     vbox.append(&hbox_btns);
 
-    // Log area
+    // Results list above the log area
     let scrolled = ScrolledWindow::new();
     scrolled.set_vexpand(true);
     let text_view = TextView::new();
@@ -109,17 +567,318 @@ fn build_ui(app: &Application) {
     text_view.set_vexpand(true);
     let buffer = text_view.buffer();
     scrolled.set_child(Some(&text_view));
-    vbox.append(&scrolled);
+    keep_log_on_disk(&buffer);
+    {
+        let buf_clone = buffer.clone();
+        window.connect_destroy(move |_| forget_log(&buf_clone));
+    }
+    let btn_save_log = Button::with_label(tr("Save Log…"));
+    btn_save_log.set_tooltip_text(Some(tr(
+        "Save the whole log, including lines no longer shown",
+    )));
+    {
+        let buf_clone = buffer.clone();
+        let win_clone = window.clone();
+        btn_save_log.connect_clicked(move |_| save_log(&win_clone, &buf_clone));
+    }
+    // cppcheck --debug output, shown only when a scan asked for it
+    let debug_view = TextView::new();
+    debug_view.set_editable(false);
+    debug_view.set_monospace(true);
+    let debug_scrolled = ScrolledWindow::new();
+    debug_scrolled.set_min_content_height(150);
+    debug_scrolled.set_child(Some(&debug_view));
+    let debug_expander = Expander::new(Some(tr("cppcheck debug output")));
+    debug_expander.set_child(Some(&debug_scrolled));
+    debug_expander.set_visible(false);
+    // Hold new output back, e.g. to read a chatty scan without it scrolling
+    let btn_pause_log = ToggleButton::with_label(tr("Pause Output"));
+    btn_pause_log.set_tooltip_text(Some(tr(
+        "Keep new log lines back until resumed; Save Log has them all",
+    )));
+    {
+        let buf_clone = buffer.clone();
+        btn_pause_log.connect_toggled(move |btn| {
+            set_log_paused(&buf_clone, btn.is_active());
+            btn.set_label(if btn.is_active() {
+                tr("Resume Output")
+            } else {
+                tr("Pause Output")
+            });
+        });
+    }
+    let log_btns = GtkBox::new(Orientation::Horizontal, 4);
+    log_btns.set_halign(Align::End);
+    log_btns.append(&btn_pause_log);
+    log_btns.append(&btn_save_log);
+    let log_box = GtkBox::new(Orientation::Vertical, 4);
+    log_box.append(&scrolled);
+    log_box.append(&debug_expander);
+    log_box.append(&log_btns);
+    let results = ResultsView::new(&buffer, &extracted_archive);
+    let suppressions = SuppressionsPanel::new(&buffer);
+    {
+        let panel_clone = suppressions.clone();
+        results.connect_add_suppression(move |f| panel_clone.add_from_finding(f));
+    }
+    {
+        let buf_exclude = buffer.clone();
+        let proj_exclude = project_path.clone();
+        let config_clone = config.clone();
+        let controls_clone = controls.clone();
+        results.connect_exclude_file(move |f| {
+            if !controls_clone.excluded.add(&f.file) {
+                return;
+            }
+            append_text(
+                &buf_exclude,
+                &format!("Excluded {} from future scans\n", f.file),
+            );
+            if let Some(ref path) = *proj_exclude.borrow() {
+                let mut cfg = config_clone.borrow_mut();
+                cfg.set_project(path, controls_clone.settings());
+                if let Err(e) = cfg.save() {
+                    append_text(&buf_exclude, &format!("Failed to save settings: {}\n", e));
+                }
+            }
+        });
+    }
+    {
+        let buf_clear = buffer.clone();
+        let proj_clear = project_path.clone();
+        let config_clone = config.clone();
+        let controls_clone = controls.clone();
+        controls.excluded.connect_cleared(move || {
+            if let Some(ref path) = *proj_clear.borrow() {
+                let mut cfg = config_clone.borrow_mut();
+                cfg.set_project(path, controls_clone.settings());
+                if let Err(e) = cfg.save() {
+                    append_text(&buf_clear, &format!("Failed to save settings: {}\n", e));
+                }
+            }
+        });
+    }
+    {
+        let results_clone = results.clone();
+        search_entry.connect_search_changed(move |entry| {
+            results_clone.jump_to_file(&entry.text(), false);
+        });
+    }
+    {
+        // Enter moves on to the next matching finding
+        let results_clone = results.clone();
+        search_entry.connect_activate(move |entry| {
+            results_clone.jump_to_file(&entry.text(), true);
+        });
+    }
+    {
+        let config_clone = config.clone();
+        let buf_source = buffer.clone();
+        let win_clone = window.clone();
+        let results_clone = results.clone();
+        results.connect_open_source(move |f| {
+            let file = results_clone.source_path(&f.file);
+            let editor = {
+                let config = config_clone.borrow();
+                let column = (config.editor_columns && f.column > 0).then_some(f.column);
+                source_viewer::editor_command(&config.editor_command, &file, f.line, column)
+            };
+            if let Some(mut cmd) = editor {
+                log_command(&buf_source, &cmd);
+                // Reaped on a thread so no zombie is left behind
+                match cmd.spawn() {
+                    Ok(mut child) => {
+                        std::thread::spawn(move || child.wait());
+                    }
+                    Err(e) => append_text(
+                        &buf_source,
+                        &format!("Failed to start the editor for {}: {}\n", file, e),
+                    ),
+                }
+            } else if config_clone.borrow().external_viewer {
+                let uri = file_uri(&file);
+                if let Err(e) =
+                    AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>)
+                {
+                    append_text(&buf_source, &format!("Failed to open {}: {}\n", file, e));
+                }
+            } else {
+                source_viewer::show(
+                    Some(win_clone.upcast_ref()),
+                    &file,
+                    f.line,
+                    f.column,
+                    &buf_source,
+                );
+            }
+        });
+    }
+    {
+        let buf_folder = buffer.clone();
+        let results_clone = results.clone();
+        results.connect_show_in_folder(move |f| {
+            let file = results_clone.source_path(&f.file);
+            // What xdg-open does for a directory: the default file manager
+            let dir = match Path::new(&file).parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            let uri = file_uri(&dir.to_string_lossy());
+            if let Err(e) = AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>) {
+                append_text(
+                    &buf_folder,
+                    &format!("Failed to open {}: {}\n", dir.display(), e),
+                );
+            }
+        });
+    }
+    results.set_reviewed(config.borrow().reviewed.clone());
+    results.set_notes(config.borrow().notes.clone());
+    {
+        let config_clone = config.clone();
+        let buf_notes = buffer.clone();
+        results.connect_notes_changed(move |notes| {
+            let mut cfg = config_clone.borrow_mut();
+            cfg.notes = notes.clone();
+            if let Err(e) = cfg.save() {
+                append_text(&buf_notes, &format!("Failed to save settings: {}\n", e));
+            }
+        });
+    }
+    results.set_severity_first(config.borrow().sort_by_severity);
+    results.set_hidden_columns(&config.borrow().hidden_columns);
+    {
+        let config_clone = config.clone();
+        let buf_columns = buffer.clone();
+        results.connect_columns_changed(move |hidden| {
+            let mut cfg = config_clone.borrow_mut();
+            cfg.hidden_columns = hidden;
+            if let Err(e) = cfg.save() {
+                append_text(&buf_columns, &format!("Failed to save settings: {}\n", e));
+            }
+        });
+    }
+    results.set_filter_views(config.borrow().filter_views.clone());
+    {
+        let config_clone = config.clone();
+        let buf_views = buffer.clone();
+        results.connect_filter_views_changed(move |views| {
+            let mut cfg = config_clone.borrow_mut();
+            cfg.filter_views = views.to_vec();
+            if let Err(e) = cfg.save() {
+                append_text(&buf_views, &format!("Failed to save settings: {}\n", e));
+            }
+        });
+    }
+    {
+        let config_clone = config.clone();
+        let buf_reviewed = buffer.clone();
+        results.connect_reviewed_changed(move |reviewed| {
+            let mut cfg = config_clone.borrow_mut();
+            cfg.reviewed = reviewed.clone();
+            if let Err(e) = cfg.save() {
+                append_text(&buf_reviewed, &format!("Failed to save settings: {}\n", e));
+            }
+        });
+    }
+    vbox.append(&suppressions.widget);
+    let paned = Paned::new(Orientation::Vertical);
+    paned.set_vexpand(true);
+    paned.set_start_child(Some(&results.widget));
+    paned.set_end_child(Some(&log_box));
+    paned.set_position(300);
+    // Show the results, the log or both; either keeps filling from the scan
+    // output while hidden, so switching needs no re-run
+    let view_results = ToggleButton::with_label(tr("Results"));
+    let view_log = ToggleButton::with_label(tr("Log"));
+    let view_both = ToggleButton::with_label(tr("Both"));
+    view_log.set_group(Some(&view_results));
+    view_both.set_group(Some(&view_results));
+    view_both.set_active(true);
+    let view_row = GtkBox::new(Orientation::Horizontal, 0);
+    view_row.add_css_class("linked");
+    view_row.set_halign(Align::End);
+    view_row.append(&view_results);
+    view_row.append(&view_log);
+    view_row.append(&view_both);
+    for (button, show_results, show_log) in [
+        (&view_results, true, false),
+        (&view_log, false, true),
+        (&view_both, true, true),
+    ] {
+        let results_widget = results.widget.clone();
+        let log_box = log_box.clone();
+        button.connect_toggled(move |b| {
+            if b.is_active() {
+                results_widget.set_visible(show_results);
+                log_box.set_visible(show_log);
+            }
+        });
+    }
+    vbox.append(&view_row);
+    vbox.append(&paned);
+
+    // Keep the threshold and the enable checkboxes in step; the threshold
+    // also filters the displayed results
+    {
+        let syncing = Rc::new(Cell::new(false));
+        let checks = [
+            (chk_warning.clone(), Severity::Warning),
+            (chk_style.clone(), Severity::Style),
+            (chk_performance.clone(), Severity::Performance),
+        ];
+        {
+            let syncing = syncing.clone();
+            let checks = checks.clone();
+            let results_clone = results.clone();
+            threshold.connect_value_changed(move |scale| {
+                let level = threshold_level(scale);
+                results_clone.set_threshold(level);
+                if syncing.replace(true) {
+                    return;
+                }
+                for (chk, severity) in &checks {
+                    chk.set_active(severity.rank() <= level.rank());
+                }
+                syncing.set(false);
+            });
+        }
+        for (chk, _) in &checks {
+            let syncing = syncing.clone();
+            let checks = checks.clone();
+            let threshold_clone = threshold.clone();
+            chk.connect_toggled(move |_| {
+                if syncing.replace(true) {
+                    return;
+                }
+                let rank = checks
+                    .iter()
+                    .filter(|(c, _)| c.is_active())
+                    .map(|(_, s)| s.rank())
+                    .max()
+                    .unwrap_or(Severity::Error.rank());
+                threshold_clone.set_value(rank as f64);
+                syncing.set(false);
+            });
+        }
+        results.set_threshold(threshold_level(&threshold));
+        // A filter view's threshold moves the control, keeping the two alike
+        let threshold_clone = threshold.clone();
+        results.connect_apply_threshold(move |level| {
+            threshold_clone.set_value(level.rank() as f64);
+        });
+    }
+    controls.apply_enables(&config.borrow().default_enables);
 
     // Dependency install button
     let required = ["cppcheck", "cppcheck-htmlreport", "google-chrome"];
     let missing: Vec<String> = required
         .iter()
-        .filter(|&&u| Command::new("which").arg(u).output().is_err())
+        .filter(|&&u| !tools::is_on_path(u))
         .map(|&u| u.into())
         .collect();
     if !missing.is_empty() {
-        let install_btn = Button::with_label("Install Dependencies");
+        let install_btn = Button::with_label(tr("Install Dependencies"));
         vbox.append(&install_btn);
         let deps = missing.clone();
         let dep_buf = buffer.clone();
@@ -136,37 +895,279 @@ fn build_ui(app: &Application) {
         });
     }
 
-    // Progress bar
+    // Which external tools were found, so greyed-out actions make sense
+    let tools_label = Label::new(None);
+    tools_label.set_xalign(0.0);
+    tools_label.set_hexpand(true);
+    tools_label.set_wrap(true);
+    let tools_refresh = Button::with_label(tr("Check Again"));
+    tools_refresh.set_tooltip_text(Some("Look for the tools on PATH again"));
+    let hbox_tools = GtkBox::new(Orientation::Horizontal, 8);
+    hbox_tools.append(&tools_label);
+    hbox_tools.append(&tools_refresh);
+    vbox.append(&hbox_tools);
+    let update_tools = {
+        let html_clone = btn_html.clone();
+        let pdf_clone = btn_pdf.clone();
+        move || {
+            let found = tools::availability();
+            let summary: Vec<String> = found
+                .iter()
+                .map(|(tool, ok)| format!("{}: {}", tool, if *ok { "found" } else { "not found" }))
+                .collect();
+            tools_label.set_text(&format!("Tools: {}", summary.join(" · ")));
+            // Indices follow the order of tools::availability
+            for (button, index, action) in [
+                (&html_clone, 1, "Build an HTML report"),
+                (&pdf_clone, 2, "Print the HTML report to PDF"),
+            ] {
+                let (tool, ok) = found[index];
+                button.set_tooltip_text(Some(&if ok {
+                    format!("{} with {}; available after a scan", action, tool)
+                } else {
+                    format!("{} not found. {}", tool, tools::install_hint(tool))
+                }));
+            }
+        }
+    };
+    update_tools();
+    tools_refresh.connect_clicked(move |_| update_tools());
+
+    // Progress bar with a per-file counter
     let progress = ProgressBar::new();
-    vbox.append(&progress);
+    progress.set_hexpand(true);
+    progress.set_valign(Align::Center);
+    let progress_label = Label::new(None);
+    // Elapsed time and memory of the running scan
+    let resources_label = Label::new(None);
+    resources_label.set_hexpand(true);
+    resources_label.set_halign(Align::End);
+    // Findings so far per severity, while the scan runs
+    let counts_label = Label::new(None);
+    counts_label.set_visible(false);
+    let hbox_progress = GtkBox::new(Orientation::Horizontal, 8);
+    hbox_progress.append(&progress);
+    hbox_progress.append(&progress_label);
+    hbox_progress.append(&counts_label);
+    hbox_progress.append(&resources_label);
+    vbox.append(&hbox_progress);
+
+    // Outcome of the last action, kept after the log scrolls on
+    let status = Label::new(Some(tr("Ready")));
+    status.set_xalign(0.0);
+    status.set_margin_start(4);
+    status.set_margin_end(4);
+    vbox.append(&status);
 
     window.set_child(Some(&vbox));
     window.present();
 
-    // Directory chooser logic
-    {
+    // Select a project, restore its stored settings and optionally scan it
+    let open_project: OpenProject = {
         let proj_clone = project_path.clone();
         let btn_clone = select_btn.clone();
+        let config_clone = config.clone();
+        let controls_clone = controls.clone();
+        let run_clone = btn_run.clone();
+        let buf_clone = buffer.clone();
+        let results_clone = results.clone();
+        let slot_clone = suggestions_slot.clone();
+        Rc::new(move |path: &str, run: bool| {
+            *proj_clone.borrow_mut() = Some(path.to_string());
+            results_clone.set_project_root(path);
+            btn_clone.set_label(path);
+            let mut cfg = config_clone.borrow_mut();
+            if let Some(settings) = cfg.project(path) {
+                controls_clone.apply(settings);
+                log_info(&buf_clone, &format!("Restored settings for {}\n", path));
+            }
+            cfg.add_recent(path);
+            if let Err(e) = cfg.save() {
+                append_text(&buf_clone, &format!("Failed to save settings: {}\n", e));
+            }
+            drop(cfg);
+            show_suggestions(&slot_clone, &controls_clone, Path::new(path));
+            // Only a directory scan is cheap to predict
+            let settings = controls_clone.settings();
+            if settings.compile_database.is_empty()
+                && !settings.check_headers
+                && scan::count_source_files(Path::new(path), &settings.excludes) == 0
+            {
+                show_no_sources(
+                    &slot_clone,
+                    &btn_clone,
+                    &format!(
+                        "{} contains no C or C++ source files, so a scan will find \
+                         nothing. Was another directory meant?",
+                        path
+                    ),
+                );
+            }
+            // Run is insensitive while a scan is going; emit_clicked would
+            // still fire
+            if run && !run_clone.is_sensitive() {
+                append_text(
+                    &buf_clone,
+                    "A scan is already running; not scanning the opened project\n",
+                );
+            } else if run {
+                run_clone.emit_clicked();
+            }
+        })
+    };
+
+    // Recent projects menu, rebuilt each time it opens
+    {
+        let config_clone = config.clone();
+        let open_clone = open_project.clone();
+        recent_btn.set_create_popup_func(move |btn| {
+            let Some(popover) = btn.popover() else {
+                return;
+            };
+            let list = GtkBox::new(Orientation::Vertical, 4);
+            let cfg = config_clone.borrow();
+            if cfg.recent_projects.is_empty() {
+                list.append(&Label::new(Some(tr("No recent projects"))));
+            }
+            for path in &cfg.recent_projects {
+                let row = GtkBox::new(Orientation::Horizontal, 4);
+                let open_btn = Button::with_label(path);
+                open_btn.set_has_frame(false);
+                open_btn.set_hexpand(true);
+                let rerun_btn = Button::with_label(tr("Re-run"));
+                rerun_btn.set_sensitive(cfg.project(path).is_some());
+                rerun_btn.set_tooltip_text(Some("Restore this project's settings and scan it"));
+                for (button, run) in [(&open_btn, cfg.auto_run_recent), (&rerun_btn, true)] {
+                    let open_inner = open_clone.clone();
+                    let popover_inner = popover.clone();
+                    let path = path.clone();
+                    button.connect_clicked(move |_| {
+                        popover_inner.popdown();
+                        open_inner(&path, run);
+                    });
+                }
+                row.append(&open_btn);
+                row.append(&rerun_btn);
+                list.append(&row);
+            }
+            popover.set_child(Some(&list));
+        });
+    }
+
+    // Settings dialog
+    {
+        let win_clone = window.clone();
+        let config_clone = config.clone();
+        let buf_clone = buffer.clone();
+        btn_settings.connect_clicked(move |_| {
+            settings_dialog::show(&win_clone, &config_clone, &buf_clone);
+        });
+    }
+    {
+        let win_clone = window.clone();
+        let config_clone = config.clone();
+        let buf_clone = buffer.clone();
+        btn_usage.connect_clicked(move |_| {
+            usage_stats::show(&win_clone, &config_clone, &buf_clone);
+        });
+    }
+
+    // Load results from an existing XML report without running cppcheck
+    {
+        let win_clone = window.clone();
+        let buf_open = buffer.clone();
+        let results_clone = results.clone();
+        let opened_clone = opened_xml.clone();
+        let html_clone = btn_html.clone();
+        let pdf_clone = btn_pdf.clone();
+        let status_clone = status.clone();
+        let config_clone = config.clone();
+        btn_open_xml.connect_clicked(move |_| {
+            let dialog = FileChooserDialog::builder()
+                .title(tr("Open XML Results"))
+                .action(FileChooserAction::Open)
+                .transient_for(&win_clone)
+                .modal(true)
+                .build();
+            let filter = FileFilter::new();
+            filter.set_name(Some("cppcheck XML (*.xml)"));
+            filter.add_pattern("*.xml");
+            dialog.add_filter(&filter);
+            dialog.add_buttons(&[
+                (tr("Cancel"), ResponseType::Cancel),
+                (tr("Open"), ResponseType::Accept),
+            ]);
+            let buf_inner = buf_open.clone();
+            let results_inner = results_clone.clone();
+            let opened_inner = opened_clone.clone();
+            let html_inner = html_clone.clone();
+            let pdf_inner = pdf_clone.clone();
+            let status_inner = status_clone.clone();
+            let config_inner = config_clone.clone();
+            dialog.connect_response(move |d, r| {
+                d.close();
+                if r != ResponseType::Accept {
+                    return;
+                }
+                let Some(path) = d.file().and_then(|f| f.path()) else {
+                    return;
+                };
+                let path = path.to_string_lossy().to_string();
+                match fs::read_to_string(&path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| cppcheck_xml::parse_results(&text))
+                {
+                    Ok(mut findings) => {
+                        let severity_map = config_inner.borrow().severity_map.clone();
+                        for f in findings.iter_mut() {
+                            severity_map.apply(f);
+                        }
+                        append_text(
+                            &buf_inner,
+                            &format!("Loaded {} finding(s) from {}\n", findings.len(), path),
+                        );
+                        status_inner
+                            .set_text(&format!("Loaded {} findings from XML", findings.len()));
+                        results_inner.set_source_base(None);
+                        results_inner.set_findings(findings);
+                        *opened_inner.borrow_mut() = Some(path);
+                        html_inner.set_sensitive(true);
+                        pdf_inner.set_sensitive(true);
+                    }
+                    Err(e) => append_text(&buf_inner, &format!("Failed to load {}: {}\n", path, e)),
+                }
+            });
+            dialog.show();
+        });
+    }
+
+    // Diff of two exported result sets
+    {
+        let win_clone = window.clone();
+        let buf_clone = buffer.clone();
+        btn_compare.connect_clicked(move |_| compare::show(&win_clone, &buf_clone));
+    }
+
+    // Directory chooser logic
+    {
+        let open_clone = open_project.clone();
         let win_clone = window.clone();
         select_btn.connect_clicked(move |_| {
             let dialog = FileChooserDialog::builder()
-                .title("Select Project Directory")
+                .title(tr("Select Project Directory"))
                 .action(FileChooserAction::SelectFolder)
                 .transient_for(&win_clone)
                 .modal(true)
                 .build();
             dialog.add_buttons(&[
-                ("Cancel", ResponseType::Cancel),
-                ("Select", ResponseType::Accept),
+                (tr("Cancel"), ResponseType::Cancel),
+                (tr("Select"), ResponseType::Accept),
             ]);
-            let proj_inner = proj_clone.clone();
-            let btn_inner = btn_clone.clone();
+            let open_inner = open_clone.clone();
             dialog.connect_response(move |d, r| {
                 if r == ResponseType::Accept {
                     if let Some(path) = d.file().and_then(|f| f.path()) {
-                        let s = path.to_string_lossy().to_string();
-                        *proj_inner.borrow_mut() = Some(s.clone());
-                        btn_inner.set_label(&s);
+                        open_inner(&path.to_string_lossy(), false);
                     }
                 }
                 d.close();
@@ -175,151 +1176,3301 @@ fn build_ui(app: &Application) {
         });
     }
 
+    // A directory path from the clipboard, e.g. copied from a terminal
+    let paste_project: Rc<dyn Fn()> = {
+        let open_clone = open_project.clone();
+        let buf_clone = buffer.clone();
+        let clipboard = select_btn.clipboard();
+        Rc::new(move || {
+            let open_inner = open_clone.clone();
+            let buf_inner = buf_clone.clone();
+            clipboard.read_text_async(None::<&gio::Cancellable>, move |res| {
+                let text = res.ok().flatten().unwrap_or_default();
+                match pasted_directory(&text) {
+                    Ok(path) => open_inner(&path, false),
+                    Err(e) => append_text(&buf_inner, &format!("Project not changed: {}\n", e)),
+                }
+            });
+        })
+    };
+    {
+        let shortcuts = ShortcutController::new();
+        let paste_clone = paste_project.clone();
+        shortcuts.add_shortcut(Shortcut::new(
+            ShortcutTrigger::parse_string("<Control>v"),
+            Some(CallbackAction::new(move |_, _| {
+                paste_clone();
+                glib::Propagation::Stop
+            })),
+        ));
+        hbox_project.add_controller(shortcuts);
+    }
+
+    let scan_ui = ScanUi {
+        log: buffer.clone(),
+        debug: debug_expander.clone(),
+        debug_log: debug_view.buffer(),
+        progress: progress.clone(),
+        progress_label: progress_label.clone(),
+        resources: resources_label.clone(),
+        counts: counts_label.clone(),
+        status: status.clone(),
+        results: results.clone(),
+        run_btn: btn_run.clone(),
+        diagnose_btn: btn_diagnose.clone(),
+        cancel_btn: btn_cancel.clone(),
+        restart_btn: btn_restart.clone(),
+        restart: Rc::new(Cell::new(Restart::No)),
+        last_run: Rc::new(RefCell::new(None)),
+        scan_xml: Rc::new(RefCell::new(None)),
+        plain_run: Rc::new(Cell::new(false)),
+        archive: extracted_archive.clone(),
+        report_after: Rc::new(Cell::new(false)),
+        html_btn: btn_html.clone(),
+        pdf_btn: btn_pdf.clone(),
+        active: active_scan.clone(),
+        opened_xml: opened_xml.clone(),
+        config: config.clone(),
+        queue: Rc::new(RefCell::new(ScanQueue::default())),
+        open_project: open_project.clone(),
+        notices: suggestions_slot.clone(),
+        select_btn: select_btn.clone(),
+    };
+
+    {
+        let win_clone = window.clone();
+        let scan_clone = scan_ui.clone();
+        btn_queue.connect_clicked(move |_| {
+            let scan_inner = scan_clone.clone();
+            scan_queue::show(
+                &win_clone,
+                &scan_clone.queue,
+                Rc::new(move || scan_inner.run_queue()),
+            );
+        });
+    }
+
     // Run cppcheck logic
     {
         let buf_run = buffer.clone();
-        let chk_w = chk_warning.clone();
-        let chk_s = chk_style.clone();
-        let chk_p = chk_performance.clone();
         let proj_run = project_path.clone();
-        let html_btn_clone = btn_html.clone();
-        let pdf_btn_clone = btn_pdf.clone();
-        let progress_clone = progress.clone();
+        let scan_clone = scan_ui.clone();
+        let suppressions_clone = suppressions.clone();
+        let config_clone = config.clone();
+        let controls_clone = controls.clone();
         btn_run.connect_clicked(move |_| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_run, "A scan is already running\n");
+                return;
+            }
             if let Some(ref path) = *proj_run.borrow() {
-                append_text(&buf_run, &format!("Running cppcheck on {}\n", path));
-                progress_clone.set_fraction(0.0);
-                let mut cmd = Command::new("cppcheck");
-                let mut levels = Vec::new();
-                if chk_w.is_active() {
-                    levels.push("warning");
-                }
-                if chk_s.is_active() {
-                    levels.push("style");
-                }
-                if chk_p.is_active() {
-                    levels.push("performance");
-                }
-                if !levels.is_empty() {
-                    cmd.arg(&format!("--enable={}", levels.join(",")));
+                // Remember the settings used for this project
+                let settings = controls_clone.settings();
+                {
+                    let mut cfg = config_clone.borrow_mut();
+                    cfg.set_project(path, settings.clone());
+                    cfg.add_recent(path);
+                    if let Err(e) = cfg.save() {
+                        append_text(&buf_run, &format!("Failed to save settings: {}\n", e));
+                    }
                 }
-                cmd.arg(path);
-                if let Ok(out) = cmd.output() {
-                    append_text(&buf_run, &String::from_utf8_lossy(&out.stdout));
-                    append_text(&buf_run, &String::from_utf8_lossy(&out.stderr));
+                let Some(dir) = working_dir(&settings, path, &buf_run) else {
+                    return;
+                };
+                log_info(&buf_run, &format!("Running cppcheck on {}\n", path));
+                let (mut cmd, parser) = controls_clone.command(
+                    &settings,
+                    suppressions_clone.path(),
+                    &config_clone.borrow(),
+                    &buf_run,
+                );
+                // Options before the path: History re-runs count trailing paths
+                add_build_dir(&mut cmd, &settings, path, &buf_run);
+                // The report is built from this scan's results rather than
+                // from a second analysis
+                if scan_clone.report_after.get() {
+                    let xml = temp_dir()
+                        .join(format!("report-{}", glib::monotonic_time()))
+                        .join("cppcheck.xml");
+                    cmd.args(["--xml", "--xml-version=2"])
+                        .arg(format!("--output-file={}", xml.display()));
+                    log_info(
+                        &buf_run,
+                        "Findings are written as XML for the report and listed when the \
+                         scan ends\n",
+                    );
+                    *scan_clone.scan_xml.borrow_mut() = Some(xml);
                 }
-                progress_clone.set_fraction(1.0);
-                html_btn_clone.set_sensitive(true);
-                pdf_btn_clone.set_sensitive(true);
+                cmd.current_dir(dir);
+                let total = add_scan_target(&mut cmd, &settings, path, &buf_run);
+                // A project scan replaces the archive's findings; drop its files
+                scan_clone.archive.borrow_mut().take();
+                scan_clone.start(cmd, parser, total);
+                scan_clone.plain_run.set(true);
             }
         });
     }
 
-    // Generate HTML report logic
+    // Errors only: no extra --enable groups and no slow modes, leaving the
+    // project's saved settings alone
     {
-        let buf_html = buffer.clone();
+        let buf_run = buffer.clone();
         let proj_run = project_path.clone();
-        btn_html.connect_clicked(move |_| {
+        let scan_clone = scan_ui.clone();
+        let suppressions_clone = suppressions.clone();
+        let controls_clone = controls.clone();
+        btn_quick.connect_clicked(move |_| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_run, "A scan is already running\n");
+                return;
+            }
             if let Some(ref path) = *proj_run.borrow() {
-                append_text(&buf_html, &format!("Generating HTML report for {}\n", path));
-                let project_name = Path::new(path)
-                    .file_name()
-                    .and_then(|n| n.to_str())
-                    .unwrap_or("project");
-                let xml_file = format!("{}/cppcheck.xml", path);
-                if let Ok(out) = Command::new("cppcheck")
-                    .args(&["--xml", "--xml-version=2", path])
-                    .output()
-                {
-                    if fs::write(&xml_file, &out.stderr).is_err() {
-                        append_text(&buf_html, "Failed to write XML report\n");
-                        return;
-                    }
-                } else {
-                    append_text(&buf_html, "Error running cppcheck --xml\n");
-                    return;
-                }
-                let report_dir = format!("{}/html_report", path);
-                if Command::new("cppcheck-htmlreport")
-                    .args(&[
-                        "--file",
-                        &xml_file,
-                        "--report-dir",
-                        &report_dir,
-                        "--source-dir",
-                        path,
-                        "--title",
-                        &format!("Cppcheck report - {}", project_name),
-                    ])
-                    .output()
-                    .is_err()
-                {
-                    append_text(&buf_html, "Error generating HTML report\n");
+                // cppcheck's defaults: no extra groups, rule files, addons,
+                // clang parser or exclusions
+                let settings = ProjectSettings {
+                    enables: Vec::new(),
+                    enable_expression: String::new(),
+                    clang: false,
+                    bug_hunting: false,
+                    aggressive: false,
+                    rule_files: Vec::new(),
+                    excludes: Vec::new(),
+                    addons: Vec::new(),
+                    ..controls_clone.settings()
+                };
+                let Some(dir) = working_dir(&settings, path, &buf_run) else {
                     return;
-                }
-                append_text(
-                    &buf_html,
-                    &format!("HTML report saved to {}/html_report\n", path),
+                };
+                log_info(
+                    &buf_run,
+                    &format!("Quick check (errors only) of {}\n", path),
                 );
-                let index_uri = format!("file://{}/index.html", report_dir);
-                if let Err(e) =
-                    AppInfo::launch_default_for_uri(&index_uri, None::<&gio::AppLaunchContext>)
-                {
-                    append_text(&buf_html, &format!("Failed to open HTML report: {}\n", e));
-                }
+                let mut config = scan_clone.config.borrow().clone();
+                config.max_ctu_depth = config::DEFAULT_MAX_CTU_DEPTH;
+                let (mut cmd, parser) =
+                    controls_clone.command(&settings, suppressions_clone.path(), &config, &buf_run);
+                add_build_dir(&mut cmd, &settings, path, &buf_run);
+                cmd.current_dir(dir);
+                let total = add_scan_target(&mut cmd, &settings, path, &buf_run);
+                scan_clone.archive.borrow_mut().take();
+                scan_clone.start(cmd, parser, total);
             }
         });
     }
 
-    // Generate PDF report logic
+    // Scan, then build and open the HTML report from the poller
     {
-        let buf_pdf = buffer.clone();
+        let buf_run = buffer.clone();
         let proj_run = project_path.clone();
-        let pdf_tool_clone = pdf_tool.clone();
-        btn_pdf.connect_clicked(move |_| {
-            if let Some(ref path) = *proj_run.borrow() {
-                append_text(&buf_pdf, &format!("Generating PDF report for {}\n", path));
-                let report_dir = format!("{}/html_report", path);
-                let index_uri = format!("file://{}/index.html", report_dir);
-                let pdf_file = format!("{}/report.pdf", path);
-                if let Some(ref tool) = pdf_tool_clone {
-                    if let Ok(_) = Command::new(tool)
-                        .args(&[
-                            "--headless",
-                            "--disable-gpu",
-                            &format!("--print-to-pdf={}", pdf_file),
-                            &index_uri,
-                        ])
-                        .output()
-                    {
-                        if Path::new(&pdf_file).exists() {
-                            append_text(&buf_pdf, &format!("PDF report saved to {}\n", pdf_file));
-                            let pdf_uri = format!("file://{}", pdf_file);
-                            if let Err(e) = AppInfo::launch_default_for_uri(
-                                &pdf_uri,
-                                None::<&gio::AppLaunchContext>,
-                            ) {
-                                append_text(
-                                    &buf_pdf,
-                                    &format!("Failed to open PDF report: {}\n", e),
-                                );
-                            }
-                        } else {
-                            append_text(&buf_pdf, "PDF report was not generated\n");
-                        }
-                    } else {
-                        append_text(&buf_pdf, "Error generating PDF report\n");
-                    }
-                } else {
-                    append_text(&buf_pdf, "No PDF utility available\n");
-                }
+        let scan_clone = scan_ui.clone();
+        btn_full.connect_clicked(move |_| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_run, "A scan is already running\n");
+                return;
+            }
+            if proj_run.borrow().is_none() {
+                append_text(&buf_run, "Select a project directory first\n");
+                return;
+            }
+            scan_clone.report_after.set(true);
+            scan_clone.run_btn.emit_clicked();
+            // The scan couldn't start; don't build a report after a later one
+            if scan_clone.active.borrow().is_none() {
+                scan_clone.report_after.set(false);
             }
         });
     }
-}
 
-// Helper to append text to the TextView buffer
-fn append_text(buffer: &TextBuffer, text: &str) {
-    let mut iter = buffer.end_iter();
-    buffer.insert(&mut iter, text);
+    // Scan a source archive, unpacked to a temporary directory
+    {
+        let buf_archive = buffer.clone();
+        let scan_clone = scan_ui.clone();
+        let suppressions_clone = suppressions.clone();
+        let controls_clone = controls.clone();
+        let win_clone = window.clone();
+        btn_archive.connect_clicked(move |btn| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_archive, "A scan is already running\n");
+                return;
+            }
+            let dialog = FileChooserDialog::builder()
+                .title(tr("Scan Archive"))
+                .action(FileChooserAction::Open)
+                .transient_for(&win_clone)
+                .modal(true)
+                .build();
+            let filter = FileFilter::new();
+            filter.set_name(Some("Source archives (*.zip, *.tar.gz, …)"));
+            for pattern in [
+                "*.zip",
+                "*.tar.gz",
+                "*.tgz",
+                "*.tar.bz2",
+                "*.tbz2",
+                "*.tar.xz",
+                "*.txz",
+                "*.tar",
+            ] {
+                filter.add_pattern(pattern);
+            }
+            dialog.add_filter(&filter);
+            dialog.add_buttons(&[
+                (tr("Cancel"), ResponseType::Cancel),
+                (tr("Scan"), ResponseType::Accept),
+            ]);
+            let btn = btn.clone();
+            let buf_inner = buf_archive.clone();
+            let scan_inner = scan_clone.clone();
+            let suppressions_inner = suppressions_clone.clone();
+            let controls_inner = controls_clone.clone();
+            dialog.connect_response(move |d, r| {
+                d.close();
+                if r != ResponseType::Accept {
+                    return;
+                }
+                let Some(path) = d.file().and_then(|f| f.path()) else {
+                    return;
+                };
+                if !archive::is_archive(&path) {
+                    append_text(
+                        &buf_inner,
+                        &format!("{} is not a supported archive\n", path.display()),
+                    );
+                    return;
+                }
+                let tool = archive::extract_tool(&path);
+                if !tools::is_on_path(tool) {
+                    report_tool_missing(&btn, tool, &buf_inner);
+                    return;
+                }
+                let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                let start = {
+                    let scan_inner = scan_inner.clone();
+                    let suppressions_inner = suppressions_inner.clone();
+                    let controls_inner = controls_inner.clone();
+                    let btn = btn.clone();
+                    move || {
+                        scan_archive(
+                            &scan_inner,
+                            &controls_inner,
+                            suppressions_inner.path(),
+                            &btn,
+                            path.clone(),
+                        )
+                    }
+                };
+                if size < archive::LARGE_ARCHIVE {
+                    start();
+                    return;
+                }
+                // Big archives take long to unpack and fill the temp dir
+                let parent = btn.root().and_downcast::<gtk4::Window>();
+                let confirm = MessageDialog::new(
+                    parent.as_ref(),
+                    DialogFlags::MODAL,
+                    MessageType::Question,
+                    ButtonsType::YesNo,
+                    "Unpack a large archive?",
+                );
+                confirm.set_secondary_text(Some(&format!(
+                    "The archive is {} MB. Unpacking it into {} and scanning it may take \
+                     a long time and needs at least as much free disk space.",
+                    size / (1024 * 1024),
+                    env::temp_dir().display()
+                )));
+                confirm.connect_response(move |c, r| {
+                    c.close();
+                    if r == ResponseType::Yes {
+                        start();
+                    }
+                });
+                confirm.show();
+            });
+            dialog.show();
+        });
+    }
+
+    // Stop the running scan, optionally starting over
+    {
+        let scan_clone = scan_ui.clone();
+        btn_cancel.connect_clicked(move |_| {
+            if let Some(ref handle) = *scan_clone.active.borrow() {
+                handle.cancel();
+            }
+        });
+    }
+    {
+        let scan_clone = scan_ui.clone();
+        btn_restart.connect_clicked(move |_| scan_clone.restart());
+    }
+
+    // With Config::rescan_on_change, scan again once the checks, threshold
+    // or output format have stayed put for RESCAN_DELAY. Changes are
+    // measured against the settings of the last run, so opening a project
+    // doesn't count as one
+    {
+        let generation = Rc::new(Cell::new(0u32));
+        let schedule: Rc<dyn Fn()> = {
+            let proj_run = project_path.clone();
+            let scan_clone = scan_ui.clone();
+            let controls_clone = controls.clone();
+            let run_clone = btn_run.clone();
+            Rc::new(move || {
+                if !scan_clone.config.borrow().rescan_on_change {
+                    return;
+                }
+                let current = generation.get().wrapping_add(1);
+                generation.set(current);
+                let generation = generation.clone();
+                let proj_run = proj_run.clone();
+                let scan_clone = scan_clone.clone();
+                let controls_clone = controls_clone.clone();
+                let run_clone = run_clone.clone();
+                // Checked again every RESCAN_DELAY while a queue, comparison
+                // or diagnosis is running, so the change isn't lost
+                glib::timeout_add_local(RESCAN_DELAY, move || {
+                    if generation.get() != current {
+                        return ControlFlow::Break;
+                    }
+                    let Some(ref path) = *proj_run.borrow() else {
+                        return ControlFlow::Break;
+                    };
+                    let changed = scan_clone
+                        .config
+                        .borrow()
+                        .project(path)
+                        .is_some_and(|last| *last != controls_clone.settings());
+                    if !changed {
+                        return ControlFlow::Break;
+                    }
+                    if scan_clone.queue.borrow().is_running() {
+                        return ControlFlow::Continue;
+                    }
+                    if scan_clone.active.borrow().is_none() && run_clone.is_sensitive() {
+                        log_info(&scan_clone.log, "Scan settings changed, rescanning\n");
+                        run_clone.emit_clicked();
+                    } else if scan_clone.plain_run.get() && scan_clone.restart_btn.is_sensitive() {
+                        // Other scans would restart with their own settings
+                        log_info(&scan_clone.log, "Scan settings changed, restarting scan\n");
+                        scan_clone.restart();
+                    } else {
+                        return ControlFlow::Continue;
+                    }
+                    ControlFlow::Break
+                });
+            })
+        };
+        for chk in [
+            &controls.chk_warning,
+            &controls.chk_style,
+            &controls.chk_performance,
+            &controls.chk_clang,
+            &controls.chk_unused,
+            &controls.chk_bug_hunting,
+            &controls.chk_headers,
+            &controls.chk_aggressive,
+        ] {
+            let schedule = schedule.clone();
+            chk.connect_toggled(move |_| schedule());
+        }
+        {
+            let schedule = schedule.clone();
+            controls
+                .threshold
+                .connect_value_changed(move |_| schedule());
+        }
+        controls.template_combo.connect_changed(move |_| schedule());
+    }
+
+    // Re-check just the files with errors, e.g. to verify fixes
+    {
+        let buf_run = buffer.clone();
+        let proj_run = project_path.clone();
+        let scan_clone = scan_ui.clone();
+        let suppressions_clone = suppressions.clone();
+        let controls_clone = controls.clone();
+        let results_clone = results.clone();
+        btn_rerun_errors.connect_clicked(move |_| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_run, "A scan is already running\n");
+                return;
+            }
+            // Finding paths may be relative to another directory or in an archive
+            let files: Vec<String> = results_clone
+                .files_with(Severity::Error)
+                .iter()
+                .map(|f| results_clone.source_path(f))
+                .collect();
+            if files.is_empty() {
+                append_text(&buf_run, "No files with error findings to re-run\n");
+                return;
+            }
+            log_info(
+                &buf_run,
+                &format!(
+                    "Re-running cppcheck on {} file(s) with errors\n",
+                    files.len()
+                ),
+            );
+            let settings = controls_clone.settings();
+            let (mut cmd, parser) = controls_clone.command(
+                &settings,
+                suppressions_clone.path(),
+                &scan_clone.config.borrow(),
+                &buf_run,
+            );
+            if let Some(ref path) = *proj_run.borrow() {
+                let Some(dir) = working_dir(&settings, path, &buf_run) else {
+                    return;
+                };
+                cmd.current_dir(dir);
+                add_build_dir(&mut cmd, &settings, path, &buf_run);
+                if let Some(arg) = relative_paths_arg(&settings, path) {
+                    cmd.arg(arg);
+                }
+            }
+            cmd.args(&files);
+            scan_clone.start(cmd, parser, files.len());
+        });
+    }
+
+    // Configuration check, kept apart from the normal run and its results
+    {
+        let proj_run = project_path.clone();
+        let scan_clone = scan_ui.clone();
+        btn_diagnose.connect_clicked(move |_| {
+            if let Some(ref path) = *proj_run.borrow() {
+                scan_clone.diagnose(path);
+            }
+        });
+    }
+
+    // One file of the project scanned, its findings narrowed to a range of
+    // lines, e.g. a single troublesome function
+    {
+        let win_clone = window.clone();
+        let buf_run = buffer.clone();
+        let proj_run = project_path.clone();
+        let scan_clone = scan_ui.clone();
+        let suppressions_clone = suppressions.clone();
+        let controls_clone = controls.clone();
+        btn_line_range.connect_clicked(move |_| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_run, "A scan is already running\n");
+                return;
+            }
+            let Some(path) = proj_run.borrow().clone() else {
+                append_text(&buf_run, "Select a project to check a line range\n");
+                return;
+            };
+            let buf_run = buf_run.clone();
+            let scan_clone = scan_clone.clone();
+            let suppressions_clone = suppressions_clone.clone();
+            let controls_clone = controls_clone.clone();
+            line_range::choose(&win_clone, &path.clone(), move |range| {
+                let settings = controls_clone.settings();
+                let Some(dir) = working_dir(&settings, &path, &buf_run) else {
+                    return;
+                };
+                log_info(
+                    &buf_run,
+                    &format!("Checking {} of {}\n", range.describe(), path),
+                );
+                let (mut cmd, parser) = controls_clone.command(
+                    &settings,
+                    suppressions_clone.path(),
+                    &scan_clone.config.borrow(),
+                    &buf_run,
+                );
+                add_build_dir(&mut cmd, &settings, &path, &buf_run);
+                if let Some(arg) = relative_paths_arg(&settings, &path) {
+                    cmd.arg(arg);
+                }
+                cmd.current_dir(dir);
+                if settings.compile_database.is_empty() {
+                    cmd.arg(&range.file);
+                } else {
+                    // Keep the include paths and defines the database gives it
+                    let database = Path::new(&path).join(&settings.compile_database);
+                    cmd.arg(format!("--project={}", database.display()));
+                    cmd.arg(format!("--file-filter={}", range.file));
+                }
+                scan_clone.start(cmd, parser, 1);
+                scan_clone.results.set_line_range(Some(range));
+            });
+        });
+    }
+
+    // The project scanned once per chosen --std, then the two diffed
+    {
+        let win_clone = window.clone();
+        let buf_run = buffer.clone();
+        let proj_run = project_path.clone();
+        let scan_clone = scan_ui.clone();
+        let suppressions_clone = suppressions.clone();
+        let controls_clone = controls.clone();
+        btn_compare_std.connect_clicked(move |_| {
+            if scan_clone.active.borrow().is_some() {
+                append_text(&buf_run, "A scan is already running\n");
+                return;
+            }
+            let Some(path) = proj_run.borrow().clone() else {
+                append_text(&buf_run, "Select a project to compare standards\n");
+                return;
+            };
+            let win = win_clone.clone();
+            let buf_run = buf_run.clone();
+            let scan_clone = scan_clone.clone();
+            let suppressions_clone = suppressions_clone.clone();
+            let controls_clone = controls_clone.clone();
+            compare::choose_standards(&win_clone, move |from, to| {
+                if from == to {
+                    append_text(&buf_run, "Choose two different standards to compare\n");
+                    return;
+                }
+                let settings = controls_clone.settings();
+                let Some(dir) = working_dir(&settings, &path, &buf_run) else {
+                    return;
+                };
+                let mut runs = VecDeque::new();
+                let mut parser = Template::default();
+                for std in [from, to] {
+                    let (mut cmd, template) = controls_clone.command(
+                        &settings,
+                        suppressions_clone.path(),
+                        &scan_clone.config.borrow(),
+                        &buf_run,
+                    );
+                    // No build dir: its cached results would carry over
+                    // from one standard to the other
+                    cmd.arg(format!("--std={}", std));
+                    cmd.current_dir(&dir);
+                    add_scan_target(&mut cmd, &settings, &path, &buf_run);
+                    runs.push_back((std.to_string(), cmd));
+                    parser = template;
+                }
+                log_info(
+                    &buf_run,
+                    &format!("Comparing {} with {} on {}\n", from, to, path),
+                );
+                scan_clone.compare_runs(&win, runs, parser, Vec::new());
+            });
+        });
+    }
+
+    // History of recent invocations, rebuilt each time it opens
+    {
+        let scan_clone = scan_ui.clone();
+        history_btn.set_create_popup_func(move |btn| {
+            let Some(popover) = btn.popover() else {
+                return;
+            };
+            let list = GtkBox::new(Orientation::Vertical, 4);
+            let history = scan_clone.config.borrow().history.clone();
+            if history.is_empty() {
+                list.append(&Label::new(Some(tr("No scans yet"))));
+            }
+            let idle = scan_clone.active.borrow().is_none();
+            for entry in history {
+                let command = scan::command_line(&entry.args);
+                let row = GtkBox::new(Orientation::Horizontal, 8);
+                let label = Label::new(Some(&format!("{}  {}", entry.time, command)));
+                label.set_xalign(0.0);
+                label.set_hexpand(true);
+                label.set_selectable(true);
+                label.set_wrap(true);
+                label.set_max_width_chars(80);
+                let copy_btn = Button::with_label(tr("Copy"));
+                {
+                    let popover_inner = popover.clone();
+                    copy_btn.connect_clicked(move |b| {
+                        b.clipboard().set_text(&command);
+                        popover_inner.popdown();
+                    });
+                }
+                let rerun_btn = Button::with_label(tr("Re-run"));
+                rerun_btn.set_sensitive(idle);
+                {
+                    let popover_inner = popover.clone();
+                    let scan_inner = scan_clone.clone();
+                    rerun_btn.connect_clicked(move |_| {
+                        popover_inner.popdown();
+                        scan_inner.rerun(&entry.args);
+                    });
+                }
+                row.append(&label);
+                row.append(&copy_btn);
+                row.append(&rerun_btn);
+                list.append(&row);
+            }
+            let scrolled = ScrolledWindow::new();
+            scrolled.set_propagate_natural_height(true);
+            scrolled.set_max_content_height(400);
+            scrolled.set_child(Some(&list));
+            popover.set_child(Some(&scrolled));
+        });
+    }
+
+    {
+        let win_clone = window.clone();
+        let controls_clone = controls.clone();
+        let buf_clone = buffer.clone();
+        let popover_clone = profile_popover.clone();
+        btn_export_profile.connect_clicked(move |_| {
+            popover_clone.popdown();
+            export_profile(&win_clone, &controls_clone, &buf_clone);
+        });
+    }
+    {
+        let win_clone = window.clone();
+        let controls_clone = controls.clone();
+        let config_clone = config.clone();
+        let proj_clone = project_path.clone();
+        let buf_clone = buffer.clone();
+        let popover_clone = profile_popover.clone();
+        btn_import_profile.connect_clicked(move |_| {
+            popover_clone.popdown();
+            import_profile(
+                &win_clone,
+                &controls_clone,
+                &config_clone,
+                &proj_clone,
+                &buf_clone,
+            );
+        });
+    }
+    {
+        let win_clone = window.clone();
+        let controls_clone = controls.clone();
+        let config_clone = config.clone();
+        let proj_clone = project_path.clone();
+        let suppressions_clone = suppressions.clone();
+        let buf_clone = buffer.clone();
+        let popover_clone = profile_popover.clone();
+        btn_export_project.connect_clicked(move |_| {
+            popover_clone.popdown();
+            let Some(ref project) = *proj_clone.borrow() else {
+                append_text(&buf_clone, "Select a project directory first\n");
+                return;
+            };
+            export_cppcheck_project(
+                &win_clone,
+                project,
+                &controls_clone.settings(),
+                config_clone.borrow().max_ctu_depth,
+                &suppressions_clone.entries(),
+                &buf_clone,
+            );
+        });
+    }
+
+    for path in not_found {
+        append_text(
+            &buffer,
+            &format!("No such file or directory: {}\n", path.display()),
+        );
+    }
+    if let Some(ref path) = initial_project {
+        open_project(path, false);
+    }
+
+    // F8 / Shift+F8 walk through the findings like an IDE's error list
+    {
+        let shortcuts = ShortcutController::new();
+        for (trigger, forward) in [("F8", true), ("<Shift>F8", false)] {
+            let results_clone = results.clone();
+            let status_clone = status.clone();
+            let action = CallbackAction::new(move |_, _| {
+                match results_clone.select_adjacent(forward) {
+                    None => status_clone.set_text("No findings"),
+                    Some(true) if forward => status_clone.set_text("Wrapped to the first finding"),
+                    Some(true) => status_clone.set_text("Wrapped to the last finding"),
+                    Some(false) => {}
+                }
+                glib::Propagation::Stop
+            });
+            shortcuts.add_shortcut(Shortcut::new(
+                ShortcutTrigger::parse_string(trigger),
+                Some(action),
+            ));
+        }
+        window.add_controller(shortcuts);
+    }
+
+    // Ctrl+P lists every action by name, for the keyboard and for features
+    // that have no room for a button of their own
+    {
+        let mut actions: Vec<command_palette::Action> = [
+            &btn_run,
+            &btn_quick,
+            &btn_full,
+            &btn_cancel,
+            &btn_restart,
+            &btn_rerun_errors,
+            &btn_html,
+            &btn_pdf,
+            &btn_open_report,
+            &btn_diagnose,
+            &btn_line_range,
+            &btn_queue,
+            &btn_compare,
+            &btn_compare_std,
+            &btn_settings,
+            &btn_usage,
+            &btn_save_log,
+        ]
+        .into_iter()
+        .map(command_palette::Action::button)
+        .collect();
+        {
+            // The button's label turns into the project path once one is open
+            let select_clone = select_btn.clone();
+            actions.push(command_palette::Action::new(
+                tr("Select Project Directory"),
+                || true,
+                move || select_clone.emit_clicked(),
+            ));
+        }
+        for format in export::Format::ALL {
+            let results_clone = results.clone();
+            actions.push(command_palette::Action::new(
+                &format!("Export {}", format.name()),
+                || true,
+                move || results_clone.choose_export_file(format),
+            ));
+        }
+        {
+            let paste_clone = paste_project.clone();
+            actions.push(command_palette::Action::new(
+                tr("Paste Project Path"),
+                || true,
+                move || paste_clone(),
+            ));
+        }
+        {
+            let pause_clone = btn_pause_log.clone();
+            actions.push(command_palette::Action::new(
+                tr("Pause or Resume Output"),
+                || true,
+                move || pause_clone.set_active(!pause_clone.is_active()),
+            ));
+        }
+        {
+            // The copy on disk keeps the cleared lines for Save Log
+            let buf_clone = buffer.clone();
+            actions.push(command_palette::Action::new(
+                tr("Clear Log"),
+                || true,
+                move || buf_clone.set_text(""),
+            ));
+        }
+        let actions = Rc::new(actions);
+        let win_clone = window.clone();
+        let shortcuts = ShortcutController::new();
+        shortcuts.add_shortcut(Shortcut::new(
+            ShortcutTrigger::parse_string("<Control>p"),
+            Some(CallbackAction::new(move |_, _| {
+                command_palette::show(&win_clone, &actions);
+                glib::Propagation::Stop
+            })),
+        ));
+        window.add_controller(shortcuts);
+    }
+
+    // Labels are translated when the widgets are built, so a new language
+    // takes a fresh window
+    {
+        let config_clone = config.clone();
+        let proj_clone = project_path.clone();
+        let active_clone = active_scan.clone();
+        let buf_clone = buffer.clone();
+        let win_clone = window.clone();
+        let app_clone = app.clone();
+        lang_combo.connect_changed(move |c| {
+            let Some(code) = c.active_id() else {
+                return;
+            };
+            {
+                let mut cfg = config_clone.borrow_mut();
+                cfg.language = code.to_string();
+                if let Err(e) = cfg.save() {
+                    append_text(&buf_clone, &format!("Failed to save settings: {}\n", e));
+                }
+            }
+            if active_clone.borrow().is_some() {
+                append_text(
+                    &buf_clone,
+                    "The new language applies to windows opened after this scan\n",
+                );
+                return;
+            }
+            build_ui(&app_clone, proj_clone.borrow().clone(), &[]);
+            win_clone.close();
+        });
+    }
+
+    // Ask before closing the window in the middle of a scan
+    {
+        let active_clone = active_scan.clone();
+        window.connect_close_request(move |win| {
+            let Some(handle) = active_clone.borrow().clone() else {
+                return glib::Propagation::Proceed;
+            };
+            let dialog = MessageDialog::new(
+                Some(win),
+                DialogFlags::MODAL,
+                MessageType::Warning,
+                ButtonsType::None,
+                "A scan is still running",
+            );
+            dialog.set_secondary_text(Some(
+                "Quitting now stops cppcheck and discards this scan's results.",
+            ));
+            dialog.add_buttons(&[
+                (tr("Keep Scanning"), ResponseType::Cancel),
+                (tr("Cancel Scan and Quit"), ResponseType::Accept),
+            ]);
+            let win_clone = win.clone();
+            dialog.connect_response(move |d, r| {
+                d.close();
+                if r == ResponseType::Accept {
+                    handle.cancel();
+                    win_clone.destroy();
+                }
+            });
+            dialog.show();
+            glib::Propagation::Stop
+        });
+    }
+
+    // Report bases whose reports go to the cache dir instead, once the user
+    // agreed because they aren't writable
+    let report_fallback = Rc::new(RefCell::new(HashSet::<String>::new()));
+
+    // Generate HTML report logic; the slow steps run on a worker thread
+    {
+        let buf_html = buffer.clone();
+        let fallback_clone = report_fallback.clone();
+        let proj_run = project_path.clone();
+        let suppressions_clone = suppressions.clone();
+        let config_clone = config.clone();
+        let results_clone = results.clone();
+        let opened_clone = opened_xml.clone();
+        let status_clone = status.clone();
+        let open_report_clone = btn_open_report.clone();
+        let last_clone = last_report.clone();
+        let controls_clone = controls.clone();
+        let scan_xml_clone = scan_ui.scan_xml.clone();
+        btn_html.connect_clicked(move |btn| {
+            // Left by a Full Report scan
+            let scanned_xml = scan_xml_clone.take();
+            let opened = opened_clone.borrow().clone();
+            let Some(path) = report_base(&proj_run.borrow(), &opened) else {
+                return;
+            };
+            // PATH may have changed since startup
+            if !tools::is_on_path("cppcheck-htmlreport") {
+                report_tool_missing(btn, "cppcheck-htmlreport", &buf_html);
+                return;
+            }
+            log_info(&buf_html, &format!("Generating HTML report for {}\n", path));
+            let project_name = Path::new(&path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("project")
+                .to_string();
+            let out_dir = report_output_dir(&path, &fallback_clone.borrow());
+            let report_dir = out_dir.join("html_report");
+            if let Err(e) = fs::create_dir_all(&report_dir) {
+                report_write_failed(btn, &path, &report_dir, &e, &fallback_clone, &buf_html);
+                status_clone.set_text("HTML report failed");
+                return;
+            }
+            // A kept XML stays with the report; otherwise it goes to a
+            // private temp dir that is removed once the report is built
+            let keep_xml = config_clone.borrow().keep_xml;
+            let xml_dir = match scanned_xml.as_ref().and_then(|xml| xml.parent()) {
+                _ if keep_xml => out_dir.clone(),
+                Some(dir) => dir.to_path_buf(),
+                None => temp_dir().join(format!("report-{}", glib::monotonic_time())),
+            };
+            let events = html_report::spawn(html_report::Job {
+                source_dir: path.clone(),
+                opened_xml: opened,
+                scanned_xml,
+                xml_dir,
+                keep_xml,
+                report_dir: report_dir.clone(),
+                suppressions_list: suppressions_clone.path(),
+                suppressed_dirs: config_clone.borrow().suppressed_dirs(),
+                command_prefix: config_clone.borrow().command_prefix.clone(),
+                env: config_clone.borrow().cppcheck_env.clone(),
+                memory_limit_mb: config_clone.borrow().memory_limit_mb,
+                low_priority: config_clone.borrow().low_priority,
+                relative_paths: relative_paths_arg(&controls_clone.settings(), &path),
+                title: format!("Cppcheck report - {}", project_name),
+                debug: LOG_LEVEL.with(Cell::get) == LogLevel::Debug,
+            });
+            btn.set_sensitive(false);
+            let mut busy = Some(BusyCursor::new(btn));
+
+            let btn = btn.clone();
+            let buf_html = buf_html.clone();
+            let fallback_clone = fallback_clone.clone();
+            let results_clone = results_clone.clone();
+            let status_clone = status_clone.clone();
+            let config_clone = config_clone.clone();
+            let open_report_clone = open_report_clone.clone();
+            let last_clone = last_clone.clone();
+            glib::timeout_add_local(Duration::from_millis(50), move || loop {
+                match events.try_recv() {
+                    Ok(html_report::Event::Stage(stage)) => status_clone.set_text(stage),
+                    Ok(html_report::Event::Log(text)) => append_text(&buf_html, &text),
+                    Ok(html_report::Event::Details(details)) => results_clone.add_details(&details),
+                    Ok(html_report::Event::Finished(result)) => {
+                        busy.take();
+                        btn.set_sensitive(true);
+                        match result {
+                            Ok(size) => {
+                                append_text(
+                                    &buf_html,
+                                    &format!(
+                                        "HTML report saved to {} ({})\n",
+                                        report_dir.display(),
+                                        size
+                                    ),
+                                );
+                                status_clone.set_text("HTML report generated");
+                                let index_uri =
+                                    file_uri(&report_dir.join("index.html").to_string_lossy());
+                                report_ready(&open_report_clone, &last_clone, &index_uri);
+                                if !config_clone.borrow().open_reports {
+                                    log_info(&buf_html, "Open it with Open Report\n");
+                                } else if size.is_large() {
+                                    confirm_open_report(&btn, index_uri, size, &buf_html);
+                                } else {
+                                    open_report(&index_uri, &buf_html);
+                                }
+                            }
+                            Err(html_report::Error::Write(target, e)) => {
+                                report_write_failed(
+                                    &btn,
+                                    &path,
+                                    &target,
+                                    &e,
+                                    &fallback_clone,
+                                    &buf_html,
+                                );
+                                status_clone.set_text("HTML report failed");
+                            }
+                            Err(html_report::Error::Failed) => {
+                                status_clone.set_text("HTML report failed");
+                            }
+                        }
+                        return ControlFlow::Break;
+                    }
+                    Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                    Err(TryRecvError::Disconnected) => {
+                        busy.take();
+                        btn.set_sensitive(true);
+                        return ControlFlow::Break;
+                    }
+                }
+            });
+        });
+    }
+
+    // Generate PDF report logic
+    {
+        let buf_pdf = buffer.clone();
+        let proj_run = project_path.clone();
+        let fallback_clone = report_fallback.clone();
+        let opened_clone = opened_xml.clone();
+        let status_clone = status.clone();
+        let config_clone = config.clone();
+        let open_report_clone = btn_open_report.clone();
+        let last_clone = last_report.clone();
+        btn_pdf.connect_clicked(move |btn| {
+            if let Some(ref path) = report_base(&proj_run.borrow(), &opened_clone.borrow()) {
+                // Resolved per click so a browser installed later is found
+                let Some(tool) = tools::pdf_tool() else {
+                    report_tool_missing(btn, tools::PDF_TOOLS[0], &buf_pdf);
+                    return;
+                };
+                log_info(&buf_pdf, &format!("Generating PDF report for {}\n", path));
+                let out_dir = report_output_dir(path, &fallback_clone.borrow());
+                let index_uri = file_uri(&out_dir.join("html_report/index.html").to_string_lossy());
+                let pdf_file = out_dir.join("report.pdf").to_string_lossy().to_string();
+                let mut cmd = Command::new(tool);
+                cmd.args([
+                    "--headless",
+                    "--disable-gpu",
+                    &format!("--print-to-pdf={}", pdf_file),
+                    &index_uri,
+                ]);
+                log_command(&buf_pdf, &cmd);
+                // A stale PDF would pass for a fresh one
+                let _ = fs::remove_file(&pdf_file);
+                let attempts = config_clone.borrow().pdf_attempts.max(1);
+                // The browser can take a while; run it off the main loop
+                let (sender, events) = mpsc::channel();
+                {
+                    let pdf_file = pdf_file.clone();
+                    std::thread::spawn(move || {
+                        let mut ran = false;
+                        for attempt in 1..=attempts {
+                            let _ = sender.send(PdfEvent::Attempt(attempt));
+                            match cmd.output() {
+                                Ok(_) => ran = true,
+                                Err(e) => {
+                                    let _ = sender.send(PdfEvent::RunFailed(e.to_string()));
+                                }
+                            }
+                            if Path::new(&pdf_file).exists() {
+                                break;
+                            }
+                        }
+                        let _ = sender.send(PdfEvent::Finished(ran));
+                    });
+                }
+                btn.set_sensitive(false);
+                let mut busy = Some(BusyCursor::new(btn));
+                let btn = btn.clone();
+                let buf_pdf = buf_pdf.clone();
+                let status_clone = status_clone.clone();
+                let config_clone = config_clone.clone();
+                let open_report_clone = open_report_clone.clone();
+                let last_clone = last_clone.clone();
+                glib::timeout_add_local(Duration::from_millis(50), move || loop {
+                    let ran = match events.try_recv() {
+                        Ok(PdfEvent::Attempt(attempt)) => {
+                            if attempts > 1 {
+                                log_info(
+                                    &buf_pdf,
+                                    &format!("PDF export, attempt {} of {}\n", attempt, attempts),
+                                );
+                            }
+                            continue;
+                        }
+                        Ok(PdfEvent::RunFailed(e)) => {
+                            append_text(&buf_pdf, &format!("Failed to run {}: {}\n", tool, e));
+                            continue;
+                        }
+                        Ok(PdfEvent::Finished(ran)) => ran,
+                        Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                        Err(TryRecvError::Disconnected) => false,
+                    };
+                    busy.take();
+                    btn.set_sensitive(true);
+                    if !ran {
+                        append_text(&buf_pdf, "Error generating PDF report\n");
+                        status_clone.set_text("PDF report failed");
+                    } else if Path::new(&pdf_file).exists() {
+                        append_text(&buf_pdf, &format!("PDF report saved to {}\n", pdf_file));
+                        status_clone.set_text("PDF report generated");
+                        let pdf_uri = file_uri(&pdf_file);
+                        report_ready(&open_report_clone, &last_clone, &pdf_uri);
+                        if !config_clone.borrow().open_reports {
+                            log_info(&buf_pdf, "Open it with Open Report\n");
+                        } else if let Err(e) = AppInfo::launch_default_for_uri(
+                            &pdf_uri,
+                            None::<&gio::AppLaunchContext>,
+                        ) {
+                            append_text(&buf_pdf, &format!("Failed to open PDF report: {}\n", e));
+                        }
+                    } else {
+                        append_text(
+                            &buf_pdf,
+                            &format!(
+                                "PDF report was not generated after {} attempt(s)\n",
+                                attempts
+                            ),
+                        );
+                        status_clone.set_text("PDF report failed");
+                    }
+                    return ControlFlow::Break;
+                });
+            }
+        });
+    }
+
+    {
+        let buf_open = buffer.clone();
+        let last_clone = last_report.clone();
+        btn_open_report.connect_clicked(move |_| {
+            if let Some(ref uri) = *last_clone.borrow() {
+                if let Err(e) = AppInfo::launch_default_for_uri(uri, None::<&gio::AppLaunchContext>)
+                {
+                    append_text(&buf_open, &format!("Failed to open {}: {}\n", uri, e));
+                }
+            }
+        });
+    }
+
+    // Generated reports of the current project, rebuilt each time it opens
+    {
+        let buf_copy = buffer.clone();
+        let proj_run = project_path.clone();
+        let fallback_clone = report_fallback.clone();
+        let opened_clone = opened_xml.clone();
+        let config_clone = config.clone();
+        copy_path_btn.set_create_popup_func(move |btn| {
+            let Some(popover) = btn.popover() else {
+                return;
+            };
+            let list = GtkBox::new(Orientation::Vertical, 4);
+            let opened = opened_clone.borrow().clone();
+            let paths = match report_base(&proj_run.borrow(), &opened) {
+                Some(base) => report_paths(
+                    &report_output_dir(&base, &fallback_clone.borrow()),
+                    &opened,
+                    config_clone.borrow().keep_xml,
+                ),
+                None => Vec::new(),
+            };
+            if paths.is_empty() {
+                list.append(&Label::new(Some(tr("No reports generated yet"))));
+            }
+            for (what, path) in paths {
+                let path = path.to_string_lossy().to_string();
+                let item = Button::with_label(&format!("{}: {}", what, path));
+                item.set_has_frame(false);
+                if let Some(label) = item.child().and_downcast::<Label>() {
+                    label.set_xalign(0.0);
+                }
+                let popover_inner = popover.clone();
+                let buf_inner = buf_copy.clone();
+                item.connect_clicked(move |b| {
+                    b.clipboard().set_text(&path);
+                    log_info(&buf_inner, &format!("Copied {}\n", path));
+                    popover_inner.popdown();
+                });
+                list.append(&item);
+            }
+            popover.set_child(Some(&list));
+        });
+    }
+}
+
+// Reports that exist in `out_dir` (see report_output_dir), as absolute
+// paths: the HTML report directory and its index.html, the PDF, and the
+// XML results when they are kept or were opened
+fn report_paths(
+    out_dir: &Path,
+    opened_xml: &Option<String>,
+    keep_xml: bool,
+) -> Vec<(&'static str, PathBuf)> {
+    let report_dir = out_dir.join("html_report");
+    let xml = match opened_xml {
+        Some(xml) => Some(PathBuf::from(xml)),
+        None if keep_xml => Some(out_dir.join("cppcheck.xml")),
+        None => None,
+    };
+    [
+        ("HTML report", Some(report_dir.clone())),
+        ("index.html", Some(report_dir.join("index.html"))),
+        ("PDF", Some(out_dir.join("report.pdf"))),
+        ("XML", xml),
+    ]
+    .into_iter()
+    .filter_map(|(what, path)| Some((what, fs::canonicalize(path?).ok()?)))
+    .collect()
+}
+
+// Directory reports are written to and whose sources they show: the
+// project, or the folder of an opened XML report when no project is set
+fn report_base(project: &Option<String>, opened_xml: &Option<String>) -> Option<String> {
+    project.clone().or_else(|| {
+        let xml = opened_xml.as_ref()?;
+        Some(Path::new(xml).parent()?.to_string_lossy().to_string())
+    })
+}
+
+// Directory the reports of `base` (see report_base) are written to
+fn report_output_dir(base: &str, fallback: &HashSet<String>) -> PathBuf {
+    if fallback.contains(base) {
+        fallback_report_dir(base)
+    } else {
+        PathBuf::from(base)
+    }
+}
+
+// Writable stand-in for a read-only report base
+fn fallback_report_dir(base: &str) -> PathBuf {
+    let name = Path::new(base).file_name().unwrap_or_default();
+    glib::user_cache_dir()
+        .join("cppcheck_gui")
+        .join("reports")
+        .join(name)
+}
+
+// Log a failed report write under `base`. When the permissions are at fault,
+// offer to put its reports in the cache dir and, if accepted, retry via
+// `button`
+fn report_write_failed(
+    button: &Button,
+    base: &str,
+    target: &Path,
+    error: &io::Error,
+    fallback: &Rc<RefCell<HashSet<String>>>,
+    log: &TextBuffer,
+) {
+    append_text(
+        log,
+        &format!("Failed to write {}: {}\n", target.display(), error),
+    );
+    let permission = matches!(
+        error.kind(),
+        io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+    );
+    if !permission || fallback.borrow().contains(base) {
+        return;
+    }
+    let fallback_dir = fallback_report_dir(base);
+    let parent = button.root().and_downcast::<gtk4::Window>();
+    let dialog = MessageDialog::new(
+        parent.as_ref(),
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::YesNo,
+        "The project directory is not writable",
+    );
+    dialog.set_secondary_text(Some(&format!(
+        "Reports are written next to the sources, but {} can't be written ({}). \
+         Write this session's reports to {} instead?",
+        target.display(),
+        error,
+        fallback_dir.display()
+    )));
+    let button = button.clone();
+    let base = base.to_string();
+    let fallback = fallback.clone();
+    let log = log.clone();
+    dialog.connect_response(move |d, r| {
+        d.close();
+        if r == ResponseType::Yes {
+            append_text(
+                &log,
+                &format!("Writing reports to {}\n", fallback_dir.display()),
+            );
+            fallback.borrow_mut().insert(base.clone());
+            button.emit_clicked();
+        }
+    });
+    dialog.show();
+}
+
+// Explain in a dialog (and the log) that a report tool is not installed
+fn report_tool_missing(button: &Button, tool: &str, log: &TextBuffer) {
+    let hint = tools::install_hint(tool);
+    append_text(log, &format!("{} not found. {}\n", tool, hint));
+    let parent = button.root().and_downcast::<gtk4::Window>();
+    let dialog = MessageDialog::new(
+        parent.as_ref(),
+        DialogFlags::MODAL,
+        MessageType::Warning,
+        ButtonsType::Ok,
+        format!("{} is not installed", tool),
+    );
+    dialog.set_secondary_text(Some(&hint));
+    dialog.connect_response(|d, _| d.close());
+    dialog.show();
+}
+
+// Widgets and state a running scan reports to
+#[derive(Clone)]
+struct ScanUi {
+    log: TextBuffer,
+    // Panel for the output of scans run with scan::DEBUG_FLAG
+    debug: Expander,
+    debug_log: TextBuffer,
+    progress: ProgressBar,
+    progress_label: Label,
+    resources: Label,
+    // Live findings per severity, with Config::live_counts
+    counts: Label,
+    status: Label,
+    results: ResultsView,
+    run_btn: Button,
+    diagnose_btn: Button,
+    cancel_btn: Button,
+    restart_btn: Button,
+    // What follows once the cancelled scan has finished
+    restart: Rc<Cell<Restart>>,
+    // Command of the scan started last and the XML it writes, for
+    // Restart::SameCommand
+    last_run: Rc<RefCell<Option<ScanRun>>>,
+    // --output-file of the scan about to start, taken by start(); then the
+    // XML of a completed Full Report scan, taken by the HTML report
+    scan_xml: Rc<RefCell<Option<PathBuf>>>,
+    // Whether that scan came from Run, so a restart can use the current
+    // settings instead
+    plain_run: Rc<Cell<bool>>,
+    // Build and open the HTML report once the scan has completed
+    report_after: Rc<Cell<bool>>,
+    // Last unpacked archive; its findings are shown with archive paths
+    archive: Rc<RefCell<Option<archive::Extracted>>>,
+    html_btn: Button,
+    pdf_btn: Button,
+    active: Rc<RefCell<Option<ScanHandle>>>,
+    opened_xml: Rc<RefCell<Option<String>>>,
+    config: Rc<RefCell<Config>>,
+    // Projects scanned one after another; each finished scan starts the
+    // next through open_project
+    queue: Rc<RefCell<ScanQueue>>,
+    open_project: OpenProject,
+    // Banners above the scan settings, and the chooser they may point to
+    notices: GtkBox,
+    select_btn: Button,
+}
+
+impl ScanUi {
+    // Spawn `cmd`, record it in the history and stream its output into the
+    // log and results; `total` is the number of files expected
+    fn start(&self, cmd: Command, parser: Template, total: usize) {
+        let args: Vec<String> = std::iter::once(cmd.get_program())
+            .chain(cmd.get_args())
+            .map(|a| a.to_string_lossy().to_string())
+            .collect();
+        log_command(&self.log, &cmd);
+        let debug = args.iter().any(|a| a == scan::DEBUG_FLAG);
+        // Finding paths are relative to where cppcheck runs
+        let scan_dir = cmd.get_current_dir().map(PathBuf::from);
+        // An archive scan runs in the unpacked root, which has no history
+        let from_archive = self
+            .archive
+            .borrow()
+            .as_ref()
+            .is_some_and(|a| scan_dir.as_deref() == Some(a.root.as_path()));
+        let source_base = scan::source_base(&cmd);
+        let mut xml_output = self.scan_xml.take();
+        // cppcheck doesn't create the directory of its --output-file
+        if let Some(dir) = xml_output.as_ref().and_then(|xml| xml.parent()) {
+            if let Err(e) = fs::create_dir_all(dir) {
+                append_text(
+                    &self.log,
+                    &format!("Failed to create {}: {}\n", dir.display(), e),
+                );
+            }
+        }
+        *self.last_run.borrow_mut() = Some(ScanRun {
+            cmd: scan::copy_command(&cmd),
+            parser: parser.clone(),
+            total,
+            xml_output: xml_output.clone(),
+        });
+        self.plain_run.set(false);
+        let (handle, events) = match scan::spawn(cmd) {
+            Ok(scan) => scan,
+            Err(e) => {
+                append_text(&self.log, &format!("Failed to start cppcheck: {}\n", e));
+                return;
+            }
+        };
+        {
+            let mut cfg = self.config.borrow_mut();
+            cfg.add_history(&args);
+            if let Err(e) = cfg.save() {
+                append_text(&self.log, &format!("Failed to save settings: {}\n", e));
+            }
+        }
+        // Fresh results replace those of an opened XML report
+        self.opened_xml.borrow_mut().take();
+        self.run_btn.set_sensitive(false);
+        self.diagnose_btn.set_sensitive(false);
+        self.cancel_btn.set_sensitive(true);
+        self.restart_btn.set_sensitive(true);
+        self.set_progress(0.0);
+        self.progress_label
+            .set_text(&progress_text("Analyzing", 0, total));
+        match self.queue.borrow().position() {
+            Some((i, n)) => self
+                .status
+                .set_text(&format!("Scanning project {} of {} in the queue…", i, n)),
+            None => self.status.set_text("Scanning…"),
+        }
+        self.debug_log.set_text("");
+        self.debug.set_visible(debug);
+        self.results.set_line_range(None);
+        self.results.set_source_base(source_base);
+        self.results.set_findings(Vec::new());
+        *self.active.borrow_mut() = Some(handle.clone());
+        let mut busy = Some(BusyCursor::new(&self.run_btn));
+
+        // Keep the bar moving until real progress is known, which may be
+        // never (no source files counted, or no "Checking" lines)
+        let pulsing = Rc::new(Cell::new(true));
+        {
+            let pulsing = pulsing.clone();
+            let progress = self.progress.clone();
+            // A percentage means nothing while pulsing
+            progress.set_show_text(false);
+            glib::timeout_add_local(Duration::from_millis(150), move || {
+                if !pulsing.get() {
+                    return ControlFlow::Break;
+                }
+                progress.pulse();
+                ControlFlow::Continue
+            });
+        }
+
+        // Show how long the scan has been running and how much memory it
+        // uses, to tell a slow scan from a stuck one
+        {
+            let started = glib::monotonic_time();
+            let handle = handle.clone();
+            let active = self.active.clone();
+            let label = self.resources.clone();
+            let update = move |handle: &ScanHandle| {
+                let secs = (glib::monotonic_time() - started) / 1_000_000;
+                let mut text = format!("Elapsed {}", elapsed_text(secs as u64));
+                if let Some(bytes) = handle.resident_memory() {
+                    text.push_str(&format!(" · {} MB", bytes / (1024 * 1024)));
+                }
+                label.set_text(&text);
+            };
+            update(&handle);
+            glib::timeout_add_local(Duration::from_secs(1), move || {
+                // Stop with this scan; the last reading stays visible
+                if !active.borrow().as_ref().is_some_and(|a| a.same(&handle)) {
+                    return ControlFlow::Break;
+                }
+                update(&handle);
+                ControlFlow::Continue
+            });
+        }
+
+        // Stream output into the log while the worker threads run
+        let live_counts = self.config.borrow().live_counts;
+        self.counts.set_visible(live_counts);
+        self.counts
+            .set_markup(&severity_counts_markup(&BTreeMap::new()));
+        // Applied as findings arrive so the live counts match the results
+        let severity_map = self.config.borrow().severity_map.clone();
+        // For the usage statistics
+        let started = glib::monotonic_time();
+        let ui = self.clone();
+        let mut checked = HashSet::new();
+        let mut found = Vec::new();
+        let mut counts = BTreeMap::new();
+        let mut shown_live = 0;
+        let memory_limit = self.config.borrow().memory_limit_mb;
+        let mut out_of_memory = false;
+        let mut no_sources = false;
+        let mut last_live = 0;
+        glib::timeout_add_local(Duration::from_millis(50), move || loop {
+            match events.try_recv() {
+                Ok(ScanEvent::Line(stream, line)) => {
+                    match scan::classify(stream, &line, &parser, debug) {
+                        Output::Progress(file) => {
+                            if let Some(file) = file {
+                                if checked.insert(file.to_string()) && total > 0 {
+                                    pulsing.set(false);
+                                    ui.set_progress((checked.len() as f64 / total as f64).min(1.0));
+                                    ui.progress_label.set_text(&progress_text(
+                                        "Analyzing",
+                                        checked.len(),
+                                        total,
+                                    ));
+                                }
+                            }
+                        }
+                        Output::Finding(mut finding) => {
+                            severity_map.apply(&mut finding);
+                            if let Some(ref extracted) = *ui.archive.borrow() {
+                                finding.file = extracted.display_path(&finding.file);
+                            }
+                            if live_counts {
+                                *counts.entry(finding.severity).or_insert(0) += 1;
+                                ui.counts.set_markup(&severity_counts_markup(&counts));
+                            }
+                            found.push(finding);
+                        }
+                        Output::Debug => {
+                            insert_log_text(&ui.debug_log, &format!("{}\n", line));
+                            continue;
+                        }
+                        Output::Message => {
+                            out_of_memory |= scan::out_of_memory(&line);
+                            no_sources |= scan::no_source_files(&line);
+                        }
+                    }
+                    append_text(&ui.log, &format!("{}\n", line));
+                }
+                Ok(ScanEvent::Finished(status)) => {
+                    pulsing.set(false);
+                    ui.active.borrow_mut().take();
+                    busy.take();
+                    // With --output-file the findings are all in the XML
+                    if let Some(ref xml) = xml_output {
+                        let parsed = fs::read_to_string(xml)
+                            .map_err(|e| e.to_string())
+                            .and_then(|text| cppcheck_xml::parse_results(&text));
+                        match parsed {
+                            Ok(findings) => found.extend(findings.into_iter().map(|mut f| {
+                                severity_map.apply(&mut f);
+                                f
+                            })),
+                            Err(e) if !handle.was_cancelled() => append_text(
+                                &ui.log,
+                                &format!("Failed to read {}: {}\n", xml.display(), e),
+                            ),
+                            Err(_) => {}
+                        }
+                    }
+                    // cppcheck exits non-zero on bad options or a broken
+                    // setup; findings alone don't change the exit code
+                    let exit_failed =
+                        found.is_empty() && status.as_ref().is_ok_and(|s| !s.success());
+                    // Killed or out of memory under a cap: most likely the cap
+                    let hit_limit = memory_limit > 0
+                        && !handle.was_cancelled()
+                        && status
+                            .as_ref()
+                            .is_ok_and(|s| !s.success() && (out_of_memory || s.code().is_none()));
+                    let completed =
+                        !handle.was_cancelled() && status.is_ok() && !exit_failed && !hit_limit;
+                    if hit_limit {
+                        append_text(
+                            &ui.log,
+                            &format!(
+                                "cppcheck stopped at the memory limit of {} MB; the \
+                                 results are incomplete. Raise the limit in Settings \
+                                 or scan less at once\n",
+                                memory_limit
+                            ),
+                        );
+                        ui.status.set_text("Scan stopped: memory limit reached");
+                    } else if handle.was_cancelled() {
+                        append_text(&ui.log, "Scan cancelled\n");
+                        ui.status.set_text("Scan cancelled");
+                    } else if let Err(e) = status {
+                        append_text(&ui.log, &format!("cppcheck failed: {}\n", e));
+                        ui.status.set_text("Scan failed");
+                    } else if no_sources && found.is_empty() {
+                        append_text(
+                            &ui.log,
+                            "cppcheck found no C or C++ source files to check; choose \
+                             the directory that holds the sources\n",
+                        );
+                        ui.status
+                            .set_text("Scan failed: no C or C++ source files found");
+                        show_no_sources(
+                            &ui.notices,
+                            &ui.select_btn,
+                            "cppcheck found no C or C++ source files in the scanned \
+                             paths. Check the selected directory, the excluded files \
+                             and the compile database.",
+                        );
+                    } else if exit_failed {
+                        let how = match status.ok().and_then(|s| s.code()) {
+                            Some(code) => format!("exited with code {}", code),
+                            None => "was killed".to_string(),
+                        };
+                        append_text(
+                            &ui.log,
+                            &format!(
+                                "cppcheck {} without reporting findings; see its \
+                                 messages above\n",
+                                how
+                            ),
+                        );
+                        ui.status.set_text("Scan failed");
+                    } else if found.is_empty() {
+                        append_text(&ui.log, "No issues found\n");
+                        ui.status.set_markup(&format!(
+                            "<span foreground=\"{}\">Scan complete: no issues found</span>",
+                            NO_ISSUES_COLOR
+                        ));
+                    } else {
+                        ui.status
+                            .set_text(&format!("Scan complete: {} findings", found.len()));
+                    }
+                    if completed {
+                        auto_save_results(&ui.config.borrow(), &found, &ui.log);
+                        let secs = (glib::monotonic_time() - started) / 1_000_000;
+                        let mut cfg = ui.config.borrow_mut();
+                        cfg.record_scan(found.len(), secs as u64);
+                        if let Err(e) = cfg.save() {
+                            append_text(&ui.log, &format!("Failed to save settings: {}\n", e));
+                        }
+                        drop(cfg);
+                        if ui.queue.borrow().is_running() {
+                            found = ui.queue.borrow_mut().record(std::mem::take(&mut found));
+                        }
+                    }
+                    ui.results
+                        .set_severity_first(ui.config.borrow().sort_by_severity);
+                    // Queue runs mix projects and archives show other paths
+                    let blame_dir = scan_dir
+                        .clone()
+                        .filter(|_| completed && !ui.queue.borrow().is_running() && !from_archive);
+                    if let Some(dir) = blame_dir {
+                        ui.highlight_recent(dir, &found);
+                    }
+                    ui.results.set_findings(std::mem::take(&mut found));
+                    ui.set_progress(1.0);
+                    ui.progress_label
+                        .set_text(&progress_text("Analyzed", checked.len(), total));
+                    ui.set_idle_buttons();
+                    // The old process and its readers are done by now, so
+                    // the new scan can't receive any of its output
+                    let restart = ui.restart.replace(Restart::No);
+                    if let Some(xml) = xml_output.take() {
+                        if restart == Restart::No && completed && ui.report_after.get() {
+                            *ui.scan_xml.borrow_mut() = Some(xml);
+                        } else if let Some(dir) = xml.parent() {
+                            let _ = fs::remove_dir_all(dir);
+                        }
+                    }
+                    if restart == Restart::CurrentSettings {
+                        log_info(&ui.log, "Restarting scan\n");
+                        ui.run_btn.emit_clicked();
+                    } else if restart == Restart::SameCommand {
+                        log_info(&ui.log, "Restarting scan\n");
+                        ui.start_again();
+                    } else if ui.report_after.replace(false) {
+                        if completed {
+                            ui.html_btn.emit_clicked();
+                        } else {
+                            append_text(
+                                &ui.log,
+                                "Full report stopped: the scan did not complete\n",
+                            );
+                        }
+                    } else if ui.queue.borrow().is_running() {
+                        if completed {
+                            ui.next_in_queue();
+                        } else {
+                            ui.queue.borrow_mut().stop();
+                            append_text(&ui.log, "Scan queue stopped\n");
+                        }
+                    }
+                    return ControlFlow::Break;
+                }
+                Err(TryRecvError::Empty) => {
+                    // Results fill in as the scan goes, at most once a second
+                    let now = glib::monotonic_time();
+                    if found.len() != shown_live && now - last_live >= 1_000_000 {
+                        ui.results.set_findings(found.clone());
+                        shown_live = found.len();
+                        last_live = now;
+                    }
+                    return ControlFlow::Continue;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    pulsing.set(false);
+                    ui.active.borrow_mut().take();
+                    busy.take();
+                    ui.set_idle_buttons();
+                    ui.restart.set(Restart::No);
+                    ui.report_after.set(false);
+                    if let Some(dir) = xml_output.as_ref().and_then(|xml| xml.parent()) {
+                        let _ = fs::remove_dir_all(dir);
+                    }
+                    return ControlFlow::Break;
+                }
+            }
+        });
+    }
+
+    // Buttons as they are between scans
+    fn set_idle_buttons(&self) {
+        self.run_btn.set_sensitive(true);
+        self.diagnose_btn.set_sensitive(true);
+        self.cancel_btn.set_sensitive(false);
+        self.restart_btn.set_sensitive(false);
+        self.html_btn.set_sensitive(true);
+        self.pdf_btn.set_sensitive(true);
+    }
+
+    // Mark the findings on lines changed within Config::recent_days once git
+    // blame is done, unless another scan has started meanwhile
+    fn highlight_recent(&self, dir: PathBuf, findings: &[Finding]) {
+        let days = self.config.borrow().recent_days;
+        if days == 0 || findings.is_empty() {
+            return;
+        }
+        let results = self.results.clone();
+        let log = self.log.clone();
+        let active = self.active.clone();
+        let receiver = blame::spawn_recent(dir, findings, days);
+        glib::timeout_add_local(Duration::from_millis(100), move || {
+            match receiver.try_recv() {
+                Ok(recent) => {
+                    if !recent.is_empty() && active.borrow().is_none() {
+                        log_info(
+                            &log,
+                            &format!(
+                                "{} finding(s) on lines changed in the last {} day(s), \
+                                 shown in bold\n",
+                                recent.len(),
+                                days
+                            ),
+                        );
+                        results.set_recent(recent);
+                    }
+                    ControlFlow::Break
+                }
+                Err(TryRecvError::Empty) => ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => ControlFlow::Break,
+            }
+        });
+    }
+
+    // Update the bar, labelled with the percentage if the user wants it
+    fn set_progress(&self, fraction: f64) {
+        self.progress.set_fraction(fraction);
+        let show = self.config.borrow().progress_percent;
+        self.progress.set_show_text(show);
+        if show {
+            self.progress
+                .set_text(Some(&format!("{:.0}%", fraction * 100.0)));
+        }
+    }
+
+    // Scan the projects of the queue from the first one on
+    fn run_queue(&self) {
+        if self.active.borrow().is_some() {
+            append_text(&self.log, "A scan is already running\n");
+            return;
+        }
+        if !self.queue.borrow_mut().start() {
+            append_text(&self.log, "The scan queue is empty\n");
+            return;
+        }
+        self.next_in_queue();
+    }
+
+    // Open the next project of the queue and scan it; projects that can't
+    // be scanned (e.g. a missing working directory) are skipped
+    fn next_in_queue(&self) {
+        loop {
+            let step = self.queue.borrow_mut().advance();
+            match step {
+                scan_queue::Step::Next(path) => {
+                    if let Some((i, n)) = self.queue.borrow().position() {
+                        log_info(
+                            &self.log,
+                            &format!("Scan queue: project {} of {}: {}\n", i, n, path),
+                        );
+                    }
+                    (self.open_project)(&path, true);
+                    if self.active.borrow().is_some() {
+                        return;
+                    }
+                    append_text(
+                        &self.log,
+                        &format!("Scan queue: could not scan {}, skipping it\n", path),
+                    );
+                }
+                scan_queue::Step::Done(counts) => {
+                    let total: usize = counts.iter().map(|(_, n)| n).sum();
+                    for (path, n) in &counts {
+                        log_info(&self.log, &format!("  {}: {} finding(s)\n", path, n));
+                    }
+                    let summary = format!(
+                        "Scan queue finished: {} findings in {} project(s)",
+                        total,
+                        counts.len()
+                    );
+                    append_text(&self.log, &format!("{}\n", summary));
+                    self.status.set_text(&summary);
+                    return;
+                }
+            }
+        }
+    }
+
+    // Cancel the running scan; the poller starts the new one when the old
+    // process has exited
+    fn restart(&self) {
+        let Some(ref handle) = *self.active.borrow() else {
+            return;
+        };
+        self.restart.set(if self.plain_run.get() {
+            Restart::CurrentSettings
+        } else {
+            Restart::SameCommand
+        });
+        self.restart_btn.set_sensitive(false);
+        handle.cancel();
+    }
+
+    // Run the last scan again exactly as it was started, e.g. a quick
+    // check or a line range check, keeping its line range
+    fn start_again(&self) {
+        let run = self.last_run.borrow().as_ref().map(|run| ScanRun {
+            cmd: scan::copy_command(&run.cmd),
+            parser: run.parser.clone(),
+            total: run.total,
+            xml_output: run.xml_output.clone(),
+        });
+        let Some(run) = run else {
+            return;
+        };
+        *self.scan_xml.borrow_mut() = run.xml_output;
+        let line_range = self.results.line_range();
+        self.start(run.cmd, run.parser, run.total);
+        self.results.set_line_range(line_range);
+    }
+
+    // Re-run a command exactly as recorded in the history
+    fn rerun(&self, args: &[String]) {
+        let Some((program, rest)) = args.split_first() else {
+            return;
+        };
+        let mut cmd = Command::new(program);
+        cmd.args(rest);
+        scan::set_child_env(&mut cmd, &self.config.borrow().cppcheck_env);
+        let parser = rest
+            .iter()
+            .find_map(|a| a.strip_prefix("--template="))
+            .map(Template::parse)
+            .unwrap_or_default();
+        let excludes: Vec<String> = rest
+            .iter()
+            .filter_map(|a| a.strip_prefix("-i"))
+            .map(String::from)
+            .collect();
+        // The scanned paths are the trailing arguments that are not options
+        let total = rest
+            .iter()
+            .rev()
+            .take_while(|a| !a.starts_with('-'))
+            .map(|p| {
+                let p = Path::new(p);
+                if p.is_dir() {
+                    scan::count_source_files(p, &excludes)
+                } else {
+                    1
+                }
+            })
+            .sum();
+        log_info(
+            &self.log,
+            &format!("Re-running {}\n", scan::command_line(args)),
+        );
+        // A Full Report scan puts its findings in the XML
+        *self.scan_xml.borrow_mut() = rest
+            .iter()
+            .find_map(|a| a.strip_prefix("--output-file="))
+            .map(PathBuf::from);
+        self.start(cmd, parser, total);
+    }
+
+    // Scan with each command of `runs` in turn, labelled by its standard,
+    // then show the diff of the first two; results and history are left alone
+    fn compare_runs(
+        &self,
+        window: &ApplicationWindow,
+        mut runs: VecDeque<(String, Command)>,
+        parser: Template,
+        mut done: Vec<(String, Vec<Finding>)>,
+    ) {
+        let Some((label, cmd)) = runs.pop_front() else {
+            self.run_btn.set_sensitive(true);
+            self.diagnose_btn.set_sensitive(true);
+            self.cancel_btn.set_sensitive(false);
+            if let [(before, before_findings), (after, after_findings), ..] = done.as_slice() {
+                self.status
+                    .set_text(&format!("Compared {} with {}", before, after));
+                compare::show_diff(
+                    window,
+                    "Compare Standards",
+                    (before, before_findings),
+                    (after, after_findings),
+                );
+            }
+            return;
+        };
+        log_command(&self.log, &cmd);
+        let (handle, events) = match scan::spawn(cmd) {
+            Ok(scan) => scan,
+            Err(e) => {
+                append_text(&self.log, &format!("Failed to start cppcheck: {}\n", e));
+                self.run_btn.set_sensitive(true);
+                self.diagnose_btn.set_sensitive(true);
+                self.cancel_btn.set_sensitive(false);
+                return;
+            }
+        };
+        self.run_btn.set_sensitive(false);
+        self.diagnose_btn.set_sensitive(false);
+        self.cancel_btn.set_sensitive(true);
+        self.status
+            .set_text(&format!("Comparing standards: scanning with {}…", label));
+        *self.active.borrow_mut() = Some(handle.clone());
+        let mut busy = Some(BusyCursor::new(&self.run_btn));
+
+        let ui = self.clone();
+        let window = window.clone();
+        let mut runs = Some(runs);
+        let mut parser = Some(parser);
+        let mut findings = Vec::new();
+        glib::timeout_add_local(Duration::from_millis(50), move || loop {
+            match events.try_recv() {
+                Ok(ScanEvent::Line(stream, line)) => {
+                    match scan::classify(stream, &line, parser.as_ref().unwrap(), false) {
+                        Output::Progress(_) | Output::Debug => {}
+                        Output::Finding(finding) => findings.push(finding),
+                        Output::Message => append_text(&ui.log, &format!("{}\n", line)),
+                    }
+                }
+                Ok(ScanEvent::Finished(status)) => {
+                    ui.active.borrow_mut().take();
+                    busy.take();
+                    if handle.was_cancelled() {
+                        append_text(&ui.log, "Standards comparison cancelled\n");
+                        ui.status.set_text("Standards comparison cancelled");
+                    } else if let Err(e) = status {
+                        append_text(&ui.log, &format!("cppcheck failed: {}\n", e));
+                        ui.status.set_text("Standards comparison failed");
+                    } else {
+                        log_info(
+                            &ui.log,
+                            &format!("{}: {} finding(s)\n", label, findings.len()),
+                        );
+                        let mut done = std::mem::take(&mut done);
+                        done.push((label.clone(), std::mem::take(&mut findings)));
+                        ui.compare_runs(
+                            &window,
+                            runs.take().unwrap_or_default(),
+                            parser.take().unwrap_or_default(),
+                            done,
+                        );
+                        return ControlFlow::Break;
+                    }
+                    ui.run_btn.set_sensitive(true);
+                    ui.diagnose_btn.set_sensitive(true);
+                    ui.cancel_btn.set_sensitive(false);
+                    return ControlFlow::Break;
+                }
+                Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => {
+                    ui.active.borrow_mut().take();
+                    busy.take();
+                    ui.run_btn.set_sensitive(true);
+                    ui.diagnose_btn.set_sensitive(true);
+                    ui.cancel_btn.set_sensitive(false);
+                    return ControlFlow::Break;
+                }
+            }
+        });
+    }
+
+    // Run `cppcheck --check-config` on `path` and list the include and
+    // configuration problems it reports in the log; results are left alone
+    fn diagnose(&self, path: &str) {
+        let mut cmd = {
+            let config = self.config.borrow();
+            scan::cppcheck_command(
+                &config.command_prefix,
+                &config.cppcheck_env,
+                config.memory_limit_mb,
+                config.low_priority,
+            )
+        };
+        cmd.args(["--check-config", path]);
+        log_command(&self.log, &cmd);
+        let (handle, events) = match scan::spawn(cmd) {
+            Ok(scan) => scan,
+            Err(e) => {
+                append_text(&self.log, &format!("Failed to start cppcheck: {}\n", e));
+                return;
+            }
+        };
+        log_info(
+            &self.log,
+            &format!("Checking the configuration of {}\n", path),
+        );
+        self.run_btn.set_sensitive(false);
+        self.diagnose_btn.set_sensitive(false);
+        self.cancel_btn.set_sensitive(true);
+        self.status.set_text("Checking configuration…");
+        *self.active.borrow_mut() = Some(handle.clone());
+        let mut busy = Some(BusyCursor::new(&self.run_btn));
+
+        let ui = self.clone();
+        let parser = Template::default();
+        let mut problems = Vec::new();
+        glib::timeout_add_local(Duration::from_millis(50), move || loop {
+            match events.try_recv() {
+                Ok(ScanEvent::Line(stream, line)) => {
+                    match scan::classify(stream, &line, &parser, false) {
+                        // Progress lines would bury the diagnostics
+                        Output::Progress(_) | Output::Debug => {}
+                        Output::Finding(finding) => {
+                            problems.push(finding);
+                            append_text(&ui.log, &format!("{}\n", line));
+                        }
+                        Output::Message => append_text(&ui.log, &format!("{}\n", line)),
+                    }
+                }
+                Ok(ScanEvent::Finished(status)) => {
+                    ui.active.borrow_mut().take();
+                    busy.take();
+                    if handle.was_cancelled() {
+                        append_text(&ui.log, "Configuration check cancelled\n");
+                        ui.status.set_text("Configuration check cancelled");
+                    } else if let Err(e) = status {
+                        append_text(&ui.log, &format!("cppcheck failed: {}\n", e));
+                        ui.status.set_text("Configuration check failed");
+                    } else if problems.is_empty() {
+                        append_text(&ui.log, "No configuration problems found\n");
+                        ui.status.set_text("Configuration check: no problems");
+                    } else {
+                        ui.status.set_text(&format!(
+                            "Configuration check: {} problem(s)",
+                            problems.len()
+                        ));
+                        let missing = problems
+                            .iter()
+                            .filter(|f| f.id.starts_with("missingInclude"))
+                            .count();
+                        append_text(
+                            &ui.log,
+                            &format!(
+                                "{} configuration problem(s), {} missing include(s); \
+                                 missing includes usually need an -I path\n",
+                                problems.len(),
+                                missing
+                            ),
+                        );
+                    }
+                    ui.run_btn.set_sensitive(true);
+                    ui.diagnose_btn.set_sensitive(true);
+                    ui.cancel_btn.set_sensitive(false);
+                    return ControlFlow::Break;
+                }
+                Err(TryRecvError::Empty) => return ControlFlow::Continue,
+                Err(TryRecvError::Disconnected) => {
+                    ui.active.borrow_mut().take();
+                    busy.take();
+                    ui.run_btn.set_sensitive(true);
+                    ui.diagnose_btn.set_sensitive(true);
+                    ui.cancel_btn.set_sensitive(false);
+                    ui.restart_btn.set_sensitive(false);
+                    ui.restart.set(Restart::No);
+                    return ControlFlow::Break;
+                }
+            }
+        });
+    }
+}
+
+// A scan as ScanUi::start got it, for starting it again
+struct ScanRun {
+    cmd: Command,
+    parser: Template,
+    total: usize,
+    // Where it writes its findings as XML, if anywhere
+    xml_output: Option<PathBuf>,
+}
+
+// What ScanUi::restart has the cancelled scan followed by
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Restart {
+    No,
+    // The same command again
+    SameCommand,
+    // Run, with the settings as they are now
+    CurrentSettings,
+}
+
+// Progress of the PDF export thread
+enum PdfEvent {
+    Attempt(u32),
+    // The browser could not be started
+    RunFailed(String),
+    // Whether the browser ran at all
+    Finished(bool),
+}
+
+// Opens a project path, scanning it right away when the flag is set
+type OpenProject = Rc<dyn Fn(&str, bool)>;
+
+// Widgets holding the per-project scan settings
+#[derive(Clone)]
+struct ScanControls {
+    chk_warning: CheckButton,
+    chk_style: CheckButton,
+    chk_performance: CheckButton,
+    template_combo: ComboBoxText,
+    template_entry: Entry,
+    chk_clang: CheckButton,
+    chk_unused: CheckButton,
+    chk_bug_hunting: CheckButton,
+    chk_headers: CheckButton,
+    chk_aggressive: CheckButton,
+    rule_files: RuleFiles,
+    excluded: ExcludedFiles,
+    workdir_entry: Entry,
+    build_dir_entry: Entry,
+    addons_entry: Entry,
+    libraries_entry: Entry,
+    compile_db_entry: Entry,
+    chk_relative: CheckButton,
+    relative_base_entry: Entry,
+    enable_entry: Entry,
+    threshold: Scale,
+}
+
+impl ScanControls {
+    fn settings(&self) -> ProjectSettings {
+        let mut enables = Vec::new();
+        for (chk, group) in [
+            (&self.chk_warning, "warning"),
+            (&self.chk_style, "style"),
+            (&self.chk_performance, "performance"),
+            (&self.chk_unused, UNUSED_FUNCTION),
+        ] {
+            if chk.is_active() {
+                enables.push(group.to_string());
+            }
+        }
+        // Groups without a checkbox follow the threshold alone
+        let level = threshold_level(&self.threshold);
+        if level.rank() >= Severity::Portability.rank() {
+            enables.push(Severity::Portability.to_string());
+        }
+        if level == Severity::Information {
+            enables.push(Severity::Information.to_string());
+        }
+        ProjectSettings {
+            enables,
+            template: self
+                .template_combo
+                .active_text()
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            custom_template: self.template_entry.text().to_string(),
+            clang: self.chk_clang.is_active(),
+            bug_hunting: self.chk_bug_hunting.is_active(),
+            check_headers: self.chk_headers.is_active(),
+            aggressive: self.chk_aggressive.is_active(),
+            rule_files: self.rule_files.files(),
+            excludes: self.excluded.files(),
+            working_dir: self.workdir_entry.text().trim().to_string(),
+            build_dir: self.build_dir_entry.text().trim().to_string(),
+            addons: comma_list(&self.addons_entry.text()),
+            libraries: comma_list(&self.libraries_entry.text()),
+            compile_database: self.compile_db_entry.text().trim().to_string(),
+            relative_paths: self.chk_relative.is_active(),
+            relative_base: self.relative_base_entry.text().trim().to_string(),
+            enable_expression: self.enable_entry.text().trim().to_string(),
+        }
+    }
+
+    // cppcheck invocation for `settings`, without the paths to scan, and
+    // the parser for its output
+    fn command(
+        &self,
+        settings: &ProjectSettings,
+        suppressions_list: Option<String>,
+        config: &Config,
+        log: &TextBuffer,
+    ) -> (Command, Template) {
+        let mut cmd = scan::cppcheck_command(
+            &config.command_prefix,
+            &config.cppcheck_env,
+            config.memory_limit_mb,
+            config.low_priority,
+        );
+        if !config.command_prefix.is_empty() {
+            log_info(
+                log,
+                &format!(
+                    "Running cppcheck through {}\n",
+                    scan::command_line(&config.command_prefix)
+                ),
+            );
+        }
+        if config.cppcheck_debug {
+            cmd.arg(scan::DEBUG_FLAG);
+            log_info(log, "cppcheck debug output goes to its own panel\n");
+        }
+        // A valid advanced expression replaces the checkbox groups for the
+        // run only; settings.enables stays the checkbox state
+        let enables = match config::parse_enable_expression(&settings.enable_expression) {
+            Ok(groups) if !groups.is_empty() => groups,
+            Ok(_) => settings.enables.clone(),
+            Err(e) => {
+                append_text(
+                    log,
+                    &format!("Advanced --enable ignored, using the checkboxes: {}\n", e),
+                );
+                settings.enables.clone()
+            }
+        };
+        if settings.aggressive {
+            cmd.args(["--enable=all", "--inconclusive"]);
+            log_info(
+                log,
+                "Aggressive mode: all checks plus inconclusive findings, expect more noise\n",
+            );
+        } else if !enables.is_empty() {
+            cmd.arg(format!("--enable={}", enables.join(",")));
+        }
+        // --enable=all includes unusedFunction
+        if settings.aggressive || enables.iter().any(|e| e == UNUSED_FUNCTION || e == "all") {
+            // cppcheck drops unusedFunction when files are split
+            // across jobs; pin one job so results don't go missing
+            cmd.arg("-j1");
+            log_info(
+                log,
+                "unusedFunction needs the whole program: running with -j1\n",
+            );
+        }
+        if let Some(file) = suppressions_list {
+            cmd.arg(format!("--suppressions-list={}", file));
+        }
+        let suppressed_dirs = config.suppressed_dirs();
+        cmd.args(suppress::directory_suppressions(&suppressed_dirs));
+        if !suppressed_dirs.is_empty() {
+            log_info(
+                log,
+                &format!(
+                    "Ignoring findings in system headers under {}\n",
+                    suppressed_dirs.join(", ")
+                ),
+            );
+        }
+        if settings.clang {
+            // clang may have gone away since startup
+            if tools::is_on_path("clang") {
+                cmd.arg("--clang");
+            } else {
+                append_text(log, "clang not found, using the built-in parser\n");
+            }
+        }
+        if settings.bug_hunting {
+            cmd.arg("--bug-hunting");
+            log_info(
+                log,
+                "Bug hunting is experimental and can take a long time\n",
+            );
+        }
+        for file in &settings.rule_files {
+            cmd.arg(format!("--rule-file={}", file));
+        }
+        for file in &settings.excludes {
+            cmd.arg(format!("-i{}", file));
+        }
+        for library in &settings.libraries {
+            cmd.arg(format!("--library={}", library));
+        }
+        for addon in &settings.addons {
+            cmd.arg(format!("--addon={}", addon));
+        }
+        if !settings.addons.is_empty() && !config.addon_python.is_empty() {
+            cmd.arg(format!("--addon-python={}", config.addon_python));
+            log_info(log, &format!("Addons run with {}\n", config.addon_python));
+        }
+        if config.max_ctu_depth != config::DEFAULT_MAX_CTU_DEPTH {
+            cmd.arg(format!("--max-ctu-depth={}", config.max_ctu_depth));
+        }
+        // cppcheck's own format is replaced by one that also has the CWE
+        // and the long message
+        let template =
+            selected_template(settings).unwrap_or_else(|| findings::DETAILED_TEMPLATE.to_string());
+        // Custom templates print nothing for inconclusive findings unless
+        // asked to; the parser still uses `template` and takes the mark as
+        // part of the message
+        if settings.aggressive && !template.contains("{inconclusive:") {
+            cmd.arg(format!(
+                "--template={}",
+                template.replacen(
+                    "{message}",
+                    &format!("{}{{message}}", findings::INCONCLUSIVE_MARK),
+                    1
+                )
+            ));
+        } else {
+            cmd.arg(format!("--template={}", template));
+        }
+        let parser = Template::parse(&template);
+        if !config.editor_command.is_empty()
+            && config.editor_columns
+            && !template.contains("{column}")
+        {
+            log_info(
+                log,
+                "The output template has no {column}: editor jumps go to the start \
+                 of the line\n",
+            );
+        }
+        if !parser.is_parseable() {
+            append_text(
+                log,
+                "Template lacks {file} or {severity}; results list will stay empty\n",
+            );
+        }
+        (cmd, parser)
+    }
+
+    fn apply(&self, settings: &ProjectSettings) {
+        self.apply_enables(&settings.enables);
+        // Unknown names fall back to "custom", the entry after the presets
+        let index = findings::TEMPLATE_PRESETS
+            .iter()
+            .position(|(name, _)| *name == settings.template)
+            .unwrap_or(findings::TEMPLATE_PRESETS.len());
+        self.template_combo.set_active(Some(index as u32));
+        self.template_entry.set_text(&settings.custom_template);
+        self.chk_clang
+            .set_active(settings.clang && self.chk_clang.is_sensitive());
+        self.chk_bug_hunting
+            .set_active(settings.bug_hunting && self.chk_bug_hunting.is_sensitive());
+        self.chk_headers.set_active(settings.check_headers);
+        self.chk_aggressive.set_active(settings.aggressive);
+        self.rule_files.set_files(&settings.rule_files);
+        self.excluded.set_files(&settings.excludes);
+        self.workdir_entry.set_text(&settings.working_dir);
+        self.build_dir_entry.set_text(&settings.build_dir);
+        self.addons_entry.set_text(&settings.addons.join(", "));
+        self.libraries_entry
+            .set_text(&settings.libraries.join(", "));
+        self.compile_db_entry.set_text(&settings.compile_database);
+        self.chk_relative.set_active(settings.relative_paths);
+        self.relative_base_entry.set_text(&settings.relative_base);
+        self.enable_entry.set_text(&settings.enable_expression);
+    }
+
+    // Tick the checkboxes of `enables`; groups without one move the threshold
+    fn apply_enables(&self, enables: &[String]) {
+        for (chk, group) in [
+            (&self.chk_warning, "warning"),
+            (&self.chk_style, "style"),
+            (&self.chk_performance, "performance"),
+            (&self.chk_unused, UNUSED_FUNCTION),
+        ] {
+            chk.set_active(enables.iter().any(|e| e == group));
+        }
+        for severity in [Severity::Portability, Severity::Information] {
+            if enables.iter().any(|e| *e == severity.as_str())
+                && threshold_level(&self.threshold).rank() < severity.rank()
+            {
+                self.threshold.set_value(severity.rank() as f64);
+            }
+        }
+    }
+}
+
+// Unpack `archive` on a worker thread, then scan it with the current
+// settings; the previous archive's files are removed once this one is in
+fn scan_archive(
+    ui: &ScanUi,
+    controls: &ScanControls,
+    suppressions_list: Option<String>,
+    button: &Button,
+    archive: PathBuf,
+) {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let dest = temp_dir().join(format!("archive-{}", glib::monotonic_time()));
+    log_info(&ui.log, &format!("Extracting {}\n", archive.display()));
+    ui.status.set_text(&format!("Extracting {}…", name));
+    button.set_sensitive(false);
+    let events = archive::spawn_extract(archive, dest.clone());
+
+    let ui = ui.clone();
+    let controls = controls.clone();
+    let button = button.clone();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        let result = match events.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return ControlFlow::Continue,
+            Err(TryRecvError::Disconnected) => Err("extraction stopped unexpectedly".to_string()),
+        };
+        button.set_sensitive(true);
+        if let Err(e) = result {
+            append_text(&ui.log, &format!("{}\n", e));
+            ui.status.set_text("Extraction failed");
+            return ControlFlow::Break;
+        }
+        let extracted = archive::Extracted {
+            root: dest.clone(),
+            name: name.clone(),
+        };
+        if ui.active.borrow().is_some() {
+            // Dropping `extracted` removes the files again
+            append_text(&ui.log, "A scan is already running\n");
+            return ControlFlow::Break;
+        }
+        let settings = controls.settings();
+        let (mut cmd, parser) = controls.command(
+            &settings,
+            suppressions_list.clone(),
+            &ui.config.borrow(),
+            &ui.log,
+        );
+        cmd.arg(&extracted.root).current_dir(&extracted.root);
+        let headers = add_header_files(&mut cmd, &settings, &extracted.root, &ui.log);
+        let total = scan::count_source_files(&extracted.root, &settings.excludes) + headers;
+        log_info(&ui.log, &format!("Running cppcheck on {}\n", name));
+        *ui.archive.borrow_mut() = Some(extracted);
+        ui.start(cmd, parser, total);
+        ControlFlow::Break
+    });
+}
+
+// Write the current scan settings to a profile file; the profile is named
+// after the file
+fn export_profile(window: &ApplicationWindow, controls: &ScanControls, log: &TextBuffer) {
+    let dialog = FileChooserDialog::builder()
+        .title(tr("Export Profile"))
+        .action(FileChooserAction::Save)
+        .transient_for(window)
+        .modal(true)
+        .build();
+    dialog.set_current_name("cppcheck-profile.json");
+    dialog.add_buttons(&[
+        (tr("Cancel"), ResponseType::Cancel),
+        (tr("Export"), ResponseType::Accept),
+    ]);
+    let controls = controls.clone();
+    let log = log.clone();
+    dialog.connect_response(move |d, r| {
+        d.close();
+        if r != ResponseType::Accept {
+            return;
+        }
+        let Some(path) = d.file().and_then(|f| f.path()) else {
+            return;
+        };
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let profile = profile::Profile {
+            name: name.clone(),
+            settings: controls.settings(),
+        };
+        match fs::write(&path, profile.to_json()) {
+            Ok(()) => log_info(
+                &log,
+                &format!("Exported profile '{}' to {}\n", name, path.display()),
+            ),
+            Err(e) => append_text(
+                &log,
+                &format!("Failed to write {}: {}\n", path.display(), e),
+            ),
+        }
+    });
+    dialog.show();
+}
+
+// Save the settings as a .cppcheck project file of the official GUI
+fn export_cppcheck_project(
+    window: &ApplicationWindow,
+    project: &str,
+    settings: &ProjectSettings,
+    max_ctu_depth: u32,
+    suppressions: &[suppress::Suppression],
+    log: &TextBuffer,
+) {
+    let dialog = FileChooserDialog::builder()
+        .title(tr("Export cppcheck Project"))
+        .action(FileChooserAction::Save)
+        .transient_for(window)
+        .modal(true)
+        .build();
+    let name = Path::new(project)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "project".to_string());
+    dialog.set_current_name(&format!("{}.cppcheck", name));
+    let _ = dialog.set_current_folder(Some(&gio::File::for_path(project)));
+    dialog.add_buttons(&[
+        (tr("Cancel"), ResponseType::Cancel),
+        (tr("Export"), ResponseType::Accept),
+    ]);
+    let xml = cppcheck_project::to_xml(project, settings, max_ctu_depth, suppressions);
+    let skipped = cppcheck_project::unsupported(settings);
+    let log = log.clone();
+    dialog.connect_response(move |d, r| {
+        d.close();
+        if r != ResponseType::Accept {
+            return;
+        }
+        let Some(path) = d.file().and_then(|f| f.path()) else {
+            return;
+        };
+        match fs::write(&path, &xml) {
+            Ok(()) => {
+                log_info(
+                    &log,
+                    &format!("Exported cppcheck project to {}\n", path.display()),
+                );
+                if !skipped.is_empty() {
+                    log_info(
+                        &log,
+                        &format!(
+                            "Not part of cppcheck project files, set them in the \
+                             official GUI: {}\n",
+                            skipped.join(", ")
+                        ),
+                    );
+                }
+            }
+            Err(e) => append_text(
+                &log,
+                &format!("Failed to write {}: {}\n", path.display(), e),
+            ),
+        }
+    });
+    dialog.show();
+}
+
+// Load a profile file into the scan controls and, with a project open,
+// save it as that project's settings
+fn import_profile(
+    window: &ApplicationWindow,
+    controls: &ScanControls,
+    config: &Rc<RefCell<Config>>,
+    project: &Rc<RefCell<Option<String>>>,
+    log: &TextBuffer,
+) {
+    let dialog = FileChooserDialog::builder()
+        .title(tr("Import Profile"))
+        .action(FileChooserAction::Open)
+        .transient_for(window)
+        .modal(true)
+        .build();
+    let filter = FileFilter::new();
+    filter.set_name(Some("Profiles (*.json)"));
+    filter.add_pattern("*.json");
+    dialog.add_filter(&filter);
+    dialog.add_buttons(&[
+        (tr("Cancel"), ResponseType::Cancel),
+        (tr("Import"), ResponseType::Accept),
+    ]);
+    let controls = controls.clone();
+    let config = config.clone();
+    let project = project.clone();
+    let log = log.clone();
+    dialog.connect_response(move |d, r| {
+        d.close();
+        if r != ResponseType::Accept {
+            return;
+        }
+        let Some(path) = d.file().and_then(|f| f.path()) else {
+            return;
+        };
+        let profile = match fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| profile::Profile::from_json(&text))
+        {
+            Ok(profile) => profile,
+            Err(e) => {
+                append_text(&log, &format!("Failed to load {}: {}\n", path.display(), e));
+                return;
+            }
+        };
+        controls.apply(&profile.settings);
+        log_info(
+            &log,
+            &format!(
+                "Imported profile '{}' from {}\n",
+                profile.name,
+                path.display()
+            ),
+        );
+        if let Some(ref path) = *project.borrow() {
+            let mut cfg = config.borrow_mut();
+            cfg.set_project(path, controls.settings());
+            if let Err(e) = cfg.save() {
+                append_text(&log, &format!("Failed to save settings: {}\n", e));
+            }
+        }
+    });
+    dialog.show();
+}
+
+fn open_report(index_uri: &str, log: &TextBuffer) {
+    if let Err(e) = AppInfo::launch_default_for_uri(index_uri, None::<&gio::AppLaunchContext>) {
+        append_text(log, &format!("Failed to open HTML report: {}\n", e));
+    }
+}
+
+// Point the Open Report button at a newly generated report
+fn report_ready(button: &Button, last: &Rc<RefCell<Option<String>>>, uri: &str) {
+    *last.borrow_mut() = Some(uri.to_string());
+    button.set_tooltip_text(Some(&format!("Open {}", uri)));
+    button.set_sensitive(true);
+}
+
+// A huge report can bog down the browser, so opening it is confirmed
+fn confirm_open_report(
+    button: &Button,
+    index_uri: String,
+    size: html_report::Size,
+    log: &TextBuffer,
+) {
+    let parent = button.root().and_downcast::<gtk4::Window>();
+    let confirm = MessageDialog::new(
+        parent.as_ref(),
+        DialogFlags::MODAL,
+        MessageType::Question,
+        ButtonsType::YesNo,
+        "Open a large report?",
+    );
+    confirm.set_secondary_text(Some(&format!(
+        "The report is {}. Opening it may make the browser slow or unresponsive. \
+         It stays on disk either way.",
+        size
+    )));
+    let log = log.clone();
+    confirm.connect_response(move |c, r| {
+        c.close();
+        if r == ResponseType::Yes {
+            open_report(&index_uri, &log);
+        } else {
+            log_info(&log, "HTML report not opened\n");
+        }
+    });
+    confirm.show();
+}
+
+// With check_headers, pass every header under `root` that isn't excluded
+// to cppcheck as a file of its own, through a --file-list so a large tree
+// doesn't overflow the command line; returns how many were added
+fn add_header_files(
+    cmd: &mut Command,
+    settings: &ProjectSettings,
+    root: &Path,
+    log: &TextBuffer,
+) -> usize {
+    if !settings.check_headers {
+        return 0;
+    }
+    let headers = scan::header_files(root, &settings.excludes);
+    if headers.is_empty() {
+        return 0;
+    }
+    let dir = temp_dir();
+    let list = dir.join(format!("headers-{}.txt", glib::monotonic_time()));
+    let text: String = headers
+        .iter()
+        .map(|header| format!("{}\n", header.display()))
+        .collect();
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|()| fs::write(&list, text)) {
+        append_text(
+            log,
+            &format!(
+                "Failed to write {}, headers not added: {}\n",
+                list.display(),
+                e
+            ),
+        );
+        return 0;
+    }
+    cmd.arg(format!("--file-list={}", list.display()));
+    log_info(
+        log,
+        &format!(
+            "Checking {} header file(s) directly as well, not only where they are #included\n",
+            headers.len()
+        ),
+    );
+    headers.len()
+}
+
+// Button picking a folder into `entry`
+fn folder_button(window: &ApplicationWindow, entry: &Entry, title: &'static str) -> Button {
+    let button = Button::with_label(tr("Choose…"));
+    let entry_clone = entry.clone();
+    let win_clone = window.clone();
+    button.connect_clicked(move |_| {
+        let dialog = FileChooserDialog::builder()
+            .title(title)
+            .action(FileChooserAction::SelectFolder)
+            .transient_for(&win_clone)
+            .modal(true)
+            .build();
+        dialog.add_buttons(&[
+            (tr("Cancel"), ResponseType::Cancel),
+            (tr("Select"), ResponseType::Accept),
+        ]);
+        let entry_inner = entry_clone.clone();
+        dialog.connect_response(move |d, r| {
+            if r == ResponseType::Accept {
+                if let Some(path) = d.file().and_then(|f| f.path()) {
+                    entry_inner.set_text(&path.to_string_lossy());
+                }
+            }
+            d.close();
+        });
+        dialog.show();
+    });
+    button
+}
+
+// What cppcheck scans for `project`: the compile database when one is set,
+// otherwise the directory and, if asked, its headers. Returns the number of
+// files for the progress bar, 0 when unknown
+fn add_scan_target(
+    cmd: &mut Command,
+    settings: &ProjectSettings,
+    project: &str,
+    log: &TextBuffer,
+) -> usize {
+    if let Some(arg) = relative_paths_arg(settings, project) {
+        cmd.arg(arg);
+    }
+    if settings.compile_database.is_empty() {
+        cmd.arg(project);
+        let headers = add_header_files(cmd, settings, Path::new(project), log);
+        return scan::count_source_files(Path::new(project), &settings.excludes) + headers;
+    }
+    // join keeps an absolute path as it is
+    let database = Path::new(project).join(&settings.compile_database);
+    cmd.arg(format!("--project={}", database.display()));
+    log_info(
+        log,
+        &format!("Scanning the files listed in {}\n", database.display()),
+    );
+    if settings.check_headers {
+        log_info(
+            log,
+            "Headers are not added: cppcheck takes no other files with a compile database\n",
+        );
+    }
+    // One entry per translation unit
+    fs::read_to_string(&database)
+        .ok()
+        .and_then(|text| json::parse(&text).ok())
+        .and_then(|value| value.as_array().map(<[_]>::len))
+        .unwrap_or(0)
+}
+
+// --relative-paths for `settings`, with the base made absolute because
+// cppcheck may run elsewhere
+fn relative_paths_arg(settings: &ProjectSettings, project: &str) -> Option<String> {
+    if !settings.relative_paths {
+        return None;
+    }
+    if settings.relative_base.is_empty() {
+        return Some("--relative-paths".to_string());
+    }
+    // join keeps an absolute base as it is
+    let base = Path::new(project).join(&settings.relative_base);
+    Some(format!("--relative-paths={}", base.display()))
+}
+
+// Comma-separated names of an entry, e.g. addons or libraries
+fn comma_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+// Offer what the build files of the project at `root` suggest, in place of
+// earlier suggestions
+fn show_suggestions(slot: &GtkBox, controls: &ScanControls, root: &Path) {
+    while let Some(child) = slot.first_child() {
+        slot.remove(&child);
+    }
+    let suggestions = build_system::suggest(root, &controls.settings());
+    if suggestions.is_empty() {
+        return;
+    }
+    let bar = InfoBar::new();
+    bar.set_message_type(MessageType::Info);
+    bar.set_show_close_button(true);
+    let text: Vec<&str> = suggestions.iter().map(|s| s.text.as_str()).collect();
+    let label = Label::new(Some(&text.join("\n")));
+    label.set_wrap(true);
+    label.set_xalign(0.0);
+    label.set_hexpand(true);
+    bar.add_child(&label);
+    let actions: Vec<build_system::Action> =
+        suggestions.into_iter().filter_map(|s| s.action).collect();
+    for (i, action) in actions.iter().enumerate() {
+        bar.add_button(&action.label(), ResponseType::Other(i as u16));
+    }
+    let controls = controls.clone();
+    bar.connect_response(move |bar, response| {
+        let ResponseType::Other(i) = response else {
+            bar.set_visible(false);
+            return;
+        };
+        match actions.get(i as usize) {
+            Some(build_system::Action::UseCompileDatabase(db)) => {
+                controls.compile_db_entry.set_text(db)
+            }
+            Some(build_system::Action::AddLibrary(library)) => {
+                let mut libraries = comma_list(&controls.libraries_entry.text());
+                libraries.push(library.to_string());
+                controls.libraries_entry.set_text(&libraries.join(", "));
+            }
+            None => {}
+        }
+        bar.set_response_sensitive(response, false);
+    });
+    slot.append(&bar);
+}
+
+// Warning that the project has nothing to scan, with a way straight back
+// to the directory chooser; replaces an earlier one
+fn show_no_sources(slot: &GtkBox, select_btn: &Button, text: &str) {
+    let mut child = slot.first_child();
+    while let Some(widget) = child {
+        child = widget.next_sibling();
+        if widget.widget_name() == NO_SOURCES_BANNER {
+            slot.remove(&widget);
+        }
+    }
+    let bar = InfoBar::new();
+    bar.set_widget_name(NO_SOURCES_BANNER);
+    bar.set_message_type(MessageType::Warning);
+    bar.set_show_close_button(true);
+    let label = Label::new(Some(text));
+    label.set_wrap(true);
+    label.set_xalign(0.0);
+    label.set_hexpand(true);
+    bar.add_child(&label);
+    bar.add_button(tr("Choose Another Directory"), ResponseType::Accept);
+    let select_btn = select_btn.clone();
+    bar.connect_response(move |bar, response| {
+        bar.set_visible(false);
+        if response == ResponseType::Accept {
+            select_btn.emit_clicked();
+        }
+    });
+    slot.append(&bar);
+}
+
+// Pass the project's cache directory as --cppcheck-build-dir, creating it
+// first; without one, or if it can't be created, the scan runs uncached
+fn add_build_dir(cmd: &mut Command, settings: &ProjectSettings, project: &str, log: &TextBuffer) {
+    if settings.build_dir.is_empty() {
+        return;
+    }
+    // join keeps an absolute build_dir as it is
+    let dir = Path::new(project).join(&settings.build_dir);
+    match fs::create_dir_all(&dir) {
+        Ok(()) => {
+            cmd.arg(format!("--cppcheck-build-dir={}", dir.display()));
+            log_info(
+                log,
+                &format!("Caching analysis results in {}\n", dir.display()),
+            );
+        }
+        Err(e) => append_text(
+            log,
+            &format!(
+                "Failed to create cache directory {}: {}; scanning without it\n",
+                dir.display(),
+                e
+            ),
+        ),
+    }
+}
+
+// Directory to run cppcheck in for `project`, or None (logged) when the
+// configured one doesn't exist
+fn working_dir(settings: &ProjectSettings, project: &str, log: &TextBuffer) -> Option<PathBuf> {
+    // join keeps an absolute working_dir as it is
+    let dir = Path::new(project).join(&settings.working_dir);
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        append_text(
+            log,
+            &format!("Working directory {} does not exist\n", dir.display()),
+        );
+        None
+    }
+}
+
+// "error 2  warning 5" in the severity colors; "no findings" for none
+fn severity_counts_markup(counts: &BTreeMap<Severity, usize>) -> String {
+    let parts: Vec<String> = counts
+        .iter()
+        .map(|(severity, count)| {
+            format!(
+                "<span foreground=\"{}\">{} {}</span>",
+                severity.color(),
+                severity,
+                count
+            )
+        })
+        .collect();
+    if parts.is_empty() {
+        "no findings".to_string()
+    } else {
+        parts.join("  ")
+    }
+}
+
+fn progress_text(verb: &str, checked: usize, total: usize) -> String {
+    format!("{} {} of {} files", verb, checked.min(total), total)
+}
+
+fn threshold_level(scale: &Scale) -> Severity {
+    let index = scale.value().round() as usize;
+    Severity::THRESHOLDS[index.min(Severity::THRESHOLDS.len() - 1)]
+}
+
+// Template of `settings` to pass via --template, or None for cppcheck's
+// default output; unknown preset names mean the custom template, as in
+// ScanControls::apply
+fn selected_template(settings: &ProjectSettings) -> Option<String> {
+    match findings::TEMPLATE_PRESETS
+        .iter()
+        .find(|(name, _)| *name == settings.template)
+    {
+        Some((_, preset)) => preset.map(String::from),
+        None => Some(settings.custom_template.clone()).filter(|t| !t.trim().is_empty()),
+    }
+}
+
+// Per-process scratch directory for intermediate files
+fn temp_dir() -> PathBuf {
+    env::temp_dir().join(format!("cppcheck_gui-{}", std::process::id()))
+}
+
+// With auto-save on, write `findings` to a new timestamped file in the
+// configured directory
+fn auto_save_results(config: &Config, findings: &[Finding], log: &TextBuffer) {
+    if !config.auto_save {
+        return;
+    }
+    if config.auto_save_dir.is_empty() {
+        append_text(log, "Auto-save is on but no directory is set\n");
+        return;
+    }
+    let format = export::Format::parse(&config.auto_save_format).unwrap_or(export::Format::Json);
+    let stamp = glib::DateTime::now_local()
+        .and_then(|t| t.format("%Y%m%d-%H%M%S"))
+        .map(|t| t.to_string())
+        // Seconds since the epoch when the local time is unknown
+        .unwrap_or_else(|_| (glib::real_time() / 1_000_000).to_string());
+    let dir = Path::new(&config.auto_save_dir);
+    let contents = format.render(findings);
+    // Scans ending within the same second get "-2", "-3", … instead of
+    // overwriting each other
+    let mut attempt = 1;
+    let (path, result) = loop {
+        let suffix = match attempt {
+            1 => String::new(),
+            n => format!("-{}", n),
+        };
+        let path = dir.join(format!(
+            "cppcheck-results-{}{}.{}",
+            stamp,
+            suffix,
+            format.extension()
+        ));
+        let created = fs::create_dir_all(dir).and_then(|()| {
+            fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+        });
+        match created {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 1000 => attempt += 1,
+            Err(e) => break (path, Err(e)),
+            Ok(mut file) => {
+                let result = file.write_all(contents.as_bytes());
+                break (path, result);
+            }
+        }
+    };
+    match result {
+        Ok(()) => log_info(
+            log,
+            &format!(
+                "Saved {} finding(s) to {}\n",
+                findings.len(),
+                path.display()
+            ),
+        ),
+        Err(e) => append_text(
+            log,
+            &format!("Failed to auto-save results to {}: {}\n", path.display(), e),
+        ),
+    }
+}
+
+// "m:ss", or "h:mm:ss" from an hour on
+fn elapsed_text(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+// Percent-encoded file:// URI, safe for paths with spaces, '#' or '%'
+fn file_uri(path: &str) -> String {
+    gio::File::for_path(path).uri().to_string()
+}
+
+thread_local! {
+    // How much the GUI logs about itself, from Config::log_level
+    static LOG_LEVEL: Cell<LogLevel> = const { Cell::new(LogLevel::Normal) };
+}
+
+fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.with(|l| l.set(level));
+}
+
+thread_local! {
+    // Lines kept in a log view, from Config::max_log_lines
+    static MAX_LOG_LINES: Cell<u32> = const { Cell::new(config::DEFAULT_MAX_LOG_LINES) };
+    // Untrimmed copy on disk of each window's log, for Save Log
+    static LOG_FILES: RefCell<HashMap<TextBuffer, (PathBuf, fs::File)>> =
+        RefCell::new(HashMap::new());
+    // Output held back from paused log views
+    static PAUSED_LOGS: RefCell<HashMap<TextBuffer, PausedLog>> = RefCell::new(HashMap::new());
+}
+
+thread_local! {
+    // Scans and reports holding a BusyCursor, per window
+    static BUSY: RefCell<HashMap<gtk4::Root, u32>> = RefCell::new(HashMap::new());
+}
+
+// The progress cursor on the window of a widget while any of these for
+// that window lives
+struct BusyCursor(Option<gtk4::Root>);
+
+impl BusyCursor {
+    fn new(widget: &impl IsA<gtk4::Widget>) -> BusyCursor {
+        let root = widget.root();
+        if let Some(ref root) = root {
+            BUSY.with(|busy| *busy.borrow_mut().entry(root.clone()).or_insert(0) += 1);
+            root.set_cursor_from_name(Some("progress"));
+            // Shown even if the main loop is blocked next
+            WidgetExt::display(root).flush();
+        }
+        BusyCursor(root)
+    }
+}
+
+impl Drop for BusyCursor {
+    fn drop(&mut self) {
+        if let Some(ref root) = self.0 {
+            let idle = BUSY.with(|busy| {
+                let mut busy = busy.borrow_mut();
+                let count = busy.entry(root.clone()).or_insert(1);
+                *count -= 1;
+                let idle = *count == 0;
+                if idle {
+                    busy.remove(root);
+                }
+                idle
+            });
+            if idle {
+                root.set_cursor(None);
+            }
+        }
+    }
+}
+
+// Text appended while a log view is paused, at most MAX_LOG_LINES lines;
+// older ones would be trimmed from the view on resume anyway
+#[derive(Default)]
+struct PausedLog {
+    pending: VecDeque<String>,
+    lines: usize,
+    dropped: usize,
+}
+
+impl PausedLog {
+    fn push(&mut self, text: &str) {
+        let max = MAX_LOG_LINES.with(Cell::get) as usize;
+        self.lines += text.matches('\n').count();
+        self.pending.push_back(text.to_string());
+        while self.lines > max && self.pending.len() > 1 {
+            if let Some(old) = self.pending.pop_front() {
+                let n = old.matches('\n').count();
+                self.lines -= n;
+                self.dropped += n;
+            }
+        }
+    }
+}
+
+// While paused, text appended to `buffer` is held back; resuming adds it
+fn set_log_paused(buffer: &TextBuffer, paused: bool) {
+    if paused {
+        PAUSED_LOGS.with(|logs| {
+            logs.borrow_mut().entry(buffer.clone()).or_default();
+        });
+        return;
+    }
+    let Some(log) = PAUSED_LOGS.with(|logs| logs.borrow_mut().remove(buffer)) else {
+        return;
+    };
+    if log.dropped > 0 {
+        insert_log_text(
+            buffer,
+            &format!(
+                "… {} line(s) skipped while the output was paused; Save Log has them\n",
+                log.dropped
+            ),
+        );
+    }
+    let text: String = log.pending.into_iter().collect();
+    insert_log_text(buffer, &text);
+}
+
+fn set_max_log_lines(lines: u32) {
+    MAX_LOG_LINES.with(|l| l.set(lines));
+}
+
+// Mirror all text appended to `buffer` into a file under temp_dir(); without
+// one Save Log falls back to the lines still shown
+fn keep_log_on_disk(buffer: &TextBuffer) {
+    let path = temp_dir().join(format!("log-{}.txt", glib::monotonic_time()));
+    let file = fs::create_dir_all(temp_dir()).and_then(|_| fs::File::create(&path));
+    match file {
+        Ok(file) => LOG_FILES.with(|files| {
+            files.borrow_mut().insert(buffer.clone(), (path, file));
+        }),
+        Err(e) => append_text(
+            buffer,
+            &format!("Failed to create {}: {}\n", path.display(), e),
+        ),
+    }
+}
+
+// Drop the on-disk copy of a closed window's log
+fn forget_log(buffer: &TextBuffer) {
+    PAUSED_LOGS.with(|logs| logs.borrow_mut().remove(buffer));
+    if let Some((path, _)) = LOG_FILES.with(|files| files.borrow_mut().remove(buffer)) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn save_log(window: &ApplicationWindow, buffer: &TextBuffer) {
+    let dialog = FileChooserDialog::builder()
+        .title(tr("Save Log"))
+        .action(FileChooserAction::Save)
+        .transient_for(window)
+        .modal(true)
+        .build();
+    dialog.set_current_name("cppcheck-gui.log");
+    dialog.add_buttons(&[
+        (tr("Cancel"), ResponseType::Cancel),
+        (tr("Save"), ResponseType::Accept),
+    ]);
+    let buf_clone = buffer.clone();
+    dialog.connect_response(move |d, r| {
+        d.close();
+        if r != ResponseType::Accept {
+            return;
+        }
+        let Some(dest) = d.file().and_then(|f| f.path()) else {
+            return;
+        };
+        let source = LOG_FILES.with(|files| files.borrow().get(&buf_clone).map(|(p, _)| p.clone()));
+        let result = match source {
+            Some(path) => fs::copy(path, &dest).map(|_| ()),
+            None => {
+                let (start, end) = buf_clone.bounds();
+                fs::write(&dest, buf_clone.text(&start, &end, false))
+            }
+        };
+        match result {
+            Ok(()) => log_info(&buf_clone, &format!("Log saved to {}\n", dest.display())),
+            Err(e) => append_text(
+                &buf_clone,
+                &format!("Failed to write {}: {}\n", dest.display(), e),
+            ),
+        }
+    });
+    dialog.show();
+}
+
+// The directory named by pasted text: a plain or quoted path, ~/..., or a
+// file:// URI as file managers copy them. Relative paths are taken from the
+// current directory
+fn pasted_directory(text: &str) -> Result<String, String> {
+    let text = text.lines().next().unwrap_or("").trim();
+    let text = text
+        .strip_prefix(['"', '\''])
+        .and_then(|t| t.strip_suffix(['"', '\'']))
+        .unwrap_or(text);
+    if text.is_empty() {
+        return Err("the clipboard holds no path".to_string());
+    }
+    let path = if text.starts_with("file://") {
+        gio::File::for_uri(text)
+            .path()
+            .ok_or_else(|| format!("{} is not a local path", text))?
+    } else if let Some(rest) = text.strip_prefix("~/") {
+        let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+        Path::new(&home).join(rest)
+    } else {
+        PathBuf::from(text)
+    };
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    if !path.is_dir() {
+        return Err(format!("{} is not a directory", path.display()));
+    }
+    let path = std::path::absolute(&path).map_err(|e| e.to_string())?;
+    let path = path.to_string_lossy();
+    Ok(path
+        .strip_suffix('/')
+        .filter(|p| !p.is_empty())
+        .unwrap_or(&path)
+        .to_string())
+}
+
+// Log what the GUI is doing, unless the log is set to quiet
+fn log_info(buffer: &TextBuffer, text: &str) {
+    if LOG_LEVEL.with(Cell::get) >= LogLevel::Normal {
+        append_text(buffer, text);
+    }
+}
+
+// Log the command about to run, in debug mode only
+fn log_command(buffer: &TextBuffer, cmd: &Command) {
+    if LOG_LEVEL.with(Cell::get) == LogLevel::Debug {
+        append_text(buffer, &format!("$ {}\n", scan::describe(cmd)));
+    }
+}
+
+// Helper to append text to the TextView buffer. Past MAX_LOG_LINES the
+// oldest lines are dropped from the view; the copy on disk keeps them
+fn append_text(buffer: &TextBuffer, text: &str) {
+    LOG_FILES.with(|files| {
+        if let Some((_, file)) = files.borrow_mut().get_mut(buffer) {
+            let _ = file.write_all(text.as_bytes());
+        }
+    });
+    let held = PAUSED_LOGS.with(|logs| match logs.borrow_mut().get_mut(buffer) {
+        Some(log) => {
+            log.push(text);
+            true
+        }
+        None => false,
+    });
+    if !held {
+        insert_log_text(buffer, text);
+    }
+}
+
+// Add `text` to the view of the log, trimmed to MAX_LOG_LINES
+fn insert_log_text(buffer: &TextBuffer, text: &str) {
+    let mut iter = buffer.end_iter();
+    buffer.insert(&mut iter, text);
+    let excess = buffer.line_count() - MAX_LOG_LINES.with(Cell::get) as i32;
+    if excess > 0 {
+        let mut start = buffer.start_iter();
+        let mut end = buffer
+            .iter_at_line(excess)
+            .unwrap_or_else(|| buffer.end_iter());
+        buffer.delete(&mut start, &mut end);
+    }
 }
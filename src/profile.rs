@@ -0,0 +1,109 @@
+use crate::config::ProjectSettings;
+use crate::json::{self, Value};
+
+// A named set of scan settings, shared between machines as a JSON file.
+// Paths are stored as they are; relative ones resolve against the project
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    pub settings: ProjectSettings,
+}
+
+const FORMAT_VERSION: u32 = 1;
+
+impl Profile {
+    pub fn to_json(&self) -> String {
+        let s = &self.settings;
+        let list = |items: &[String]| {
+            let quoted: Vec<String> = items.iter().map(|i| json::quote(i)).collect();
+            format!("[{}]", quoted.join(", "))
+        };
+        format!(
+            "{{\n  \"version\": {},\n  \"name\": {},\n  \"enables\": {},\n  \
+             \"template\": {},\n  \"custom_template\": {},\n  \"clang\": {},\n  \
+             \"bug_hunting\": {},\n  \"check_headers\": {},\n  \"aggressive\": {},\n  \
+             \"rule_files\": {},\n  \"excludes\": {},\n  \"working_dir\": {},\n  \
+             \"build_dir\": {},\n  \"addons\": {},\n  \"libraries\": {},\n  \
+             \"compile_database\": {},\n  \"relative_paths\": {},\n  \
+             \"relative_base\": {},\n  \"enable_expression\": {}\n}}\n",
+            FORMAT_VERSION,
+            json::quote(&self.name),
+            list(&s.enables),
+            json::quote(&s.template),
+            json::quote(&s.custom_template),
+            s.clang,
+            s.bug_hunting,
+            s.check_headers,
+            s.aggressive,
+            list(&s.rule_files),
+            list(&s.excludes),
+            json::quote(&s.working_dir),
+            json::quote(&s.build_dir),
+            list(&s.addons),
+            list(&s.libraries),
+            json::quote(&s.compile_database),
+            s.relative_paths,
+            json::quote(&s.relative_base),
+            json::quote(&s.enable_expression),
+        )
+    }
+
+    // Profile from a file written by `to_json`; missing settings keep
+    // their defaults so older and hand-written profiles still load
+    pub fn from_json(text: &str) -> Result<Profile, String> {
+        let value = json::parse(text)?;
+        if !matches!(value, Value::Object(_)) {
+            return Err("expected a profile object".to_string());
+        }
+        if let Some(version) = value.get("version").and_then(Value::as_u32) {
+            if version > FORMAT_VERSION {
+                return Err(format!("unsupported profile version {}", version));
+            }
+        }
+        let string = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .map(String::from)
+                .unwrap_or_default()
+        };
+        let flag = |key: &str| value.get(key).and_then(Value::as_bool).unwrap_or(false);
+        let list = |key: &str| -> Result<Vec<String>, String> {
+            let Some(items) = value.get(key) else {
+                return Ok(Vec::new());
+            };
+            items
+                .as_array()
+                .ok_or_else(|| format!("\"{}\" is not a list", key))?
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .map(String::from)
+                        .ok_or_else(|| format!("\"{}\" holds a non-string", key))
+                })
+                .collect()
+        };
+        Ok(Profile {
+            name: string("name"),
+            settings: ProjectSettings {
+                enables: list("enables")?,
+                template: string("template"),
+                custom_template: string("custom_template"),
+                clang: flag("clang"),
+                bug_hunting: flag("bug_hunting"),
+                check_headers: flag("check_headers"),
+                aggressive: flag("aggressive"),
+                rule_files: list("rule_files")?,
+                excludes: list("excludes")?,
+                working_dir: string("working_dir"),
+                build_dir: string("build_dir"),
+                addons: list("addons")?,
+                libraries: list("libraries")?,
+                compile_database: string("compile_database"),
+                relative_paths: flag("relative_paths"),
+                relative_base: string("relative_base"),
+                enable_expression: string("enable_expression"),
+            },
+        })
+    }
+}
@@ -0,0 +1,1597 @@
+use crate::append_text;
+use crate::archive::Extracted;
+use crate::config::FilterView;
+use crate::export::{self, Format};
+use crate::findings::{Finding, Severity};
+use crate::glob;
+use crate::i18n::tr;
+use crate::line_range::LineRange;
+use crate::suppress;
+use gio::AppInfo;
+use gtk4::prelude::*;
+use gtk4::{
+    gdk, Align, Box as GtkBox, Button, ButtonsType, CellRendererText, CellRendererToggle,
+    CheckButton, ComboBoxText, DialogFlags, Entry, FileChooserAction, FileChooserDialog,
+    GestureClick, Label, MenuButton, MessageDialog, MessageType, Orientation, Popover,
+    ResponseType, ScrolledWindow, TextBuffer, TextView, TreeIter, TreePath, TreeStore, TreeView,
+    TreeViewColumn, Window,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// Columns of the results store
+const COL_SEVERITY: u32 = 0;
+const COL_FILE: u32 = 1;
+const COL_LINE: u32 = 2;
+const COL_COLUMN: u32 = 3;
+const COL_ID: u32 = 4;
+const COL_MESSAGE: u32 = 5;
+// Index into `findings`, not displayed; NO_FINDING on group rows
+const COL_INDEX: u32 = 6;
+// Row foreground color for the severity
+const COL_COLOR: u32 = 7;
+const COL_REVIEWED: u32 = 8;
+// Markup tooltip with the full message and CWE
+const COL_TOOLTIP: u32 = 9;
+// Pango weight: bold for findings on recently changed lines
+const COL_WEIGHT: u32 = 10;
+// Triage note of the finding, editable in place
+const COL_NOTE: u32 = 11;
+
+const WEIGHT_NORMAL: i32 = 400;
+const WEIGHT_RECENT: i32 = 700;
+
+// COL_INDEX of a row standing for a group of duplicates
+const NO_FINDING: u32 = u32::MAX;
+
+// Lines of source shown around a selected finding
+const PREVIEW_CONTEXT: usize = 2;
+
+// Results list with a session-level set of hidden rule ids
+#[derive(Clone)]
+pub struct ResultsView {
+    pub widget: GtkBox,
+    tree: TreeView,
+    // Flat unless "Group duplicates" is on; then identical findings are
+    // children of one group row
+    store: TreeStore,
+    findings: Rc<RefCell<Vec<Finding>>>,
+    hidden_ids: Rc<RefCell<BTreeSet<String>>>,
+    // Review keys of findings marked as reviewed, kept across runs
+    reviewed: Rc<RefCell<BTreeSet<String>>>,
+    // Triage notes by review key, kept across runs and exported
+    notes: Rc<RefCell<BTreeMap<String, String>>>,
+    // (file, line) of findings on lines changed recently, per git blame
+    recent: Rc<RefCell<HashSet<(String, u32)>>>,
+    // Findings ranked below this severity are not shown
+    threshold: Rc<Cell<Severity>>,
+    // Only findings whose file matches this glob are shown; empty for all
+    file_glob: Rc<RefCell<String>>,
+    glob_entry: Entry,
+    // Only findings in these lines are shown, after Check Line Range
+    line_range: Rc<RefCell<Option<LineRange>>>,
+    range_label: Label,
+    range_box: GtkBox,
+    hidden_btn: MenuButton,
+    // Named filter views offered in `views_combo`
+    views: Rc<RefCell<Vec<FilterView>>>,
+    views_combo: ComboBoxText,
+    delete_view_btn: Button,
+    // Every column with its title, which also names it in the config
+    columns: Vec<(&'static str, TreeViewColumn)>,
+    // Export only the rows currently shown, in their displayed order
+    export_filtered: CheckButton,
+    // Replace the project directory in exported paths and messages
+    anonymize: CheckButton,
+    // Directory of the open project, for `anonymize`
+    project_root: Rc<RefCell<Option<String>>>,
+    // Where relative finding paths start; the project directory when unset
+    source_base: Rc<RefCell<Option<PathBuf>>>,
+    archive: Rc<RefCell<Option<Extracted>>>,
+    group_duplicates: CheckButton,
+    collapse_repeats: CheckButton,
+    // List errors first, until the user sorts by a column this session
+    severity_first: Rc<Cell<bool>>,
+    sorted_by_user: Rc<Cell<bool>>,
+    context_menu: Popover,
+    // Inline suppressions inserted this session, newest last, and the
+    // toolbar button taking the newest out again
+    inserted: Rc<RefCell<Vec<InsertedSuppression>>>,
+    undo_btn: Button,
+    preview: TextBuffer,
+    log: TextBuffer,
+    add_suppression_handler: Rc<RefCell<Option<FindingHandler>>>,
+    open_source_handler: Rc<RefCell<Option<FindingHandler>>>,
+    show_folder_handler: Rc<RefCell<Option<FindingHandler>>>,
+    exclude_file_handler: Rc<RefCell<Option<FindingHandler>>>,
+    reviewed_handler: Rc<RefCell<Option<ReviewedHandler>>>,
+    notes_handler: Rc<RefCell<Option<NotesHandler>>>,
+    columns_handler: Rc<RefCell<Option<ColumnsHandler>>>,
+    views_handler: Rc<RefCell<Option<ViewsHandler>>>,
+    threshold_handler: Rc<RefCell<Option<ThresholdHandler>>>,
+}
+
+// A "// cppcheck-suppress id" comment at the 1-based `line` of `file`
+struct InsertedSuppression {
+    file: String,
+    line: u32,
+    id: String,
+}
+
+type FindingHandler = Box<dyn Fn(&Finding)>;
+type ReviewedHandler = Box<dyn Fn(&BTreeSet<String>)>;
+type NotesHandler = Box<dyn Fn(&BTreeMap<String, String>)>;
+type ColumnsHandler = Box<dyn Fn(Vec<String>)>;
+type ViewsHandler = Box<dyn Fn(&[FilterView])>;
+type ThresholdHandler = Box<dyn Fn(Severity)>;
+
+impl ResultsView {
+    // `archive` is the window's last unpacked archive, whose findings show
+    // paths inside it
+    pub fn new(log: &TextBuffer, archive: &Rc<RefCell<Option<Extracted>>>) -> Self {
+        let store = TreeStore::new(&[
+            String::static_type(),
+            String::static_type(),
+            u32::static_type(),
+            u32::static_type(),
+            String::static_type(),
+            String::static_type(),
+            u32::static_type(),
+            String::static_type(),
+            bool::static_type(),
+            String::static_type(),
+            i32::static_type(),
+            String::static_type(),
+        ]);
+        let tree = TreeView::with_model(&store);
+        tree.set_tooltip_column(COL_TOOLTIP as i32);
+        let reviewed_cell = CellRendererToggle::new();
+        reviewed_cell.set_activatable(true);
+        let reviewed_column = TreeViewColumn::new();
+        reviewed_column.set_title("Reviewed");
+        reviewed_column.pack_start(&reviewed_cell, false);
+        reviewed_column.add_attribute(&reviewed_cell, "active", COL_REVIEWED as i32);
+        reviewed_column.set_sort_column_id(COL_REVIEWED as i32);
+        tree.append_column(&reviewed_column);
+        let mut columns = vec![("Reviewed", reviewed_column)];
+        for (title, col) in [
+            ("Severity", COL_SEVERITY),
+            ("File", COL_FILE),
+            ("Line", COL_LINE),
+            ("Column", COL_COLUMN),
+            ("Id", COL_ID),
+            ("Message", COL_MESSAGE),
+        ] {
+            let cell = CellRendererText::new();
+            let column = TreeViewColumn::new();
+            column.set_title(title);
+            column.pack_start(&cell, true);
+            column.add_attribute(&cell, "text", col as i32);
+            column.add_attribute(&cell, "foreground", COL_COLOR as i32);
+            column.add_attribute(&cell, "weight", COL_WEIGHT as i32);
+            column.set_sort_column_id(col as i32);
+            column.set_resizable(true);
+            tree.append_column(&column);
+            columns.push((title, column));
+        }
+        let note_cell = CellRendererText::new();
+        note_cell.set_editable(true);
+        note_cell.set_placeholder_text(Some("Add a note"));
+        let note_column = TreeViewColumn::new();
+        note_column.set_title("Notes");
+        note_column.pack_start(&note_cell, true);
+        note_column.add_attribute(&note_cell, "text", COL_NOTE as i32);
+        note_column.set_sort_column_id(COL_NOTE as i32);
+        note_column.set_resizable(true);
+        tree.append_column(&note_column);
+        columns.push(("Notes", note_column));
+
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&tree));
+
+        // Review and clear hidden ids
+        let hidden_btn = MenuButton::new();
+        hidden_btn.set_popover(Some(&Popover::new()));
+        let export_btn = MenuButton::new();
+        export_btn.set_label(tr("Export"));
+        let export_filtered = CheckButton::with_label(tr("Export filtered view"));
+        let anonymize = CheckButton::with_label(tr("Anonymize paths"));
+        anonymize.set_tooltip_text(Some(&format!(
+            "Write the project directory as {} and other absolute directories as \
+             {}, for sharing reports without the local directory layout",
+            export::PROJECT_PLACEHOLDER,
+            export::EXTERNAL_PLACEHOLDER
+        )));
+        let export_menu = GtkBox::new(Orientation::Vertical, 4);
+        export_menu.append(&export_filtered);
+        export_menu.append(&anonymize);
+        let export_popover = Popover::new();
+        export_popover.set_child(Some(&export_menu));
+        export_btn.set_popover(Some(&export_popover));
+        // Collapse findings with the same id and message into one row
+        let group_duplicates = CheckButton::with_label(tr("Group duplicates"));
+        // Keep one finding per file, line and id, e.g. of a header analysed
+        // again in every translation unit that includes it
+        let collapse_repeats = CheckButton::with_label(tr("Collapse header duplicates"));
+        collapse_repeats.set_tooltip_text(Some(tr(
+            "Show a finding reported at the same file, line and id only once",
+        )));
+        // Show or hide single columns
+        let columns_btn = MenuButton::new();
+        columns_btn.set_label(tr("Columns"));
+        let columns_menu = GtkBox::new(Orientation::Vertical, 4);
+        let columns_popover = Popover::new();
+        columns_popover.set_child(Some(&columns_menu));
+        columns_btn.set_popover(Some(&columns_popover));
+        let glob_entry = Entry::new();
+        glob_entry.set_placeholder_text(Some(tr("Files matching, e.g. src/net/*")));
+        glob_entry.set_tooltip_text(Some(
+            "Show only findings in files matching this pattern: * and ? stay within \
+             a directory, ** spans directories. Relative patterns may match from any \
+             directory on",
+        ));
+        // Saved combinations of threshold, file pattern and hidden ids
+        let views_combo = ComboBoxText::new();
+        views_combo.set_tooltip_text(Some(tr("Apply a saved filter view")));
+        let save_view_btn = MenuButton::new();
+        save_view_btn.set_label(tr("Save View…"));
+        save_view_btn.set_tooltip_text(Some(
+            "Save the severity threshold, file pattern and hidden ids under a name",
+        ));
+        let view_name = Entry::new();
+        view_name.set_placeholder_text(Some("View name"));
+        let save_btn = Button::with_label(tr("Save"));
+        let save_menu = GtkBox::new(Orientation::Horizontal, 4);
+        save_menu.append(&view_name);
+        save_menu.append(&save_btn);
+        let save_popover = Popover::new();
+        save_popover.set_child(Some(&save_menu));
+        save_view_btn.set_popover(Some(&save_popover));
+        let delete_view_btn = Button::with_label(tr("Delete View"));
+        delete_view_btn.set_sensitive(false);
+        let range_label = Label::new(None);
+        let clear_range_btn = Button::with_label(tr("Show All Lines"));
+        let range_box = GtkBox::new(Orientation::Horizontal, 4);
+        range_box.append(&range_label);
+        range_box.append(&clear_range_btn);
+        range_box.set_visible(false);
+        let toolbar = GtkBox::new(Orientation::Horizontal, 4);
+        toolbar.append(&range_box);
+        toolbar.append(&views_combo);
+        toolbar.append(&save_view_btn);
+        toolbar.append(&delete_view_btn);
+        toolbar.append(&glob_entry);
+        toolbar.append(&hidden_btn);
+        toolbar.append(&export_btn);
+        toolbar.append(&columns_btn);
+        toolbar.append(&group_duplicates);
+        toolbar.append(&collapse_repeats);
+        // Open or close every group row at once
+        let expand_btn = Button::with_label(tr("Expand All"));
+        let collapse_btn = Button::with_label(tr("Collapse All"));
+        for button in [&expand_btn, &collapse_btn] {
+            button.set_sensitive(false);
+            toolbar.append(button);
+        }
+        let undo_btn = Button::with_label(tr("Undo Suppress"));
+        undo_btn.set_sensitive(false);
+        toolbar.append(&undo_btn);
+        toolbar.append(&severity_legend());
+
+        // Source preview of the selected finding
+        let preview_view = TextView::new();
+        preview_view.set_editable(false);
+        preview_view.set_monospace(true);
+        let preview_scrolled = ScrolledWindow::new();
+        preview_scrolled.set_min_content_height(100);
+        preview_scrolled.set_child(Some(&preview_view));
+
+        let widget = GtkBox::new(Orientation::Vertical, 4);
+        widget.append(&toolbar);
+        widget.append(&scrolled);
+        widget.append(&preview_scrolled);
+
+        let context_menu = Popover::new();
+        context_menu.set_parent(&tree);
+        context_menu.set_has_arrow(false);
+
+        let view = ResultsView {
+            widget,
+            tree,
+            store,
+            findings: Rc::new(RefCell::new(Vec::new())),
+            hidden_ids: Rc::new(RefCell::new(BTreeSet::new())),
+            reviewed: Rc::new(RefCell::new(BTreeSet::new())),
+            notes: Rc::new(RefCell::new(BTreeMap::new())),
+            recent: Rc::new(RefCell::new(HashSet::new())),
+            threshold: Rc::new(Cell::new(Severity::Information)),
+            file_glob: Rc::new(RefCell::new(String::new())),
+            glob_entry: glob_entry.clone(),
+            line_range: Rc::new(RefCell::new(None)),
+            range_label,
+            range_box,
+            hidden_btn,
+            views: Rc::new(RefCell::new(Vec::new())),
+            views_combo,
+            delete_view_btn,
+            columns,
+            export_filtered,
+            anonymize,
+            project_root: Rc::new(RefCell::new(None)),
+            source_base: Rc::new(RefCell::new(None)),
+            archive: archive.clone(),
+            group_duplicates,
+            collapse_repeats,
+            severity_first: Rc::new(Cell::new(false)),
+            sorted_by_user: Rc::new(Cell::new(false)),
+            context_menu,
+            inserted: Rc::new(RefCell::new(Vec::new())),
+            undo_btn,
+            preview: preview_view.buffer(),
+            log: log.clone(),
+            add_suppression_handler: Rc::new(RefCell::new(None)),
+            open_source_handler: Rc::new(RefCell::new(None)),
+            show_folder_handler: Rc::new(RefCell::new(None)),
+            exclude_file_handler: Rc::new(RefCell::new(None)),
+            reviewed_handler: Rc::new(RefCell::new(None)),
+            notes_handler: Rc::new(RefCell::new(None)),
+            columns_handler: Rc::new(RefCell::new(None)),
+            views_handler: Rc::new(RefCell::new(None)),
+            threshold_handler: Rc::new(RefCell::new(None)),
+        };
+
+        {
+            let view_clone = view.clone();
+            view.undo_btn
+                .connect_clicked(move |_| view_clone.undo_suppress());
+        }
+
+        for (title, column) in &view.columns {
+            let chk = CheckButton::with_label(title);
+            column
+                .bind_property("visible", &chk, "active")
+                .sync_create()
+                .build();
+            let view_clone = view.clone();
+            let column_clone = column.clone();
+            chk.connect_toggled(move |chk| {
+                column_clone.set_visible(chk.is_active());
+                view_clone.columns_changed();
+            });
+            columns_menu.append(&chk);
+        }
+
+        for format in Format::ALL {
+            let btn = Button::with_label(&format!("Export {}…", format.name()));
+            btn.set_has_frame(false);
+            if format == Format::Summary {
+                btn.set_tooltip_text(Some(
+                    "Markdown page with the counts per severity and the files and \
+                     ids with the most findings",
+                ));
+            }
+            let view_clone = view.clone();
+            let popover_clone = export_popover.clone();
+            btn.connect_clicked(move |_| {
+                popover_clone.popdown();
+                view_clone.choose_export_file(format);
+            });
+            export_menu.append(&btn);
+        }
+        let browser_btn = Button::with_label(tr("Open in Browser"));
+        browser_btn.set_has_frame(false);
+        browser_btn.set_tooltip_text(Some(
+            "Show the findings as a standalone HTML table in the default browser; \
+             works without cppcheck-htmlreport",
+        ));
+        {
+            let view_clone = view.clone();
+            let popover_clone = export_popover.clone();
+            browser_btn.connect_clicked(move |_| {
+                popover_clone.popdown();
+                view_clone.open_in_browser();
+            });
+        }
+        export_menu.append(&browser_btn);
+
+        {
+            let view_clone = view.clone();
+            glob_entry.connect_changed(move |entry| {
+                *view_clone.file_glob.borrow_mut() = entry.text().trim().to_string();
+                view_clone.refresh();
+            });
+        }
+
+        {
+            let view_clone = view.clone();
+            let popover_clone = save_popover.clone();
+            let save = move |entry: &Entry| {
+                let name = entry.text().trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                popover_clone.popdown();
+                entry.set_text("");
+                view_clone.save_view(&name);
+            };
+            let entry = view_name.clone();
+            let save_clone = save.clone();
+            save_btn.connect_clicked(move |_| save_clone(&entry));
+            view_name.connect_activate(save);
+        }
+        {
+            let view_clone = view.clone();
+            view.views_combo.connect_changed(move |combo| {
+                view_clone
+                    .delete_view_btn
+                    .set_sensitive(combo.active().is_some());
+                if let Some(index) = combo.active() {
+                    view_clone.apply_view(index as usize);
+                }
+            });
+        }
+        {
+            let view_clone = view.clone();
+            clear_range_btn.connect_clicked(move |_| view_clone.set_line_range(None));
+        }
+        {
+            let view_clone = view.clone();
+            view.delete_view_btn
+                .connect_clicked(move |_| view_clone.delete_view());
+        }
+
+        {
+            let tree = view.tree.clone();
+            expand_btn.connect_clicked(move |_| tree.expand_all());
+        }
+        {
+            let tree = view.tree.clone();
+            collapse_btn.connect_clicked(move |_| tree.collapse_all());
+        }
+        // Only grouped results have rows to open
+        view.group_duplicates.connect_toggled(move |check| {
+            expand_btn.set_sensitive(check.is_active());
+            collapse_btn.set_sensitive(check.is_active());
+        });
+        for check in [&view.group_duplicates, &view.collapse_repeats] {
+            let view_clone = view.clone();
+            check.connect_toggled(move |_| view_clone.refresh());
+        }
+
+        {
+            let view_clone = view.clone();
+            reviewed_cell.connect_toggled(move |_, path| view_clone.toggle_reviewed(&path));
+        }
+        {
+            let view_clone = view.clone();
+            note_cell.connect_edited(move |_, path, text| view_clone.set_note(&path, text));
+        }
+
+        // Double-click or Enter on a row opens its source, or expands a group
+        {
+            let view_clone = view.clone();
+            view.tree.connect_row_activated(move |tree, path, _| {
+                let Some(iter) = view_clone.store.iter(path) else {
+                    return;
+                };
+                let index = view_clone.store.get::<u32>(&iter, COL_INDEX as i32);
+                if index == NO_FINDING {
+                    if tree.row_expanded(path) {
+                        tree.collapse_row(path);
+                    } else {
+                        tree.expand_row(path, false);
+                    }
+                    return;
+                }
+                let index = index as usize;
+                let finding = view_clone.findings.borrow().get(index).cloned();
+                if let Some(finding) = finding {
+                    view_clone.open_source(&finding);
+                }
+            });
+        }
+
+        // Right-click on a row opens the context menu
+        let gesture = GestureClick::new();
+        gesture.set_button(gdk::BUTTON_SECONDARY);
+        {
+            let view_clone = view.clone();
+            gesture.connect_pressed(move |_, _, x, y| {
+                if let Some(finding) = view_clone.finding_at(x, y) {
+                    view_clone.show_context_menu(&finding, x, y);
+                }
+            });
+        }
+        view.tree.add_controller(gesture);
+
+        {
+            let view_clone = view.clone();
+            view.tree.selection().connect_changed(move |selection| {
+                let finding = selection.selected().and_then(|(model, iter)| {
+                    let index = model.get::<u32>(&iter, COL_INDEX as i32) as usize;
+                    view_clone.findings.borrow().get(index).cloned()
+                });
+                view_clone.show_preview(finding.as_ref());
+            });
+        }
+
+        {
+            let sorted = view.sorted_by_user.clone();
+            view.store
+                .connect_sort_column_changed(move |_| sorted.set(true));
+        }
+
+        view.update_hidden_menu();
+        view
+    }
+
+    // Called with the finding chosen via "Add to suppressions file"
+    pub fn connect_add_suppression<F: Fn(&Finding) + 'static>(&self, f: F) {
+        *self.add_suppression_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with the finding whose source should be shown
+    pub fn connect_open_source<F: Fn(&Finding) + 'static>(&self, f: F) {
+        *self.open_source_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with the finding chosen via "Show in file manager"
+    pub fn connect_show_in_folder<F: Fn(&Finding) + 'static>(&self, f: F) {
+        *self.show_folder_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with the finding chosen via "Exclude this file from future scans"
+    pub fn connect_exclude_file<F: Fn(&Finding) + 'static>(&self, f: F) {
+        *self.exclude_file_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with the full reviewed set whenever a row is (un)marked
+    pub fn connect_reviewed_changed<F: Fn(&BTreeSet<String>) + 'static>(&self, f: F) {
+        *self.reviewed_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with all notes after one is edited
+    pub fn connect_notes_changed<F: Fn(&BTreeMap<String, String>) + 'static>(&self, f: F) {
+        *self.notes_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with the titles of the hidden columns after one is toggled
+    pub fn connect_columns_changed<F: Fn(Vec<String>) + 'static>(&self, f: F) {
+        *self.columns_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with every filter view after one is saved or deleted
+    pub fn connect_filter_views_changed<F: Fn(&[FilterView]) + 'static>(&self, f: F) {
+        *self.views_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    // Called with the threshold of a view being applied, which the owner of
+    // the threshold control sets; without a handler it only filters here
+    pub fn connect_apply_threshold<F: Fn(Severity) + 'static>(&self, f: F) {
+        *self.threshold_handler.borrow_mut() = Some(Box::new(f));
+    }
+
+    pub fn set_filter_views(&self, views: Vec<FilterView>) {
+        *self.views.borrow_mut() = views;
+        self.update_views_combo(None);
+    }
+
+    pub fn set_hidden_columns(&self, hidden: &[String]) {
+        for (title, column) in &self.columns {
+            column.set_visible(!hidden.iter().any(|h| h == title));
+        }
+    }
+
+    fn columns_changed(&self) {
+        let hidden: Vec<String> = self
+            .columns
+            .iter()
+            .filter(|(_, column)| !column.is_visible())
+            .map(|(title, _)| title.to_string())
+            .collect();
+        if let Some(ref handler) = *self.columns_handler.borrow() {
+            handler(hidden);
+        }
+    }
+
+    pub fn set_reviewed(&self, reviewed: BTreeSet<String>) {
+        *self.reviewed.borrow_mut() = reviewed;
+        self.refresh();
+    }
+
+    pub fn set_notes(&self, notes: BTreeMap<String, String>) {
+        *self.notes.borrow_mut() = notes;
+        self.refresh();
+    }
+
+    pub fn set_severity_first(&self, on: bool) {
+        self.severity_first.set(on);
+    }
+
+    pub fn set_findings(&self, findings: Vec<Finding>) {
+        *self.findings.borrow_mut() = findings;
+        self.recent.borrow_mut().clear();
+        self.refresh();
+    }
+
+    // Copy the verbose message and CWE of matching XML findings onto the
+    // findings shown, which come from text output that lacks them
+    pub fn add_details(&self, details: &[Finding]) {
+        let mut changed = false;
+        for f in self.findings.borrow_mut().iter_mut() {
+            let Some(d) = details
+                .iter()
+                .find(|d| d.file == f.file && d.line == f.line && d.id == f.id)
+            else {
+                continue;
+            };
+            if d.verbose.is_some() || d.cwe.is_some() || d.inconclusive {
+                f.verbose = d.verbose.clone();
+                f.cwe = d.cwe;
+                f.inconclusive |= d.inconclusive;
+                changed = true;
+            }
+        }
+        if changed {
+            self.refresh();
+        }
+    }
+
+    // Set after set_findings, once git blame has run
+    pub fn set_recent(&self, recent: HashSet<(String, u32)>) {
+        *self.recent.borrow_mut() = recent;
+        self.refresh();
+    }
+
+    pub fn line_range(&self) -> Option<LineRange> {
+        self.line_range.borrow().clone()
+    }
+
+    pub fn set_line_range(&self, range: Option<LineRange>) {
+        match range {
+            Some(ref r) => self
+                .range_label
+                .set_text(&format!("Showing {}", r.describe())),
+            None if self.line_range.borrow().is_none() => return,
+            None => {}
+        }
+        self.range_box.set_visible(range.is_some());
+        *self.line_range.borrow_mut() = range;
+        self.refresh();
+    }
+
+    pub fn set_threshold(&self, threshold: Severity) {
+        if self.threshold.replace(threshold) != threshold {
+            self.refresh();
+        }
+    }
+
+    // Distinct files, in result order, with a finding of `severity`
+    pub fn files_with(&self, severity: Severity) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for f in self.findings.borrow().iter() {
+            if f.severity == severity && f.file != "nofile" && !files.contains(&f.file) {
+                files.push(f.file.clone());
+            }
+        }
+        files
+    }
+
+    // Select and scroll to the first shown finding whose file contains
+    // `fragment` (case-insensitive); with `next`, start after the current
+    // selection and wrap around
+    pub fn jump_to_file(&self, fragment: &str, next: bool) -> bool {
+        let fragment = fragment.trim().to_lowercase();
+        if fragment.is_empty() {
+            return false;
+        }
+        let paths: Vec<TreePath> = self
+            .finding_rows()
+            .iter()
+            .filter(|iter| {
+                let file = self.store.get::<String>(iter, COL_FILE as i32);
+                file.to_lowercase().contains(&fragment)
+            })
+            .map(|iter| self.store.path(iter))
+            .collect();
+        let current = self
+            .tree
+            .selection()
+            .selected()
+            .map(|(model, iter)| model.path(&iter));
+        let target = match current {
+            Some(ref current) if next => paths
+                .iter()
+                .find(|p| *p > current)
+                .or_else(|| paths.first()),
+            _ => paths.first(),
+        };
+        let Some(path) = target else {
+            return false;
+        };
+        self.tree.expand_to_path(path);
+        self.tree.selection().select_path(path);
+        self.tree
+            .scroll_to_cell(Some(path), None::<&TreeViewColumn>, false, 0.0, 0.0);
+        true
+    }
+
+    // Select the shown finding after (or before) the current one, wrapping
+    // at the ends: None for an empty list, Some(true) if it wrapped around
+    pub fn select_adjacent(&self, forward: bool) -> Option<bool> {
+        let paths: Vec<TreePath> = self
+            .finding_rows()
+            .iter()
+            .map(|iter| self.store.path(iter))
+            .collect();
+        let (first, last) = (paths.first()?, paths.last()?);
+        let current = self
+            .tree
+            .selection()
+            .selected()
+            .map(|(model, iter)| model.path(&iter));
+        // A selected group row counts as the position of its first location
+        let (path, wrapped) = match current {
+            None if forward => (first, false),
+            None => (last, false),
+            Some(ref c) if forward => match paths.iter().find(|p| *p > c) {
+                Some(p) => (p, false),
+                None => (first, true),
+            },
+            Some(ref c) => match paths.iter().rev().find(|p| *p < c) {
+                Some(p) => (p, false),
+                None => (last, true),
+            },
+        };
+        let path = path.clone();
+        self.tree.expand_to_path(&path);
+        self.tree.selection().select_path(&path);
+        self.tree
+            .scroll_to_cell(Some(&path), None::<&TreeViewColumn>, false, 0.0, 0.0);
+        Some(wrapped)
+    }
+
+    fn refresh(&self) {
+        self.store.clear();
+        {
+            let hidden = self.hidden_ids.borrow();
+            let reviewed = self.reviewed.borrow();
+            let notes = self.notes.borrow();
+            let note = |f: &Finding| notes.get(&f.review_key()).cloned().unwrap_or_default();
+            let recent = self.recent.borrow();
+            let max_rank = self.threshold.get().rank();
+            let file_glob = self.file_glob.borrow();
+            let line_range = self.line_range.borrow();
+            let findings = self.findings.borrow();
+            let mut shown: Vec<usize> = findings
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| !hidden.contains(&f.id) && f.severity.rank() <= max_rank)
+                .filter(|(_, f)| file_glob.is_empty() || glob::matches_path(&file_glob, &f.file))
+                .filter(|(_, f)| {
+                    line_range
+                        .as_ref()
+                        .is_none_or(|r| r.contains(&f.file, f.line))
+                })
+                .map(|(i, _)| i)
+                .collect();
+            if self.collapse_repeats.is_active() {
+                let mut seen = HashSet::new();
+                shown.retain(|&i| {
+                    let f = &findings[i];
+                    seen.insert((f.file.as_str(), f.line, f.id.as_str()))
+                });
+            }
+            // Stable, so findings of one severity keep cppcheck's order
+            if self.severity_first.get() && !self.sorted_by_user.get() {
+                shown.sort_by_key(|&i| findings[i].severity.rank());
+            }
+            // Groups of finding indices in order of first occurrence
+            let mut groups: Vec<Vec<usize>> = Vec::new();
+            if self.group_duplicates.is_active() {
+                let mut group_of: HashMap<(&str, &str), usize> = HashMap::new();
+                for &i in &shown {
+                    let f = &findings[i];
+                    let key = (f.id.as_str(), f.message.as_str());
+                    match group_of.get(&key) {
+                        Some(&group) => groups[group].push(i),
+                        None => {
+                            group_of.insert(key, groups.len());
+                            groups.push(vec![i]);
+                        }
+                    }
+                }
+            } else {
+                groups = shown.into_iter().map(|i| vec![i]).collect();
+            }
+            for group in groups {
+                let parent = (group.len() > 1).then(|| {
+                    let f = &findings[group[0]];
+                    let all_reviewed = group
+                        .iter()
+                        .all(|&i| reviewed.contains(&findings[i].review_key()));
+                    let iter = self.store.append(None);
+                    self.store.set(
+                        &iter,
+                        &[
+                            (COL_SEVERITY, &f.severity.as_str()),
+                            (COL_FILE, &format!("{} locations", group.len())),
+                            (COL_LINE, &0u32),
+                            (COL_COLUMN, &0u32),
+                            (COL_ID, &f.id),
+                            (COL_MESSAGE, &f.message),
+                            (COL_INDEX, &NO_FINDING),
+                            (COL_COLOR, &f.severity.color()),
+                            (COL_REVIEWED, &all_reviewed),
+                            (COL_TOOLTIP, &row_tooltip(f)),
+                            (COL_WEIGHT, &WEIGHT_NORMAL),
+                            (COL_NOTE, &note(f)),
+                        ],
+                    );
+                    iter
+                });
+                for i in group {
+                    let f = &findings[i];
+                    let is_recent = recent.contains(&(f.file.clone(), f.line));
+                    let mut tooltip = row_tooltip(f);
+                    if is_recent {
+                        tooltip.push_str("\n<i>On a recently changed line</i>");
+                    }
+                    let weight = if is_recent {
+                        WEIGHT_RECENT
+                    } else {
+                        WEIGHT_NORMAL
+                    };
+                    self.store.set(
+                        &self.store.append(parent.as_ref()),
+                        &[
+                            (COL_SEVERITY, &severity_text(f)),
+                            (COL_FILE, &f.file),
+                            (COL_LINE, &f.line),
+                            (COL_COLUMN, &f.column),
+                            (COL_ID, &f.id),
+                            (COL_MESSAGE, &f.message),
+                            (COL_INDEX, &(i as u32)),
+                            (COL_COLOR, &f.severity.color()),
+                            (COL_REVIEWED, &reviewed.contains(&f.review_key())),
+                            (COL_TOOLTIP, &tooltip),
+                            (COL_WEIGHT, &weight),
+                            (COL_NOTE, &note(f)),
+                        ],
+                    );
+                }
+            }
+        }
+        self.update_hidden_menu();
+    }
+
+    // Rows standing for one finding, in display order; group rows are
+    // replaced by their children
+    fn finding_rows(&self) -> Vec<TreeIter> {
+        let mut rows = Vec::new();
+        for iter in self.children(None) {
+            let children = self.children(Some(&iter));
+            if children.is_empty() {
+                rows.push(iter);
+            } else {
+                rows.extend(children);
+            }
+        }
+        rows
+    }
+
+    // Direct children of `parent`, or the top-level rows
+    fn children(&self, parent: Option<&TreeIter>) -> Vec<TreeIter> {
+        let mut rows = Vec::new();
+        if let Some(iter) = self.store.iter_children(parent) {
+            loop {
+                rows.push(iter);
+                if !self.store.iter_next(&iter) {
+                    break;
+                }
+            }
+        }
+        rows
+    }
+
+    fn toggle_reviewed(&self, path: &TreePath) {
+        let Some(iter) = self.store.iter(path) else {
+            return;
+        };
+        // A group row (un)marks all of its locations
+        let mut rows = self.children(Some(&iter));
+        if rows.is_empty() {
+            rows.push(iter);
+        }
+        let keys: Vec<String> = {
+            let findings = self.findings.borrow();
+            rows.iter()
+                .filter_map(|row| {
+                    let index = self.store.get::<u32>(row, COL_INDEX as i32) as usize;
+                    findings.get(index).map(Finding::review_key)
+                })
+                .collect()
+        };
+        if keys.is_empty() {
+            return;
+        }
+        let marked = !self.store.get::<bool>(&iter, COL_REVIEWED as i32);
+        {
+            let mut reviewed = self.reviewed.borrow_mut();
+            for key in keys {
+                if marked {
+                    reviewed.insert(key);
+                } else {
+                    reviewed.remove(&key);
+                }
+            }
+        }
+        for row in &rows {
+            self.store.set_value(row, COL_REVIEWED, &marked.to_value());
+        }
+        if self.store.iter_has_child(&iter) {
+            self.store
+                .set_value(&iter, COL_REVIEWED, &marked.to_value());
+        }
+        // The group row follows whether all of its locations are reviewed
+        if let Some(parent) = self.store.iter_parent(&iter) {
+            let all = self
+                .children(Some(&parent))
+                .iter()
+                .all(|child| self.store.get::<bool>(child, COL_REVIEWED as i32));
+            self.store.set_value(&parent, COL_REVIEWED, &all.to_value());
+        }
+        if let Some(ref handler) = *self.reviewed_handler.borrow() {
+            handler(&self.reviewed.borrow());
+        }
+    }
+
+    // A note edited on a group row goes to all of its locations; an empty
+    // one removes the note
+    fn set_note(&self, path: &TreePath, text: &str) {
+        let Some(iter) = self.store.iter(path) else {
+            return;
+        };
+        let mut rows = self.children(Some(&iter));
+        if rows.is_empty() {
+            rows.push(iter);
+        }
+        let text = text.trim();
+        {
+            let findings = self.findings.borrow();
+            let mut notes = self.notes.borrow_mut();
+            for row in &rows {
+                let index = self.store.get::<u32>(row, COL_INDEX as i32) as usize;
+                let Some(f) = findings.get(index) else {
+                    continue;
+                };
+                if text.is_empty() {
+                    notes.remove(&f.review_key());
+                } else {
+                    notes.insert(f.review_key(), text.to_string());
+                }
+            }
+        }
+        for row in &rows {
+            self.store.set_value(row, COL_NOTE, &text.to_value());
+        }
+        if self.store.iter_has_child(&iter) {
+            self.store.set_value(&iter, COL_NOTE, &text.to_value());
+        }
+        if let Some(ref handler) = *self.notes_handler.borrow() {
+            handler(&self.notes.borrow());
+        }
+    }
+
+    // Findings to export: all of them, or the displayed rows if
+    // "Export filtered view" is on; anonymized if asked
+    fn exported_findings(&self) -> Vec<Finding> {
+        let findings = self.findings.borrow();
+        let exported: Vec<Finding> = if self.export_filtered.is_active() {
+            self.finding_rows()
+                .iter()
+                .filter_map(|iter| {
+                    let index = self.store.get::<u32>(iter, COL_INDEX as i32) as usize;
+                    findings.get(index).cloned()
+                })
+                .collect()
+        } else {
+            findings.clone()
+        };
+        let notes = self.notes.borrow();
+        let exported: Vec<Finding> = exported
+            .into_iter()
+            .map(|f| Finding {
+                note: notes.get(&f.review_key()).cloned(),
+                ..f
+            })
+            .collect();
+        match self.anonymize.is_active() {
+            true => export::anonymize(&exported, self.project_root.borrow().as_deref()),
+            false => exported,
+        }
+    }
+
+    pub fn set_project_root(&self, root: &str) {
+        *self.project_root.borrow_mut() = Some(root.to_string());
+    }
+
+    // Directory the relative paths of the coming findings are relative to,
+    // e.g. from --relative-paths; None for the project directory
+    pub fn set_source_base(&self, base: Option<PathBuf>) {
+        *self.source_base.borrow_mut() = base;
+    }
+
+    // `file` of a finding as a path that can be opened from here: archive
+    // paths lead into the unpacked archive, relative ones start at the base
+    pub fn source_path(&self, file: &str) -> String {
+        if let Some(path) = self
+            .archive
+            .borrow()
+            .as_ref()
+            .and_then(|a| a.real_path(file))
+        {
+            return path;
+        }
+        if Path::new(file).is_absolute() {
+            return file.to_string();
+        }
+        let base = self
+            .source_base
+            .borrow()
+            .clone()
+            .or_else(|| self.project_root.borrow().as_ref().map(PathBuf::from));
+        match base {
+            Some(base) => base.join(file).to_string_lossy().to_string(),
+            None => file.to_string(),
+        }
+    }
+
+    pub fn choose_export_file(&self, format: Format) {
+        let parent = self.widget.root().and_downcast::<Window>();
+        let dialog = FileChooserDialog::builder()
+            .title(format!("Export {}", format.name()))
+            .action(FileChooserAction::Save)
+            .modal(true)
+            .build();
+        dialog.set_transient_for(parent.as_ref());
+        dialog.set_current_name(&format!("cppcheck-results.{}", format.extension()));
+        dialog.add_buttons(&[
+            (tr("Cancel"), ResponseType::Cancel),
+            (tr("Export"), ResponseType::Accept),
+        ]);
+        let view_clone = self.clone();
+        dialog.connect_response(move |d, r| {
+            if r == ResponseType::Accept {
+                if let Some(path) = d.file().and_then(|f| f.path()) {
+                    view_clone.export(format, &path);
+                }
+            }
+            d.close();
+        });
+        dialog.show();
+    }
+
+    fn export(&self, format: Format, path: &Path) {
+        let findings = self.exported_findings();
+        match fs::write(path, format.render(&findings)) {
+            Ok(()) => append_text(
+                &self.log,
+                &format!(
+                    "Exported {} finding(s) as {} to {}\n",
+                    findings.len(),
+                    format.name(),
+                    path.display()
+                ),
+            ),
+            Err(e) => append_text(
+                &self.log,
+                &format!("Failed to write {}: {}\n", path.display(), e),
+            ),
+        }
+    }
+
+    // Write the findings as Format::Html to a temporary file and open it
+    fn open_in_browser(&self) {
+        let dir = crate::temp_dir();
+        let path = dir.join(format!("results-{}.html", glib::monotonic_time()));
+        let findings = self.exported_findings();
+        if let Err(e) =
+            fs::create_dir_all(&dir).and_then(|()| fs::write(&path, Format::Html.render(&findings)))
+        {
+            append_text(
+                &self.log,
+                &format!("Failed to write {}: {}\n", path.display(), e),
+            );
+            return;
+        }
+        let uri = crate::file_uri(&path.to_string_lossy());
+        if let Err(e) = AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>) {
+            append_text(&self.log, &format!("Failed to open {}: {}\n", uri, e));
+        }
+    }
+
+    fn open_source(&self, finding: &Finding) {
+        if let Some(ref handler) = *self.open_source_handler.borrow() {
+            handler(finding);
+        }
+    }
+
+    fn hide_id(&self, id: &str) {
+        self.hidden_ids.borrow_mut().insert(id.to_string());
+        self.refresh();
+    }
+
+    fn unhide_id(&self, id: &str) {
+        self.hidden_ids.borrow_mut().remove(id);
+        self.refresh();
+    }
+
+    fn clear_hidden(&self) {
+        self.hidden_ids.borrow_mut().clear();
+        self.refresh();
+    }
+
+    fn show_preview(&self, finding: Option<&Finding>) {
+        let text = match finding {
+            Some(f) => fs::read_to_string(self.source_path(&f.file))
+                .ok()
+                .and_then(|contents| source_context(&contents, f.line, PREVIEW_CONTEXT))
+                .unwrap_or_else(|| "source unavailable".to_string()),
+            None => String::new(),
+        };
+        self.preview.set_text(&text);
+    }
+
+    // Save the current filters as `name`, replacing a view of that name
+    fn save_view(&self, name: &str) {
+        let saved = FilterView {
+            name: name.to_string(),
+            threshold: self.threshold.get(),
+            file_glob: self.file_glob.borrow().clone(),
+            hidden_ids: self.hidden_ids.borrow().iter().cloned().collect(),
+        };
+        {
+            let mut views = self.views.borrow_mut();
+            match views.iter_mut().find(|v| v.name == name) {
+                Some(existing) => *existing = saved,
+                None => views.push(saved),
+            }
+        }
+        self.update_views_combo(Some(name));
+        self.views_changed();
+    }
+
+    fn apply_view(&self, index: usize) {
+        let Some(view) = self.views.borrow().get(index).cloned() else {
+            return;
+        };
+        *self.hidden_ids.borrow_mut() = view.hidden_ids.iter().cloned().collect();
+        match *self.threshold_handler.borrow() {
+            Some(ref handler) => handler(view.threshold),
+            None => self.threshold.set(view.threshold),
+        }
+        if self.glob_entry.text() == view.file_glob {
+            self.refresh();
+        } else {
+            // The changed handler refreshes
+            self.glob_entry.set_text(&view.file_glob);
+        }
+    }
+
+    fn delete_view(&self) {
+        let Some(index) = self.views_combo.active() else {
+            return;
+        };
+        self.views.borrow_mut().remove(index as usize);
+        self.update_views_combo(None);
+        self.views_changed();
+    }
+
+    fn views_changed(&self) {
+        if let Some(ref handler) = *self.views_handler.borrow() {
+            handler(&self.views.borrow());
+        }
+    }
+
+    // Rebuild the views dropdown, showing `active` as selected
+    fn update_views_combo(&self, active: Option<&str>) {
+        let views = self.views.borrow().clone();
+        self.views_combo.remove_all();
+        for view in &views {
+            self.views_combo.append_text(&view.name);
+        }
+        self.views_combo.set_sensitive(!views.is_empty());
+        if let Some(i) = active.and_then(|name| views.iter().position(|v| v.name == name)) {
+            self.views_combo.set_active(Some(i as u32));
+        }
+    }
+
+    // Rebuild the "Hidden ids" popover from the current hidden set
+    fn update_hidden_menu(&self) {
+        let hidden = self.hidden_ids.borrow();
+        self.hidden_btn
+            .set_label(&format!("Hidden ids ({})", hidden.len()));
+        self.hidden_btn.set_sensitive(!hidden.is_empty());
+        let Some(popover) = self.hidden_btn.popover() else {
+            return;
+        };
+
+        let list = GtkBox::new(Orientation::Vertical, 4);
+        for id in hidden.iter() {
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            let label = Label::new(Some(id));
+            label.set_hexpand(true);
+            label.set_xalign(0.0);
+            let show_btn = Button::with_label(tr("Show"));
+            let view_clone = self.clone();
+            let id_clone = id.clone();
+            show_btn.connect_clicked(move |_| view_clone.unhide_id(&id_clone));
+            row.append(&label);
+            row.append(&show_btn);
+            list.append(&row);
+        }
+        let clear_btn = Button::with_label(tr("Show all"));
+        {
+            let view_clone = self.clone();
+            let popover_clone = popover.clone();
+            clear_btn.connect_clicked(move |_| {
+                popover_clone.popdown();
+                view_clone.clear_hidden();
+            });
+        }
+        list.append(&clear_btn);
+        popover.set_child(Some(&list));
+    }
+
+    // Select and return the finding under the given widget coordinates
+    fn finding_at(&self, x: f64, y: f64) -> Option<Finding> {
+        let (bx, by) = self
+            .tree
+            .convert_widget_to_bin_window_coords(x as i32, y as i32);
+        let (path, _, _, _) = self.tree.path_at_pos(bx, by)?;
+        let path = path?;
+        let iter = self.store.iter(&path)?;
+        self.tree.selection().select_iter(&iter);
+        let index = self.store.get::<u32>(&iter, COL_INDEX as i32) as usize;
+        self.findings.borrow().get(index).cloned()
+    }
+
+    fn show_context_menu(&self, finding: &Finding, x: f64, y: f64) {
+        let menu = GtkBox::new(Orientation::Vertical, 0);
+
+        let open_btn = Button::with_label(tr("Open source"));
+        open_btn.set_has_frame(false);
+        open_btn.set_sensitive(finding.line > 0);
+        {
+            let view_clone = self.clone();
+            let finding = finding.clone();
+            open_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                view_clone.open_source(&finding);
+            });
+        }
+        menu.append(&open_btn);
+
+        let explain_btn = Button::with_label(tr("Explain this finding"));
+        explain_btn.set_has_frame(false);
+        {
+            let view_clone = self.clone();
+            let finding = finding.clone();
+            explain_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                view_clone.explain(&finding);
+            });
+        }
+        menu.append(&explain_btn);
+
+        let folder_btn = Button::with_label(tr("Show in file manager"));
+        folder_btn.set_has_frame(false);
+        folder_btn.set_sensitive(finding.file != "nofile");
+        {
+            let view_clone = self.clone();
+            let finding = finding.clone();
+            folder_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                if let Some(ref handler) = *view_clone.show_folder_handler.borrow() {
+                    handler(&finding);
+                }
+            });
+        }
+        menu.append(&folder_btn);
+
+        let hide_btn = Button::with_label(&format!("Hide all '{}'", finding.id));
+        hide_btn.set_has_frame(false);
+        {
+            let view_clone = self.clone();
+            let id = finding.id.clone();
+            hide_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                view_clone.hide_id(&id);
+            });
+        }
+        menu.append(&hide_btn);
+
+        let suppress_btn = Button::with_label(tr("Suppress here"));
+        suppress_btn.set_has_frame(false);
+        suppress_btn.set_sensitive(finding.line > 0);
+        {
+            let view_clone = self.clone();
+            let finding = finding.clone();
+            suppress_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                view_clone.confirm_suppress(&finding);
+            });
+        }
+        menu.append(&suppress_btn);
+
+        let add_btn = Button::with_label(tr("Add to suppressions file"));
+        add_btn.set_has_frame(false);
+        {
+            let view_clone = self.clone();
+            let finding = finding.clone();
+            add_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                if let Some(ref handler) = *view_clone.add_suppression_handler.borrow() {
+                    handler(&finding);
+                }
+            });
+        }
+        menu.append(&add_btn);
+
+        let exclude_btn = Button::with_label(tr("Exclude this file from future scans"));
+        exclude_btn.set_has_frame(false);
+        exclude_btn.set_sensitive(finding.file != "nofile");
+        {
+            let view_clone = self.clone();
+            let finding = finding.clone();
+            exclude_btn.connect_clicked(move |_| {
+                view_clone.context_menu.popdown();
+                view_clone.confirm_exclude(&finding);
+            });
+        }
+        menu.append(&exclude_btn);
+
+        self.context_menu.set_child(Some(&menu));
+        self.context_menu
+            .set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+        self.context_menu.popup();
+    }
+
+    // cppcheck's longer explanation of the finding, from its XML output
+    fn explain(&self, finding: &Finding) {
+        let parent = self.tree.root().and_downcast::<Window>();
+        let dialog = MessageDialog::new(
+            parent.as_ref(),
+            DialogFlags::MODAL | DialogFlags::DESTROY_WITH_PARENT,
+            MessageType::Info,
+            ButtonsType::Close,
+            &finding.message,
+        );
+        let mut text = match finding.verbose {
+            Some(ref verbose) if *verbose != finding.message => verbose.clone(),
+            Some(_) => "cppcheck has no longer explanation for this finding.".to_string(),
+            None => "The longer explanation comes with XML results: generate the HTML \
+                     report or open the XML results of this scan to see it."
+                .to_string(),
+        };
+        text.push_str(&format!("\n\nId: {}", finding.id));
+        if let Some(cwe) = finding.cwe {
+            text.push_str(&format!(
+                "\nCWE-{}: https://cwe.mitre.org/data/definitions/{}.html",
+                cwe, cwe
+            ));
+        }
+        if finding.inconclusive {
+            text.push_str("\nInconclusive: cppcheck is not sure this is a real issue");
+        }
+        dialog.set_secondary_text(Some(&text));
+        dialog.connect_response(|d, _| d.close());
+        dialog.show();
+    }
+
+    // Ask before inserting an inline suppression comment into the source file
+    fn confirm_suppress(&self, finding: &Finding) {
+        let parent = self.tree.root().and_downcast::<Window>();
+        let dialog = MessageDialog::new(
+            parent.as_ref(),
+            DialogFlags::MODAL,
+            MessageType::Question,
+            ButtonsType::YesNo,
+            format!("Suppress '{}' in {}?", finding.id, finding.file),
+        );
+        dialog.set_secondary_text(Some(&format!(
+            "A \"// cppcheck-suppress {}\" comment will be inserted above line {}. \
+             Undo Suppress takes it out again while the GUI is open.",
+            finding.id, finding.line
+        )));
+        let view_clone = self.clone();
+        let finding = finding.clone();
+        dialog.connect_response(move |d, r| {
+            d.close();
+            if r == ResponseType::Yes {
+                view_clone.apply_suppress(&finding);
+            }
+        });
+        dialog.show();
+    }
+
+    fn apply_suppress(&self, finding: &Finding) {
+        let path = self.source_path(&finding.file);
+        match suppress::insert_inline_suppression(&path, finding.line, &finding.id) {
+            Ok(()) => {
+                append_text(
+                    &self.log,
+                    &format!(
+                        "Suppressed {} at {}:{}\n",
+                        finding.id, finding.file, finding.line
+                    ),
+                );
+                // The comment shifts every later line of that file down by one
+                for f in self.findings.borrow_mut().iter_mut() {
+                    if f.file == finding.file && f.line >= finding.line {
+                        f.line += 1;
+                    }
+                }
+                let mut inserted = self.inserted.borrow_mut();
+                for s in inserted.iter_mut() {
+                    if s.file == finding.file && s.line >= finding.line {
+                        s.line += 1;
+                    }
+                }
+                inserted.push(InsertedSuppression {
+                    file: finding.file.clone(),
+                    line: finding.line,
+                    id: finding.id.clone(),
+                });
+                drop(inserted);
+                self.update_undo();
+                self.refresh();
+            }
+            Err(e) => append_text(
+                &self.log,
+                &format!("Failed to suppress in {}: {}\n", finding.file, e),
+            ),
+        }
+    }
+
+    // Remove the newest comment inserted by apply_suppress
+    fn undo_suppress(&self) {
+        let Some(last) = self.inserted.borrow_mut().pop() else {
+            return;
+        };
+        let path = self.source_path(&last.file);
+        match suppress::remove_inline_suppression(&path, last.line, &last.id) {
+            Ok(()) => {
+                append_text(
+                    &self.log,
+                    &format!(
+                        "Removed the suppression of {} at {}:{}\n",
+                        last.id, last.file, last.line
+                    ),
+                );
+                for f in self.findings.borrow_mut().iter_mut() {
+                    if f.file == last.file && f.line > last.line {
+                        f.line -= 1;
+                    }
+                }
+                for s in self.inserted.borrow_mut().iter_mut() {
+                    if s.file == last.file && s.line > last.line {
+                        s.line -= 1;
+                    }
+                }
+                self.refresh();
+            }
+            Err(e) => append_text(
+                &self.log,
+                &format!("Failed to undo the suppression in {}: {}\n", last.file, e),
+            ),
+        }
+        self.update_undo();
+    }
+
+    fn update_undo(&self) {
+        let inserted = self.inserted.borrow();
+        self.undo_btn.set_sensitive(!inserted.is_empty());
+        let tooltip = inserted.last().map(|s| {
+            format!(
+                "Remove the \"// cppcheck-suppress {}\" comment inserted at {}:{}",
+                s.id, s.file, s.line
+            )
+        });
+        self.undo_btn.set_tooltip_text(tooltip.as_deref());
+    }
+
+    // Ask before adding the finding's file to the project's exclusions
+    fn confirm_exclude(&self, finding: &Finding) {
+        let parent = self.tree.root().and_downcast::<Window>();
+        let dialog = MessageDialog::new(
+            parent.as_ref(),
+            DialogFlags::MODAL,
+            MessageType::Question,
+            ButtonsType::YesNo,
+            format!("Exclude {} from future scans?", finding.file),
+        );
+        dialog.set_secondary_text(Some(
+            "The file is added to this project's excluded files (-i) and saved with \
+             its settings. Clear next to \"Excluded files\" brings it back.",
+        ));
+        let view_clone = self.clone();
+        let finding = finding.clone();
+        dialog.connect_response(move |d, r| {
+            d.close();
+            if r == ResponseType::Yes {
+                if let Some(ref handler) = *view_clone.exclude_file_handler.borrow() {
+                    handler(&finding);
+                }
+            }
+        });
+        dialog.show();
+    }
+}
+
+fn row_tooltip(finding: &Finding) -> String {
+    let message = finding.verbose.as_deref().unwrap_or(&finding.message);
+    let mut text = glib::markup_escape_text(message).to_string();
+    if let Some(cwe) = finding.cwe {
+        text.push_str(&format!("\n<b>CWE-{}</b>", cwe));
+    }
+    if finding.inconclusive {
+        text.push_str("\n<i>Inconclusive: cppcheck is not sure this is a real issue</i>");
+    }
+    text
+}
+
+// Inconclusive findings are lower confidence and say so
+fn severity_text(finding: &Finding) -> String {
+    if finding.inconclusive {
+        format!("{} (inconclusive)", finding.severity)
+    } else {
+        finding.severity.to_string()
+    }
+}
+
+// Colored swatches explaining the severity colors of the rows
+fn severity_legend() -> GtkBox {
+    let legend = GtkBox::new(Orientation::Horizontal, 12);
+    legend.set_hexpand(true);
+    legend.set_halign(Align::End);
+    for severity in Severity::ALL {
+        let label = Label::new(None);
+        label.set_markup(&format!(
+            "<span background=\"{}\">    </span> {}",
+            severity.color(),
+            severity
+        ));
+        legend.append(&label);
+    }
+    legend
+}
+
+// Numbered source lines around the 1-based `line`, which is marked with '>'
+fn source_context(contents: &str, line: u32, radius: usize) -> Option<String> {
+    let target = (line as usize).checked_sub(1)?;
+    let lines: Vec<&str> = contents.lines().collect();
+    if target >= lines.len() {
+        return None;
+    }
+    let first = target.saturating_sub(radius);
+    let last = (target + radius).min(lines.len() - 1);
+    let width = (last + 1).to_string().len();
+    let mut out = String::new();
+    for (i, text) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let marker = if i == target { '>' } else { ' ' };
+        out.push_str(&format!(
+            "{} {:>width$} | {}\n",
+            marker,
+            i + 1,
+            text,
+            width = width
+        ));
+    }
+    Some(out)
+}
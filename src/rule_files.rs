@@ -0,0 +1,115 @@
+use crate::i18n::tr;
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, Button, FileChooserAction, FileChooserDialog, FileFilter, Label, Orientation,
+    ResponseType, Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Custom rule XML files passed as `--rule-file`
+#[derive(Clone)]
+pub struct RuleFiles {
+    pub widget: GtkBox,
+    files: Rc<RefCell<Vec<String>>>,
+    summary: Label,
+    clear_btn: Button,
+}
+
+impl RuleFiles {
+    pub fn new() -> Self {
+        let summary = Label::new(None);
+        summary.set_hexpand(true);
+        summary.set_xalign(0.0);
+        let add_btn = Button::with_label(tr("Add…"));
+        let clear_btn = Button::with_label(tr("Clear"));
+        let widget = GtkBox::new(Orientation::Horizontal, 4);
+        widget.append(&Label::new(Some(tr("Rule files:"))));
+        widget.append(&summary);
+        widget.append(&add_btn);
+        widget.append(&clear_btn);
+
+        let rules = RuleFiles {
+            widget,
+            files: Rc::new(RefCell::new(Vec::new())),
+            summary,
+            clear_btn,
+        };
+        {
+            let rules_clone = rules.clone();
+            add_btn.connect_clicked(move |_| rules_clone.choose_files());
+        }
+        {
+            let rules_clone = rules.clone();
+            rules
+                .clear_btn
+                .connect_clicked(move |_| rules_clone.set_files(&[]));
+        }
+        rules.update_summary();
+        rules
+    }
+
+    pub fn files(&self) -> Vec<String> {
+        self.files.borrow().clone()
+    }
+
+    pub fn set_files(&self, files: &[String]) {
+        *self.files.borrow_mut() = files.to_vec();
+        self.update_summary();
+    }
+
+    fn add(&self, file: String) {
+        let mut files = self.files.borrow_mut();
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    fn update_summary(&self) {
+        let files = self.files.borrow();
+        self.summary.set_text(&match files.len() {
+            0 => "none".to_string(),
+            1 => files[0].clone(),
+            n => format!("{} files", n),
+        });
+        self.summary.set_tooltip_text(Some(&files.join("\n")));
+        self.clear_btn.set_sensitive(!files.is_empty());
+    }
+
+    fn choose_files(&self) {
+        let parent = self.widget.root().and_downcast::<Window>();
+        let dialog = FileChooserDialog::builder()
+            .title(tr("Select Rule Files"))
+            .action(FileChooserAction::Open)
+            .select_multiple(true)
+            .modal(true)
+            .build();
+        dialog.set_transient_for(parent.as_ref());
+        let filter = FileFilter::new();
+        filter.set_name(Some("Rule files (*.xml)"));
+        filter.add_pattern("*.xml");
+        dialog.add_filter(&filter);
+        dialog.add_buttons(&[
+            (tr("Cancel"), ResponseType::Cancel),
+            (tr("Add"), ResponseType::Accept),
+        ]);
+        let rules_clone = self.clone();
+        dialog.connect_response(move |d, r| {
+            if r == ResponseType::Accept {
+                let selected = d.files();
+                for i in 0..selected.n_items() {
+                    if let Some(path) = selected
+                        .item(i)
+                        .and_downcast::<gio::File>()
+                        .and_then(|f| f.path())
+                    {
+                        rules_clone.add(path.to_string_lossy().to_string());
+                    }
+                }
+                rules_clone.update_summary();
+            }
+            d.close();
+        });
+        dialog.show();
+    }
+}
@@ -0,0 +1,499 @@
+use crate::findings::{Finding, Template};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+// Extensions cppcheck analyzes as translation units by default
+const SOURCE_EXTENSIONS: &[&str] = &["c", "cc", "cpp", "cxx", "c++", "C", "tpp", "txx"];
+// Only analyzed through #include unless passed explicitly
+const HEADER_EXTENSIONS: &[&str] = &["h", "hh", "hpp", "hxx", "h++", "H"];
+// Niceness increment of low priority scans; 19 is the lowest priority
+const LOW_PRIORITY_NICENESS: &str = "10";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+// Output of a running cppcheck process, delivered line by line
+pub enum ScanEvent {
+    Line(Stream, String),
+    Finished(io::Result<ExitStatus>),
+}
+
+// What a line of cppcheck output is. cppcheck prints progress on stdout and
+// results on stderr, but versions differ in where some messages go and
+// wrappers such as `docker run -t` merge both streams, so the content
+// decides; the stream only breaks ties
+pub enum Output<'a> {
+    // With the file of a "Checking ..." line
+    Progress(Option<&'a str>),
+    Finding(Finding),
+    // cppcheck's own output for scans with DEBUG_FLAG
+    Debug,
+    Message,
+}
+
+pub fn classify<'a>(stream: Stream, line: &'a str, parser: &Template, debug: bool) -> Output<'a> {
+    let progress = is_progress(line);
+    if progress && stream == Stream::Stdout {
+        return Output::Progress(checking_file(line));
+    }
+    if let Some(finding) = parser.parse_line(line) {
+        return Output::Finding(finding);
+    }
+    if progress {
+        Output::Progress(checking_file(line))
+    } else if debug && stream == Stream::Stdout {
+        Output::Debug
+    } else {
+        Output::Message
+    }
+}
+
+// The XML report in cppcheck output that also holds other lines, for when
+// it went to stderr instead of --output-file
+pub fn xml_document(output: &str) -> Option<&str> {
+    let start = output.find("<?xml")?;
+    let end = output[start..].find("</results>")? + start + "</results>".len();
+    Some(&output[start..end])
+}
+
+// Handle to a running scan, used to cancel it from the UI
+#[derive(Clone)]
+pub struct ScanHandle {
+    child: Arc<Mutex<Child>>,
+    // Kept apart so it can be read while the waiter holds the lock
+    pid: u32,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ScanHandle {
+    // Kill the cppcheck process; its `Finished` event still arrives
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Ok(mut child) = self.child.lock() {
+            let _ = child.kill();
+        }
+    }
+
+    // Whether both handles belong to the same scan
+    pub fn same(&self, other: &ScanHandle) -> bool {
+        Arc::ptr_eq(&self.child, &other.child)
+    }
+
+    pub fn was_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    // Resident memory of the cppcheck process in bytes, where the platform
+    // tells; child processes of -j runs are not included
+    pub fn resident_memory(&self) -> Option<u64> {
+        resident_memory(self.pid)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn resident_memory(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line
+        .trim_start_matches("VmRSS:")
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn resident_memory(_pid: u32) -> Option<u64> {
+    None
+}
+
+// Start `cmd` with piped output; reader threads forward every line and a
+// final `Finished` once the process has exited
+pub fn spawn(mut cmd: Command) -> io::Result<(ScanHandle, Receiver<ScanEvent>)> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let (tx, rx) = mpsc::channel();
+    let stdout = child.stdout.take().map(|out| {
+        let tx = tx.clone();
+        thread::spawn(move || forward_lines(out, &tx, Stream::Stdout))
+    });
+    let stderr = child.stderr.take().map(|err| {
+        let tx = tx.clone();
+        thread::spawn(move || forward_lines(err, &tx, Stream::Stderr))
+    });
+    let handle = ScanHandle {
+        pid: child.id(),
+        child: Arc::new(Mutex::new(child)),
+        cancelled: Arc::new(AtomicBool::new(false)),
+    };
+    let child = handle.child.clone();
+    thread::spawn(move || {
+        // Drain both pipes before reporting the exit so no line is lost;
+        // the lock stays free meanwhile so the scan can be cancelled
+        for reader in [stdout, stderr].into_iter().flatten() {
+            let _ = reader.join();
+        }
+        let status = match child.lock() {
+            Ok(mut child) => child.wait(),
+            Err(_) => Err(io::Error::other("scan process lock poisoned")),
+        };
+        let _ = tx.send(ScanEvent::Finished(status));
+    });
+    Ok((handle, rx))
+}
+
+fn forward_lines<R: Read>(reader: R, tx: &Sender<ScanEvent>, stream: Stream) {
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if tx.send(ScanEvent::Line(stream, line)).is_err() {
+            break;
+        }
+    }
+}
+
+// Shell-like rendering of an argument vector for display and copying
+pub fn command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|a| {
+            let plain = !a.is_empty()
+                && a.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+            if plain {
+                a.clone()
+            } else {
+                format!("'{}'", a.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Arguments of a shell-like command line, the reverse of `command_line`:
+// whitespace separates, quotes group, a backslash escapes the next
+// character outside single quotes. Variables and globs are not expanded
+pub fn split_command_line(text: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if let Some(arg) = current.take() {
+                    args.push(arg);
+                }
+            }
+            '\'' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err("unmatched single quote".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let arg = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => arg.push(c),
+                            Some(c) => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                            None => return Err("unmatched double quote".to_string()),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err("unmatched double quote".to_string()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => current.get_or_insert_with(String::new).push(c),
+                None => return Err("trailing backslash".to_string()),
+            },
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(current);
+    Ok(args)
+}
+
+// cppcheck, run through `prefix` (a container or toolchain wrapper such as
+// "docker run --rm -v /src:/src image") when it is not empty, with the
+// "KEY=value" entries of `env` set. A non-zero `memory_limit_mb` caps its
+// virtual memory with the shell's `ulimit -v`, where there is a shell.
+// `low_priority` starts it, prefix and all, under `nice`
+pub fn cppcheck_command(
+    prefix: &[String],
+    env: &[String],
+    memory_limit_mb: u32,
+    low_priority: bool,
+) -> Command {
+    let nice = if low_priority && cfg!(unix) {
+        &["nice", "-n", LOW_PRIORITY_NICENESS][..]
+    } else {
+        &[]
+    };
+    let program: Vec<&str> = nice
+        .iter()
+        .copied()
+        .chain(prefix.iter().map(String::as_str))
+        .chain(std::iter::once("cppcheck"))
+        .collect();
+    let mut cmd = if memory_limit_mb > 0 && cfg!(unix) {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(format!(
+                "ulimit -v {} && exec \"$@\"",
+                u64::from(memory_limit_mb) * 1024
+            ))
+            .arg("sh")
+            .args(&program);
+        cmd
+    } else {
+        let mut cmd = Command::new(program[0]);
+        cmd.args(&program[1..]);
+        cmd
+    };
+    set_child_env(&mut cmd, env);
+    cmd
+}
+
+// Whether a non-finding output line is cppcheck's complaint that the paths
+// it was given hold nothing to check
+pub fn no_source_files(line: &str) -> bool {
+    line.to_lowercase()
+        .contains("no c or c++ source files found")
+}
+
+// Whether a non-finding output line says cppcheck ran out of memory, as it
+// does when it hits the limit of cppcheck_command
+pub fn out_of_memory(line: &str) -> bool {
+    let line = line.to_lowercase();
+    ["bad_alloc", "out of memory", "cannot allocate memory"]
+        .iter()
+        .any(|s| line.contains(s))
+}
+
+// GIO_USE_PROXY as the GUI was started with, before disable_gio_proxy
+static INHERITED_GIO_USE_PROXY: OnceLock<Option<OsString>> = OnceLock::new();
+
+// Turn off GIO proxy modules in the GUI to avoid Snap-related errors; the
+// processes of set_child_env get the original value back
+pub fn disable_gio_proxy() {
+    let _ = INHERITED_GIO_USE_PROXY.set(env::var_os("GIO_USE_PROXY"));
+    env::set_var("GIO_USE_PROXY", "none");
+}
+
+// Give `cmd` the environment the GUI was started with plus the "KEY=value"
+// entries of `env`; entries without '=' are left out
+pub fn set_child_env(cmd: &mut Command, env: &[String]) {
+    match INHERITED_GIO_USE_PROXY.get() {
+        Some(Some(value)) => {
+            cmd.env("GIO_USE_PROXY", value);
+        }
+        Some(None) => {
+            cmd.env_remove("GIO_USE_PROXY");
+        }
+        None => {}
+    }
+    for entry in env {
+        if let Some((key, value)) = entry.split_once('=') {
+            cmd.env(key, value);
+        }
+    }
+}
+
+// Problem with one "KEY=value" line of the environment setting, if any
+pub fn check_env_entry(entry: &str) -> Option<String> {
+    let Some((key, _)) = entry.split_once('=') else {
+        return Some(format!("\"{}\" is not of the form KEY=value", entry));
+    };
+    let valid = !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    (!valid).then(|| format!("\"{}\" is not a valid variable name", key))
+}
+
+// A new Command with the program, arguments, directory and environment
+// changes of `cmd`, which can't be cloned or spawned twice
+pub fn copy_command(cmd: &Command) -> Command {
+    let mut copy = Command::new(cmd.get_program());
+    copy.args(cmd.get_args());
+    if let Some(dir) = cmd.get_current_dir() {
+        copy.current_dir(dir);
+    }
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => copy.env(key, value),
+            None => copy.env_remove(key),
+        };
+    }
+    copy
+}
+
+// Directory the relative file paths in the findings of `cmd` start from:
+// the --relative-paths base, the first scanned directory for a bare
+// --relative-paths, otherwise where cppcheck runs
+pub fn source_base(cmd: &Command) -> Option<PathBuf> {
+    let dir = cmd.get_current_dir();
+    let resolve = |path: &Path| match dir {
+        Some(dir) => dir.join(path),
+        None => path.to_path_buf(),
+    };
+    let args: Vec<String> = cmd
+        .get_args()
+        .map(|a| a.to_string_lossy().to_string())
+        .collect();
+    if let Some(base) = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--relative-paths="))
+    {
+        return Some(resolve(Path::new(base)));
+    }
+    if args.iter().any(|a| a == "--relative-paths") {
+        let scanned = args
+            .iter()
+            .filter(|a| !a.starts_with('-'))
+            .map(|a| resolve(Path::new(a)))
+            .find(|path| path.is_dir());
+        if scanned.is_some() {
+            return scanned;
+        }
+    }
+    dir.map(Path::to_path_buf)
+}
+
+// Command line of `cmd`, plus the directory it runs in when one is set
+pub fn describe(cmd: &Command) -> String {
+    let args: Vec<String> = std::iter::once(cmd.get_program())
+        .chain(cmd.get_args())
+        .map(|a| a.to_string_lossy().to_string())
+        .collect();
+    // Variables set for the command, as a shell would take them
+    let mut assignments: Vec<String> = cmd
+        .get_envs()
+        .filter_map(|(key, value)| {
+            let key = key.to_string_lossy();
+            // The restored proxy setting is not the user's doing
+            if key == "GIO_USE_PROXY" {
+                return None;
+            }
+            Some(format!("{}={}", key, value?.to_string_lossy()))
+        })
+        .collect();
+    assignments.extend(args);
+    match cmd.get_current_dir() {
+        Some(dir) => format!("{} (in {})", command_line(&assignments), dir.display()),
+        None => command_line(&assignments),
+    }
+}
+
+// File named by a "Checking {file} ..." progress line; configuration
+// passes ("Checking {file}: FOO=1...") name the same file again
+pub fn checking_file(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("Checking ")?;
+    let rest = rest.strip_suffix("...").unwrap_or(rest).trim_end();
+    Some(rest.split_once(": ").map_or(rest, |(file, _)| file))
+}
+
+// cppcheck's own debug output, for diagnosing cppcheck rather than the
+// code; it comes on stdout between the progress lines
+pub const DEBUG_FLAG: &str = "--debug-normal";
+
+// "Checking ..." and "N/M files checked X% done" lines, as opposed to
+// anything else cppcheck prints on stdout
+pub fn is_progress(line: &str) -> bool {
+    checking_file(line).is_some() || line.contains(" files checked ")
+}
+
+// Number of source files under `root` that a scan will analyze, leaving
+// out the -i `excludes`
+pub fn count_source_files(root: &Path, excludes: &[String]) -> usize {
+    count_files_in(root, root, excludes)
+}
+
+fn count_files_in(dir: &Path, root: &Path, excludes: &[String]) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if is_excluded(&path, root, excludes) {
+            continue;
+        }
+        if file_type.is_dir() {
+            count += count_files_in(&path, root, excludes);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e))
+        {
+            count += 1;
+        }
+    }
+    count
+}
+
+// Header files under `root` outside the `excludes`, sorted
+pub fn header_files(root: &Path, excludes: &[String]) -> Vec<PathBuf> {
+    let mut headers = Vec::new();
+    collect_headers(root, &mut headers);
+    headers.retain(|path| !is_excluded(path, root, excludes));
+    headers.sort();
+    headers
+}
+
+// `path` is one of the `excludes` (-i paths, relative ones taken from
+// `root`) or lies in an excluded directory
+pub fn is_excluded(path: &Path, root: &Path, excludes: &[String]) -> bool {
+    excludes
+        .iter()
+        .any(|exclude| path.starts_with(root.join(exclude)))
+}
+
+fn collect_headers(dir: &Path, headers: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_headers(&path, headers);
+        } else if path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| HEADER_EXTENSIONS.contains(&e))
+        {
+            headers.push(path);
+        }
+    }
+}
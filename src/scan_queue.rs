@@ -0,0 +1,236 @@
+use crate::findings::Finding;
+use crate::i18n::tr;
+use gtk4::prelude::*;
+use gtk4::{
+    Align, ApplicationWindow, Box as GtkBox, Button, FileChooserAction, FileChooserDialog, Label,
+    ListBox, Orientation, ResponseType, ScrolledWindow, Window,
+};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+// Projects scanned one after another, e.g. a nightly-style local run across
+// several repositories. Each is scanned like a normal run with its stored
+// settings; the findings of the whole run are shown together
+#[derive(Default)]
+pub struct ScanQueue {
+    pub projects: Vec<String>,
+    run: Option<Run>,
+}
+
+struct Run {
+    pending: VecDeque<String>,
+    total: usize,
+    current: String,
+    findings: Vec<Finding>,
+    // Findings per scanned project, in scan order
+    counts: Vec<(String, usize)>,
+}
+
+// What to do once a project of the queue is done
+pub enum Step {
+    Next(String),
+    // The run is over, with the findings per project
+    Done(Vec<(String, usize)>),
+}
+
+impl ScanQueue {
+    // Begin a run over `projects`; false when there is nothing to scan
+    pub fn start(&mut self) -> bool {
+        if self.projects.is_empty() {
+            return false;
+        }
+        self.run = Some(Run {
+            pending: self.projects.iter().cloned().collect(),
+            total: self.projects.len(),
+            current: String::new(),
+            findings: Vec::new(),
+            counts: Vec::new(),
+        });
+        true
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    pub fn stop(&mut self) {
+        self.run = None;
+    }
+
+    // (1-based position of the project being scanned, projects in the run)
+    pub fn position(&self) -> Option<(usize, usize)> {
+        let run = self.run.as_ref()?;
+        Some((run.total - run.pending.len(), run.total))
+    }
+
+    // Findings of the project just scanned; returns those of the whole run
+    pub fn record(&mut self, findings: Vec<Finding>) -> Vec<Finding> {
+        let Some(run) = self.run.as_mut() else {
+            return findings;
+        };
+        run.counts.push((run.current.clone(), findings.len()));
+        run.findings.extend(findings);
+        run.findings.clone()
+    }
+
+    pub fn advance(&mut self) -> Step {
+        let Some(run) = self.run.as_mut() else {
+            return Step::Done(Vec::new());
+        };
+        match run.pending.pop_front() {
+            Some(path) => {
+                run.current = path.clone();
+                Step::Next(path)
+            }
+            None => Step::Done(self.run.take().map(|run| run.counts).unwrap_or_default()),
+        }
+    }
+}
+
+// Window editing the queue; `run` starts it
+pub fn show(parent: &ApplicationWindow, queue: &Rc<RefCell<ScanQueue>>, run: Rc<dyn Fn()>) {
+    let window = Window::builder()
+        .title(tr("Scan Queue"))
+        .transient_for(parent)
+        .default_width(600)
+        .default_height(350)
+        .build();
+
+    let list = ListBox::new();
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&list));
+
+    let add_btn = Button::with_label(tr("Add…"));
+    let remove_btn = Button::with_label(tr("Remove"));
+    let up_btn = Button::with_label(tr("Move Up"));
+    let down_btn = Button::with_label(tr("Move Down"));
+    let run_btn = Button::with_label(tr("Run Queue"));
+    run_btn.set_hexpand(true);
+    run_btn.set_halign(Align::End);
+    run_btn.set_tooltip_text(Some(
+        "Scan the projects in this order, each with its stored settings; \
+         cancelling a scan stops the queue",
+    ));
+    let buttons = GtkBox::new(Orientation::Horizontal, 4);
+    buttons.append(&add_btn);
+    buttons.append(&remove_btn);
+    buttons.append(&up_btn);
+    buttons.append(&down_btn);
+    buttons.append(&run_btn);
+
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+    vbox.append(&scrolled);
+    vbox.append(&buttons);
+    window.set_child(Some(&vbox));
+
+    // Rows follow queue.projects; `selected` is reselected afterwards
+    let refresh: Rc<dyn Fn(Option<usize>)> = {
+        let list = list.clone();
+        let queue = queue.clone();
+        let run_btn = run_btn.clone();
+        Rc::new(move |selected: Option<usize>| {
+            while let Some(row) = list.row_at_index(0) {
+                list.remove(&row);
+            }
+            let projects = queue.borrow().projects.clone();
+            for path in &projects {
+                let label = Label::new(Some(path));
+                label.set_xalign(0.0);
+                list.append(&label);
+            }
+            if let Some(i) = selected {
+                list.select_row(list.row_at_index(i as i32).as_ref());
+            }
+            run_btn.set_sensitive(!projects.is_empty());
+        })
+    };
+    refresh(None);
+
+    let selected = {
+        let list = list.clone();
+        move || list.selected_row().map(|row| row.index() as usize)
+    };
+    {
+        let window_clone = window.clone();
+        let queue = queue.clone();
+        let refresh = refresh.clone();
+        add_btn.connect_clicked(move |_| {
+            let dialog = FileChooserDialog::builder()
+                .title(tr("Add Projects"))
+                .action(FileChooserAction::SelectFolder)
+                .select_multiple(true)
+                .transient_for(&window_clone)
+                .modal(true)
+                .build();
+            dialog.add_buttons(&[
+                (tr("Cancel"), ResponseType::Cancel),
+                (tr("Add"), ResponseType::Accept),
+            ]);
+            let queue = queue.clone();
+            let refresh = refresh.clone();
+            dialog.connect_response(move |d, r| {
+                if r == ResponseType::Accept {
+                    let selected = d.files();
+                    let mut queue = queue.borrow_mut();
+                    for i in 0..selected.n_items() {
+                        if let Some(path) = selected
+                            .item(i)
+                            .and_downcast::<gio::File>()
+                            .and_then(|f| f.path())
+                        {
+                            let path = path.to_string_lossy().to_string();
+                            if !queue.projects.contains(&path) {
+                                queue.projects.push(path);
+                            }
+                        }
+                    }
+                    drop(queue);
+                    refresh(None);
+                }
+                d.close();
+            });
+            dialog.show();
+        });
+    }
+    {
+        let queue = queue.clone();
+        let refresh = refresh.clone();
+        let selected = selected.clone();
+        remove_btn.connect_clicked(move |_| {
+            let Some(i) = selected() else {
+                return;
+            };
+            let len = {
+                let mut queue = queue.borrow_mut();
+                queue.projects.remove(i);
+                queue.projects.len()
+            };
+            refresh((len > 0).then(|| i.min(len - 1)));
+        });
+    }
+    for (button, up) in [(&up_btn, true), (&down_btn, false)] {
+        let queue = queue.clone();
+        let refresh = refresh.clone();
+        let selected = selected.clone();
+        button.connect_clicked(move |_| {
+            let Some(i) = selected() else {
+                return;
+            };
+            let len = queue.borrow().projects.len();
+            let j = if up { i.checked_sub(1) } else { Some(i + 1) };
+            let Some(j) = j.filter(|&j| j < len) else {
+                return;
+            };
+            queue.borrow_mut().projects.swap(i, j);
+            refresh(Some(j));
+        });
+    }
+    run_btn.connect_clicked(move |_| run());
+    window.present();
+}
@@ -0,0 +1,423 @@
+use crate::append_text;
+use crate::config::{
+    self, Config, LogLevel, DEFAULT_MAX_CTU_DEPTH, DEFAULT_MAX_LOG_LINES, DEFAULT_PDF_ATTEMPTS,
+};
+use crate::export::Format;
+use crate::findings::SeverityMap;
+use crate::i18n::tr;
+use crate::scan;
+use crate::tools;
+use gtk4::prelude::*;
+use gtk4::{
+    Align, ApplicationWindow, Box as GtkBox, Button, CheckButton, ComboBoxText, Entry,
+    FileChooserAction, FileChooserDialog, Label, Orientation, ResponseType, ScrolledWindow,
+    SpinButton, TextBuffer, TextView, Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Modal dialog for application-wide settings; changes are saved on close
+pub fn show(parent: &ApplicationWindow, config: &Rc<RefCell<Config>>, log: &TextBuffer) {
+    let window = Window::builder()
+        .title(tr("Settings"))
+        .transient_for(parent)
+        .modal(true)
+        .default_width(400)
+        .build();
+
+    let chk_auto_run =
+        CheckButton::with_label(tr("Run the scan right away when opening a recent project"));
+    chk_auto_run.set_active(config.borrow().auto_run_recent);
+    let chk_rescan = CheckButton::with_label(tr("Rescan when the scan settings change"));
+    chk_rescan.set_tooltip_text(Some(
+        "Re-run the scan shortly after a check, the severity threshold or the \
+         output format changes; a running scan starts over",
+    ));
+    chk_rescan.set_active(config.borrow().rescan_on_change);
+    let chk_keep_xml =
+        CheckButton::with_label(tr("Keep intermediate XML (cppcheck.xml) in the project"));
+    chk_keep_xml.set_active(config.borrow().keep_xml);
+    let chk_external_viewer = CheckButton::with_label(
+        "Open sources in the default application instead of the built-in viewer",
+    );
+    chk_external_viewer.set_active(config.borrow().external_viewer);
+    let chk_progress_percent =
+        CheckButton::with_label(tr("Show the percentage done on the progress bar"));
+    chk_progress_percent.set_active(config.borrow().progress_percent);
+    let chk_live_counts =
+        CheckButton::with_label(tr("Count findings per severity while the scan runs"));
+    chk_live_counts.set_active(config.borrow().live_counts);
+    let chk_open_reports = CheckButton::with_label(tr("Open reports automatically"));
+    chk_open_reports.set_tooltip_text(Some(
+        "Launch HTML and PDF reports in the default application once generated; \
+         otherwise use Open Report",
+    ));
+    chk_open_reports.set_active(config.borrow().open_reports);
+    let chk_sort_severity =
+        CheckButton::with_label(tr("List results most severe first after each scan"));
+    chk_sort_severity.set_tooltip_text(Some(
+        "Until a column header is clicked; that sort is kept for the session",
+    ));
+    chk_sort_severity.set_active(config.borrow().sort_by_severity);
+
+    let ctu_row = GtkBox::new(Orientation::Horizontal, 8);
+    ctu_row.append(&Label::new(Some(tr(
+        "Maximum CTU depth (--max-ctu-depth):",
+    ))));
+    let spin_ctu = SpinButton::with_range(0.0, 20.0, 1.0);
+    spin_ctu.set_value(config.borrow().max_ctu_depth as f64);
+    spin_ctu.set_tooltip_text(Some(&format!(
+        "How deep whole-program analysis follows calls across files. Deeper finds \
+         more inter-file issues but takes longer; cppcheck's default is {}.",
+        DEFAULT_MAX_CTU_DEPTH
+    )));
+    ctu_row.append(&spin_ctu);
+
+    let log_row = GtkBox::new(Orientation::Horizontal, 8);
+    log_row.append(&Label::new(Some(tr("Log messages:"))));
+    let log_combo = ComboBoxText::new();
+    for level in LogLevel::ALL {
+        log_combo.append(Some(level.as_str()), level.as_str());
+    }
+    log_combo.set_active_id(Some(config.borrow().log_level.as_str()));
+    log_combo.set_tooltip_text(Some(
+        "quiet: errors only; normal: also what the GUI is doing; \
+         debug: also every command run and its working directory",
+    ));
+    log_row.append(&log_combo);
+
+    let log_lines_row = GtkBox::new(Orientation::Horizontal, 8);
+    log_lines_row.append(&Label::new(Some(tr("Maximum lines in the log view:"))));
+    let spin_log_lines = SpinButton::with_range(1000.0, 10_000_000.0, 1000.0);
+    spin_log_lines.set_value(config.borrow().max_log_lines as f64);
+    spin_log_lines.set_tooltip_text(Some(&format!(
+        "The oldest lines are dropped from the view beyond this, which keeps huge \
+         logs responsive; Save Log still writes the whole log. Default {}.",
+        DEFAULT_MAX_LOG_LINES
+    )));
+    log_lines_row.append(&spin_log_lines);
+
+    // Audit trail of every completed scan without exporting by hand
+    let auto_save_row = GtkBox::new(Orientation::Horizontal, 8);
+    let chk_auto_save = CheckButton::with_label(tr("Save results after each scan to"));
+    chk_auto_save.set_active(config.borrow().auto_save);
+    let auto_save_entry = Entry::new();
+    auto_save_entry.set_hexpand(true);
+    auto_save_entry.set_placeholder_text(Some("Directory"));
+    auto_save_entry.set_text(&config.borrow().auto_save_dir);
+    let auto_save_choose = Button::with_label(tr("Choose…"));
+    {
+        let window_clone = window.clone();
+        let entry_clone = auto_save_entry.clone();
+        auto_save_choose.connect_clicked(move |_| {
+            let dialog = FileChooserDialog::builder()
+                .title(tr("Auto-save Directory"))
+                .action(FileChooserAction::SelectFolder)
+                .transient_for(&window_clone)
+                .modal(true)
+                .build();
+            dialog.add_buttons(&[
+                (tr("Cancel"), ResponseType::Cancel),
+                (tr("Select"), ResponseType::Accept),
+            ]);
+            let entry_inner = entry_clone.clone();
+            dialog.connect_response(move |d, r| {
+                if r == ResponseType::Accept {
+                    if let Some(path) = d.file().and_then(|f| f.path()) {
+                        entry_inner.set_text(&path.to_string_lossy());
+                    }
+                }
+                d.close();
+            });
+            dialog.show();
+        });
+    }
+    let auto_save_combo = ComboBoxText::new();
+    for format in Format::ALL {
+        auto_save_combo.append(Some(format.name()), format.name());
+    }
+    if !auto_save_combo.set_active_id(Some(&config.borrow().auto_save_format)) {
+        auto_save_combo.set_active(Some(0));
+    }
+    auto_save_row.append(&chk_auto_save);
+    auto_save_row.append(&auto_save_entry);
+    auto_save_row.append(&auto_save_choose);
+    auto_save_row.append(&auto_save_combo);
+
+    let memory_row = GtkBox::new(Orientation::Horizontal, 8);
+    memory_row.append(&Label::new(Some(tr(
+        "cppcheck memory limit (MB, 0 for none):",
+    ))));
+    let spin_memory = SpinButton::with_range(0.0, 1_048_576.0, 256.0);
+    spin_memory.set_value(config.borrow().memory_limit_mb as f64);
+    spin_memory.set_tooltip_text(Some(
+        "Caps the virtual memory of cppcheck with ulimit -v so a runaway scan \
+         can't exhaust the machine; a scan that hits it stops with a message. \
+         With a command prefix the limit applies to the wrapper process.",
+    ));
+    memory_row.append(&spin_memory);
+    let chk_low_priority = CheckButton::with_label(tr("Run cppcheck at low priority (nice)"));
+    chk_low_priority.set_tooltip_text(Some(
+        "Lowers the CPU priority of scans and report runs so the desktop stays \
+         responsive during long scans; they may take longer on a busy machine",
+    ));
+    chk_low_priority.set_active(config.borrow().low_priority);
+    chk_low_priority.set_sensitive(cfg!(unix));
+
+    let recent_row = GtkBox::new(Orientation::Horizontal, 8);
+    recent_row.append(&Label::new(Some(
+        "Highlight findings on lines changed in the last days (0 for off):",
+    )));
+    let spin_recent = SpinButton::with_range(0.0, 3650.0, 1.0);
+    spin_recent.set_value(config.borrow().recent_days as f64);
+    spin_recent.set_tooltip_text(Some(
+        "After a scan, git blame finds the findings on lines committed within \
+         this many days, or not committed yet, and shows them in bold. \
+         Projects outside a git repository are left as they are.",
+    ));
+    recent_row.append(&spin_recent);
+
+    let pdf_row = GtkBox::new(Orientation::Horizontal, 8);
+    pdf_row.append(&Label::new(Some(tr("PDF export attempts:"))));
+    let spin_pdf = SpinButton::with_range(1.0, 10.0, 1.0);
+    spin_pdf.set_value(config.borrow().pdf_attempts as f64);
+    spin_pdf.set_tooltip_text(Some(&format!(
+        "Headless browsers occasionally exit without writing the PDF; it is tried \
+         this many times before giving up. Default {}.",
+        DEFAULT_PDF_ATTEMPTS
+    )));
+    pdf_row.append(&spin_pdf);
+
+    let system_row = GtkBox::new(Orientation::Horizontal, 8);
+    let chk_system = CheckButton::with_label(tr("Ignore findings in system headers under"));
+    chk_system.set_active(config.borrow().ignore_system_headers);
+    chk_system.set_tooltip_text(Some(
+        "Findings inside system and toolchain headers are rarely actionable; \
+         they are suppressed with --suppress=*:<dir>/*",
+    ));
+    let system_entry = Entry::new();
+    system_entry.set_hexpand(true);
+    system_entry.set_placeholder_text(Some("Directories, separated by ':'"));
+    system_entry.set_text(&config.borrow().system_dirs.join(":"));
+    system_row.append(&chk_system);
+    system_row.append(&system_entry);
+
+    // One variable per line; applies to the wrapper when a prefix is set
+    let env_label = Label::new(Some(tr(
+        "Environment for cppcheck (KEY=value, one per line):",
+    )));
+    env_label.set_xalign(0.0);
+    let env_view = TextView::new();
+    env_view.set_monospace(true);
+    env_view
+        .buffer()
+        .set_text(&config.borrow().cppcheck_env.join("\n"));
+    env_view.set_tooltip_text(Some(
+        "Set for the cppcheck process, e.g. PATH or CPPCHECK_DATADIR. With a command \
+         prefix they are set for the wrapper, which may not pass them on.",
+    ));
+    let env_scrolled = ScrolledWindow::new();
+    env_scrolled.set_min_content_height(60);
+    env_scrolled.set_child(Some(&env_view));
+
+    let python_row = GtkBox::new(Orientation::Horizontal, 8);
+    python_row.append(&Label::new(Some(tr("Python for addons (--addon-python):"))));
+    let python_combo = ComboBoxText::with_entry();
+    for python in tools::python_interpreters() {
+        python_combo.append_text(python);
+    }
+    let python_entry = python_combo.child().and_downcast::<Entry>();
+    if let Some(ref entry) = python_entry {
+        entry.set_text(&config.borrow().addon_python);
+        entry.set_placeholder_text(Some("cppcheck's default"));
+    }
+    python_combo.set_hexpand(true);
+    python_combo.set_tooltip_text(Some(
+        "Interpreter for addons such as misra and cert, for systems where python \
+         is missing or the wrong version. The list shows those found on PATH; a \
+         full path can be typed.",
+    ));
+    python_row.append(&python_combo);
+
+    let chk_debug = CheckButton::with_label(tr("Show cppcheck's internal debug output (--debug)"));
+    chk_debug.set_active(config.borrow().cppcheck_debug);
+    chk_debug.set_tooltip_text(Some(
+        "For troubleshooting cppcheck itself: the output is very long and goes \
+         to a separate panel below the log",
+    ));
+
+    let prefix_row = GtkBox::new(Orientation::Horizontal, 8);
+    prefix_row.append(&Label::new(Some(tr("Command prefix:"))));
+    let prefix_entry = Entry::new();
+    prefix_entry.set_hexpand(true);
+    prefix_entry.set_placeholder_text(Some("e.g. docker run --rm -v /src:/src image"));
+    prefix_entry.set_text(&scan::command_line(&config.borrow().command_prefix));
+    prefix_entry.set_tooltip_text(Some(
+        "Runs cppcheck through a container or toolchain wrapper: the prefix is \
+         split like a shell command line and \"cppcheck\" and its arguments are \
+         appended. Leave empty to run cppcheck directly.",
+    ));
+    prefix_row.append(&prefix_entry);
+
+    let editor_row = GtkBox::new(Orientation::Horizontal, 8);
+    editor_row.append(&Label::new(Some(tr("Editor command:"))));
+    let editor_entry = Entry::new();
+    editor_entry.set_hexpand(true);
+    editor_entry.set_placeholder_text(Some("e.g. code -g {file}:{line}:{column}"));
+    editor_entry.set_text(&scan::command_line(&config.borrow().editor_command));
+    editor_entry.set_tooltip_text(Some(
+        "Opens the source of a finding in this editor instead of a viewer. \
+         {file}, {line} and {column} are replaced by the finding's location",
+    ));
+    editor_row.append(&editor_entry);
+    let chk_editor_columns = CheckButton::with_label(tr("Pass the column"));
+    chk_editor_columns.set_active(config.borrow().editor_columns);
+    chk_editor_columns.set_tooltip_text(Some(
+        "Jump to the offending token rather than the line start. Without it \
+         \":{column}\" is left out of the command",
+    ));
+    editor_row.append(&chk_editor_columns);
+
+    let enables_row = GtkBox::new(Orientation::Horizontal, 8);
+    enables_row.append(&Label::new(Some(tr("Checks enabled at startup:"))));
+    let enables_entry = Entry::new();
+    enables_entry.set_hexpand(true);
+    enables_entry.set_text(&config.borrow().default_enables.join(", "));
+    enables_entry.set_tooltip_text(Some(&format!(
+        "Comma-separated --enable groups ticked when the GUI starts, e.g. for a \
+         team that always checks style; projects with stored settings keep \
+         theirs. Groups: {}",
+        config::ENABLE_GROUPS.join(", ")
+    )));
+    enables_row.append(&enables_entry);
+
+    let remap_row = GtkBox::new(Orientation::Horizontal, 8);
+    remap_row.append(&Label::new(Some(tr("Severity remap:"))));
+    let remap_entry = Entry::new();
+    remap_entry.set_hexpand(true);
+    remap_entry.set_placeholder_text(Some("e.g. variableScope=error, style=warning"));
+    remap_entry.set_text(&config.borrow().severity_map.to_string());
+    remap_entry.set_tooltip_text(Some(
+        "Show findings with your team's severities instead of cppcheck's: each rule \
+         maps a check id or a severity to another severity, and an id rule wins. \
+         Applies to the results, counts, colors and threshold from the next scan \
+         or opened report on.",
+    ));
+    remap_row.append(&remap_entry);
+
+    let close_btn = Button::with_label(tr("Close"));
+    close_btn.set_halign(Align::End);
+    {
+        let win_clone = window.clone();
+        close_btn.connect_clicked(move |_| win_clone.close());
+    }
+
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+    vbox.append(&chk_auto_run);
+    vbox.append(&chk_rescan);
+    vbox.append(&chk_keep_xml);
+    vbox.append(&chk_external_viewer);
+    vbox.append(&editor_row);
+    vbox.append(&chk_progress_percent);
+    vbox.append(&chk_live_counts);
+    vbox.append(&chk_open_reports);
+    vbox.append(&chk_sort_severity);
+    vbox.append(&ctu_row);
+    vbox.append(&log_row);
+    vbox.append(&log_lines_row);
+    vbox.append(&auto_save_row);
+    vbox.append(&system_row);
+    vbox.append(&memory_row);
+    vbox.append(&chk_low_priority);
+    vbox.append(&recent_row);
+    vbox.append(&pdf_row);
+    vbox.append(&enables_row);
+    vbox.append(&remap_row);
+    vbox.append(&prefix_row);
+    vbox.append(&python_row);
+    vbox.append(&env_label);
+    vbox.append(&env_scrolled);
+    vbox.append(&chk_debug);
+    vbox.append(&close_btn);
+    window.set_child(Some(&vbox));
+
+    let config = config.clone();
+    let log = log.clone();
+    window.connect_close_request(move |_| {
+        let mut cfg = config.borrow_mut();
+        cfg.auto_run_recent = chk_auto_run.is_active();
+        cfg.rescan_on_change = chk_rescan.is_active();
+        cfg.keep_xml = chk_keep_xml.is_active();
+        cfg.external_viewer = chk_external_viewer.is_active();
+        cfg.editor_columns = chk_editor_columns.is_active();
+        match scan::split_command_line(&editor_entry.text()) {
+            Ok(editor) => cfg.editor_command = editor,
+            Err(e) => append_text(&log, &format!("Editor command not changed: {}\n", e)),
+        }
+        cfg.progress_percent = chk_progress_percent.is_active();
+        cfg.live_counts = chk_live_counts.is_active();
+        cfg.open_reports = chk_open_reports.is_active();
+        cfg.sort_by_severity = chk_sort_severity.is_active();
+        if let Some(ref entry) = python_entry {
+            cfg.addon_python = entry.text().trim().to_string();
+        }
+        cfg.max_ctu_depth = spin_ctu.value_as_int() as u32;
+        cfg.max_log_lines = spin_log_lines.value_as_int() as u32;
+        crate::set_max_log_lines(cfg.max_log_lines);
+        cfg.pdf_attempts = spin_pdf.value_as_int() as u32;
+        cfg.memory_limit_mb = spin_memory.value_as_int() as u32;
+        cfg.low_priority = chk_low_priority.is_active();
+        cfg.recent_days = spin_recent.value_as_int() as u32;
+        cfg.cppcheck_debug = chk_debug.is_active();
+        match config::parse_enables(&enables_entry.text()) {
+            Ok(enables) => cfg.default_enables = enables,
+            Err(e) => append_text(&log, &format!("Startup checks not changed: {}\n", e)),
+        }
+        match SeverityMap::parse(&remap_entry.text()) {
+            Ok(map) => cfg.severity_map = map,
+            Err(e) => append_text(&log, &format!("Severity remap not changed: {}\n", e)),
+        }
+        let buffer = env_view.buffer();
+        let env_text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        let env: Vec<String> = env_text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+        match env.iter().find_map(|e| scan::check_env_entry(e)) {
+            None => cfg.cppcheck_env = env,
+            Some(e) => append_text(&log, &format!("Environment not changed: {}\n", e)),
+        }
+        match scan::split_command_line(&prefix_entry.text()) {
+            Ok(prefix) => cfg.command_prefix = prefix,
+            Err(e) => append_text(&log, &format!("Command prefix not changed: {}\n", e)),
+        }
+        cfg.ignore_system_headers = chk_system.is_active();
+        cfg.system_dirs = system_entry
+            .text()
+            .split(':')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(String::from)
+            .collect();
+        cfg.auto_save = chk_auto_save.is_active();
+        cfg.auto_save_dir = auto_save_entry.text().trim().to_string();
+        if let Some(format) = auto_save_combo.active_id() {
+            cfg.auto_save_format = format.to_string();
+        }
+        if let Some(level) = log_combo.active_id().and_then(|id| LogLevel::parse(&id)) {
+            cfg.log_level = level;
+            crate::set_log_level(level);
+        }
+        if let Err(e) = cfg.save() {
+            append_text(&log, &format!("Failed to save settings: {}\n", e));
+        }
+        glib::Propagation::Proceed
+    });
+    window.present();
+}
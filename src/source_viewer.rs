@@ -0,0 +1,306 @@
+use crate::append_text;
+use gtk4::prelude::*;
+use gtk4::{ScrolledWindow, TextBuffer, TextTag, TextView, Window, WrapMode};
+#[cfg(feature = "sourceview")]
+use sourceview5::prelude::*;
+use std::fs;
+use std::process::Command;
+
+// Token classes colored by the built-in highlighter
+#[cfg(not(feature = "sourceview"))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Comment,
+    String,
+    Preprocessor,
+    Keyword,
+    Number,
+}
+
+#[cfg(not(feature = "sourceview"))]
+impl Token {
+    const ALL: [Token; 5] = [
+        Token::Comment,
+        Token::String,
+        Token::Preprocessor,
+        Token::Keyword,
+        Token::Number,
+    ];
+
+    fn tag_name(&self) -> &'static str {
+        match self {
+            Token::Comment => "comment",
+            Token::String => "string",
+            Token::Preprocessor => "preprocessor",
+            Token::Keyword => "keyword",
+            Token::Number => "number",
+        }
+    }
+
+    fn color(&self) -> &'static str {
+        match self {
+            Token::Comment => "#77767b",
+            Token::String => "#26a269",
+            Token::Preprocessor => "#813d9c",
+            Token::Keyword => "#1c71d8",
+            Token::Number => "#e66100",
+        }
+    }
+}
+
+#[cfg(not(feature = "sourceview"))]
+const KEYWORDS: &[&str] = &[
+    "alignas",
+    "alignof",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "constexpr",
+    "const_cast",
+    "continue",
+    "decltype",
+    "default",
+    "delete",
+    "do",
+    "double",
+    "dynamic_cast",
+    "else",
+    "enum",
+    "explicit",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "friend",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "mutable",
+    "namespace",
+    "new",
+    "noexcept",
+    "nullptr",
+    "operator",
+    "override",
+    "private",
+    "protected",
+    "public",
+    "register",
+    "reinterpret_cast",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "static_assert",
+    "static_cast",
+    "struct",
+    "switch",
+    "template",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typedef",
+    "typeid",
+    "typename",
+    "union",
+    "unsigned",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+// `editor` with its placeholders filled in for the 1-based `line` and
+// `column` of `file`. With no column, ":{column}" is dropped and a lone
+// {column} becomes 1; None for an empty command
+pub fn editor_command(
+    editor: &[String],
+    file: &str,
+    line: u32,
+    column: Option<u32>,
+) -> Option<Command> {
+    let args: Vec<String> = editor
+        .iter()
+        .map(|arg| {
+            let arg = match column {
+                Some(column) => arg.replace("{column}", &column.to_string()),
+                None => arg.replace(":{column}", "").replace("{column}", "1"),
+            };
+            arg.replace("{line}", &line.to_string())
+                .replace("{file}", file)
+        })
+        .collect();
+    let (program, rest) = args.split_first()?;
+    let mut cmd = Command::new(program);
+    cmd.args(rest);
+    Some(cmd)
+}
+
+// Read-only view of `path` with `line` marked and the cursor at the 1-based
+// `column` of it, when known (not 0)
+pub fn show(parent: Option<&Window>, path: &str, line: u32, column: u32, log: &TextBuffer) {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            append_text(log, &format!("Failed to open {}: {}\n", path, e));
+            return;
+        }
+    };
+    let window = Window::builder()
+        .title(format!("{}:{}", path, line))
+        .default_width(800)
+        .default_height(600)
+        .build();
+    window.set_transient_for(parent);
+
+    let view = highlighted_view(path, &contents);
+    view.set_editable(false);
+    view.set_monospace(true);
+    view.set_wrap_mode(WrapMode::None);
+    let buffer = view.buffer();
+
+    let target = line.saturating_sub(1) as i32;
+    if let Some(start) = buffer.iter_at_line(target) {
+        let mut end = start;
+        end.forward_line();
+        let marked = TextTag::builder()
+            .paragraph_background("#f6f5b4")
+            .weight(700)
+            .build();
+        buffer.tag_table().add(&marked);
+        buffer.apply_tag(&marked, &start, &end);
+        let cursor = buffer
+            .iter_at_line_offset(target, column.saturating_sub(1) as i32)
+            .filter(|_| column > 0)
+            .unwrap_or(start);
+        buffer.place_cursor(&cursor);
+        let mark = buffer.create_mark(None, &start, true);
+        // Scrolling only works once the view has been laid out
+        let view_clone = view.clone();
+        glib::idle_add_local_once(move || {
+            view_clone.scroll_to_mark(&mark, 0.0, true, 0.0, 0.3);
+        });
+    }
+
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_child(Some(&view));
+    window.set_child(Some(&scrolled));
+    window.present();
+}
+
+// GtkSourceView with the language guessed from the file name, C++ when
+// it has no idea
+#[cfg(feature = "sourceview")]
+fn highlighted_view(path: &str, contents: &str) -> TextView {
+    let languages = sourceview5::LanguageManager::default();
+    let language = languages
+        .guess_language(Some(path), None)
+        .or_else(|| languages.language("cpp"));
+    let buffer = sourceview5::Buffer::new(None);
+    buffer.set_language(language.as_ref());
+    buffer.set_highlight_syntax(true);
+    buffer.set_text(contents);
+    let view = sourceview5::View::with_buffer(&buffer);
+    view.set_show_line_numbers(true);
+    view.upcast()
+}
+
+// Plain TextView colored by the built-in highlighter
+#[cfg(not(feature = "sourceview"))]
+fn highlighted_view(_path: &str, contents: &str) -> TextView {
+    let view = TextView::new();
+    let buffer = view.buffer();
+    buffer.set_text(contents);
+    for token in Token::ALL {
+        buffer.create_tag(Some(token.tag_name()), &[("foreground", &token.color())]);
+    }
+    for (start, end, token) in tokens(contents) {
+        buffer.apply_tag_by_name(
+            token.tag_name(),
+            &buffer.iter_at_offset(start as i32),
+            &buffer.iter_at_offset(end as i32),
+        );
+    }
+    view
+}
+
+// (start, end, token) char offsets of highlighted spans in C/C++ source
+#[cfg(not(feature = "sourceview"))]
+fn tokens(text: &str) -> Vec<(usize, usize, Token)> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    let mut line_start = true;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let start = i;
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            spans.push((start, i, Token::Comment));
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i = (i + 2).min(chars.len());
+            spans.push((start, i, Token::Comment));
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            spans.push((start, i, Token::String));
+        } else if c == '#' && line_start {
+            // Directive up to a comment or the end of the logical line
+            while i < chars.len() && chars[i] != '\n' {
+                if chars[i] == '/' && matches!(chars.get(i + 1), Some('/') | Some('*')) {
+                    break;
+                }
+                if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            spans.push((start, i, Token::Preprocessor));
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            spans.push((start, i, Token::Number));
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            if KEYWORDS.contains(&word.as_str()) {
+                spans.push((start, i, Token::Keyword));
+            }
+        } else {
+            i += 1;
+        }
+        // Only whitespace may precede a directive on its line
+        line_start = match chars.get(i.saturating_sub(1)) {
+            Some('\n') => true,
+            Some(c) if c.is_whitespace() => line_start,
+            _ => false,
+        };
+    }
+    spans
+}
@@ -0,0 +1,151 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+// --suppress arguments hiding every finding in files under `dirs`
+pub fn directory_suppressions(dirs: &[String]) -> Vec<String> {
+    dirs.iter()
+        .map(|d| format!("--suppress=*:{}/*", d.trim_end_matches('/')))
+        .collect()
+}
+
+// Insert `// cppcheck-suppress {id}` above the 1-based `line`, reusing that
+// line's indentation and the file's line ending style
+pub fn splice_inline_suppression(contents: &str, line: u32, id: &str) -> Option<String> {
+    let index = (line as usize).checked_sub(1)?;
+    let mut lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    let target = lines.get(index)?;
+    let indent: String = target
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .collect();
+    let newline = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let comment = format!("{}// cppcheck-suppress {}{}", indent, id, newline);
+    lines.insert(index, &comment);
+    Some(lines.concat())
+}
+
+pub fn insert_inline_suppression(path: &str, line: u32, id: &str) -> io::Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let updated = splice_inline_suppression(&contents, line, id).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("line {} is outside of {}", line, path),
+        )
+    })?;
+    fs::write(path, updated)
+}
+
+// Take out the comment splice_inline_suppression put at the 1-based `line`;
+// None unless that line still holds exactly that comment
+pub fn unsplice_inline_suppression(contents: &str, line: u32, id: &str) -> Option<String> {
+    let index = (line as usize).checked_sub(1)?;
+    let mut lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    if lines.get(index)?.trim() != format!("// cppcheck-suppress {}", id) {
+        return None;
+    }
+    lines.remove(index);
+    Some(lines.concat())
+}
+
+pub fn remove_inline_suppression(path: &str, line: u32, id: &str) -> io::Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let updated = unsplice_inline_suppression(&contents, line, id).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "the suppression comment is no longer at line {}, remove it by hand",
+                line
+            ),
+        )
+    })?;
+    fs::write(path, updated)
+}
+
+// One entry of a cppcheck suppressions list: `id[:file[:line]]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suppression {
+    pub id: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+impl Suppression {
+    pub fn parse(s: &str) -> Option<Suppression> {
+        let s = s.trim();
+        if s.is_empty() {
+            return None;
+        }
+        let (id, rest) = match s.split_once(':') {
+            Some((id, rest)) => (id, Some(rest)),
+            None => (s, None),
+        };
+        let (file, line) = match rest {
+            // A trailing numeric component is the line; the file may contain ':'
+            Some(rest) => match rest.rsplit_once(':') {
+                Some((file, line)) if line.parse::<u32>().is_ok() => {
+                    (Some(file.to_string()), line.parse().ok())
+                }
+                _ => (Some(rest.to_string()), None),
+            },
+            None => (None, None),
+        };
+        Some(Suppression {
+            id: id.to_string(),
+            file,
+            line,
+        })
+    }
+}
+
+impl fmt::Display for Suppression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.id)?;
+        if let Some(ref file) = self.file {
+            write!(f, ":{}", file)?;
+            if let Some(line) = self.line {
+                write!(f, ":{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// A line of a suppressions file; comments and blank lines are kept verbatim
+#[derive(Clone, Debug)]
+pub enum ListLine {
+    Entry(Suppression),
+    Other(String),
+}
+
+pub fn parse_suppressions_list(contents: &str) -> Vec<ListLine> {
+    contents
+        .lines()
+        .map(|l| {
+            let trimmed = l.trim();
+            if trimmed.starts_with('#') || trimmed.starts_with("//") {
+                ListLine::Other(l.to_string())
+            } else {
+                Suppression::parse(trimmed)
+                    .map(ListLine::Entry)
+                    .unwrap_or_else(|| ListLine::Other(l.to_string()))
+            }
+        })
+        .collect()
+}
+
+pub fn format_suppressions_list(lines: &[ListLine]) -> String {
+    let mut out = String::new();
+    for line in lines {
+        match line {
+            ListLine::Entry(s) => out.push_str(&s.to_string()),
+            ListLine::Other(s) => out.push_str(s),
+        }
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,215 @@
+use crate::append_text;
+use crate::findings::Finding;
+use crate::i18n::tr;
+use crate::suppress::{self, ListLine, Suppression};
+use gtk4::prelude::*;
+use gtk4::{
+    Box as GtkBox, Button, Entry, Expander, FileChooserAction, FileChooserDialog, Label,
+    Orientation, ResponseType, ScrolledWindow, TextBuffer, Window,
+};
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+// Editor for the configured suppressions file (`--suppressions-list`)
+#[derive(Clone)]
+pub struct SuppressionsPanel {
+    pub widget: Expander,
+    path: Rc<RefCell<Option<String>>>,
+    lines: Rc<RefCell<Vec<ListLine>>>,
+    path_label: Label,
+    list: GtkBox,
+    log: TextBuffer,
+}
+
+impl SuppressionsPanel {
+    pub fn new(log: &TextBuffer) -> Self {
+        let path_label = Label::new(Some(tr("No suppressions file")));
+        path_label.set_hexpand(true);
+        path_label.set_xalign(0.0);
+        let choose_btn = Button::with_label(tr("Choose File"));
+        let header = GtkBox::new(Orientation::Horizontal, 4);
+        header.append(&path_label);
+        header.append(&choose_btn);
+
+        let list = GtkBox::new(Orientation::Vertical, 2);
+        let scrolled = ScrolledWindow::new();
+        scrolled.set_min_content_height(120);
+        scrolled.set_child(Some(&list));
+
+        // Manual entry: id, optional file and line
+        let id_entry = Entry::new();
+        id_entry.set_placeholder_text(Some("Id"));
+        let file_entry = Entry::new();
+        file_entry.set_placeholder_text(Some("File (optional)"));
+        file_entry.set_hexpand(true);
+        let line_entry = Entry::new();
+        line_entry.set_placeholder_text(Some("Line (optional)"));
+        let add_btn = Button::with_label(tr("Add"));
+        let add_row = GtkBox::new(Orientation::Horizontal, 4);
+        add_row.append(&id_entry);
+        add_row.append(&file_entry);
+        add_row.append(&line_entry);
+        add_row.append(&add_btn);
+
+        let vbox = GtkBox::new(Orientation::Vertical, 4);
+        vbox.append(&header);
+        vbox.append(&scrolled);
+        vbox.append(&add_row);
+
+        let widget = Expander::new(Some("Suppressions"));
+        widget.set_child(Some(&vbox));
+
+        let panel = SuppressionsPanel {
+            widget,
+            path: Rc::new(RefCell::new(None)),
+            lines: Rc::new(RefCell::new(Vec::new())),
+            path_label,
+            list,
+            log: log.clone(),
+        };
+
+        {
+            let panel_clone = panel.clone();
+            choose_btn.connect_clicked(move |_| panel_clone.choose_file());
+        }
+        {
+            let panel_clone = panel.clone();
+            add_btn.connect_clicked(move |_| {
+                let id = id_entry.text().trim().to_string();
+                if id.is_empty() {
+                    return;
+                }
+                let file = file_entry.text().trim().to_string();
+                let line = line_entry.text().trim().parse::<u32>().ok();
+                panel_clone.add(Suppression {
+                    id,
+                    line: line.filter(|_| !file.is_empty()),
+                    file: Some(file).filter(|f| !f.is_empty()),
+                });
+                id_entry.set_text("");
+                file_entry.set_text("");
+                line_entry.set_text("");
+            });
+        }
+
+        panel.rebuild_list();
+        panel
+    }
+
+    // Path of the suppressions file, if one is configured and exists
+    pub fn path(&self) -> Option<String> {
+        self.path
+            .borrow()
+            .clone()
+            .filter(|p| Path::new(p).is_file())
+    }
+
+    pub fn set_path(&self, path: &str) {
+        *self.path.borrow_mut() = Some(path.to_string());
+        self.path_label.set_label(path);
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        *self.lines.borrow_mut() = suppress::parse_suppressions_list(&contents);
+        self.rebuild_list();
+    }
+
+    // Entries of the file, without its comments
+    pub fn entries(&self) -> Vec<Suppression> {
+        self.lines
+            .borrow()
+            .iter()
+            .filter_map(|line| match line {
+                ListLine::Entry(s) => Some(s.clone()),
+                ListLine::Other(_) => None,
+            })
+            .collect()
+    }
+
+    pub fn add_from_finding(&self, finding: &Finding) {
+        self.add(Suppression {
+            id: finding.id.clone(),
+            file: Some(finding.file.clone()),
+            line: Some(finding.line).filter(|l| *l > 0),
+        });
+        self.widget.set_expanded(true);
+    }
+
+    fn add(&self, entry: Suppression) {
+        if self.path.borrow().is_none() {
+            append_text(&self.log, "Choose a suppressions file first\n");
+            return;
+        }
+        append_text(&self.log, &format!("Added suppression {}\n", entry));
+        self.lines.borrow_mut().push(ListLine::Entry(entry));
+        self.save();
+        self.rebuild_list();
+    }
+
+    fn remove(&self, index: usize) {
+        let removed = {
+            let mut lines = self.lines.borrow_mut();
+            (index < lines.len()).then(|| lines.remove(index))
+        };
+        if let Some(ListLine::Entry(entry)) = removed {
+            append_text(&self.log, &format!("Removed suppression {}\n", entry));
+            self.save();
+            self.rebuild_list();
+        }
+    }
+
+    fn save(&self) {
+        let Some(ref path) = *self.path.borrow() else {
+            return;
+        };
+        let contents = suppress::format_suppressions_list(&self.lines.borrow());
+        if let Err(e) = fs::write(path, contents) {
+            append_text(&self.log, &format!("Failed to write {}: {}\n", path, e));
+        }
+    }
+
+    fn rebuild_list(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+        for (i, line) in self.lines.borrow().iter().enumerate() {
+            let ListLine::Entry(entry) = line else {
+                continue;
+            };
+            let row = GtkBox::new(Orientation::Horizontal, 8);
+            let label = Label::new(Some(&entry.to_string()));
+            label.set_hexpand(true);
+            label.set_xalign(0.0);
+            let remove_btn = Button::with_label(tr("Remove"));
+            let panel_clone = self.clone();
+            remove_btn.connect_clicked(move |_| panel_clone.remove(i));
+            row.append(&label);
+            row.append(&remove_btn);
+            self.list.append(&row);
+        }
+    }
+
+    fn choose_file(&self) {
+        let parent = self.widget.root().and_downcast::<Window>();
+        let dialog = FileChooserDialog::builder()
+            .title(tr("Select Suppressions File"))
+            .action(FileChooserAction::Save)
+            .modal(true)
+            .build();
+        dialog.set_transient_for(parent.as_ref());
+        dialog.add_buttons(&[
+            (tr("Cancel"), ResponseType::Cancel),
+            (tr("Select"), ResponseType::Accept),
+        ]);
+        let panel_clone = self.clone();
+        dialog.connect_response(move |d, r| {
+            if r == ResponseType::Accept {
+                if let Some(path) = d.file().and_then(|f| f.path()) {
+                    panel_clone.set_path(&path.to_string_lossy());
+                }
+            }
+            d.close();
+        });
+        dialog.show();
+    }
+}
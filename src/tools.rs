@@ -0,0 +1,93 @@
+use std::process::Command;
+
+// Whether `tool` resolves on PATH; `which` exits non-zero when it doesn't
+pub fn is_on_path(tool: &str) -> bool {
+    Command::new("which")
+        .arg(tool)
+        .output()
+        .map(|out| out.status.success())
+        .unwrap_or(false)
+}
+
+// (major, minor) from `cppcheck --version`, e.g. "Cppcheck 2.13.0"
+pub fn cppcheck_version() -> Option<(u32, u32)> {
+    let out = Command::new("cppcheck").arg("--version").output().ok()?;
+    parse_version(&String::from_utf8_lossy(&out.stdout))
+}
+
+fn parse_version(text: &str) -> Option<(u32, u32)> {
+    let version = text.split_whitespace().last()?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|m| m.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+// `--clang` (clang as the parser front end) needs clang and cppcheck 2.0+
+pub fn clang_supported(version: Option<(u32, u32)>) -> bool {
+    version.is_some_and(|v| v >= (2, 0)) && is_on_path("clang")
+}
+
+// `--bug-hunting` existed from cppcheck 2.0 until its removal in 2.7
+pub fn bug_hunting_supported(version: Option<(u32, u32)>) -> bool {
+    version.is_some_and(|v| ((2, 0)..(2, 7)).contains(&v))
+}
+
+// Interpreters offered for cppcheck addons, most generic first
+const PYTHON_CANDIDATES: &[&str] = &[
+    "python3",
+    "python",
+    "python3.13",
+    "python3.12",
+    "python3.11",
+    "python3.10",
+    "python3.9",
+    "python3.8",
+];
+
+// Python interpreters on PATH, for the --addon-python choice
+pub fn python_interpreters() -> Vec<&'static str> {
+    PYTHON_CANDIDATES
+        .iter()
+        .copied()
+        .filter(|python| is_on_path(python))
+        .collect()
+}
+
+// Browsers able to print the HTML report to PDF, in order of preference
+pub const PDF_TOOLS: &[&str] = &["google-chrome", "chromium-browser", "chromium"];
+
+// First available browser for PDF export
+pub fn pdf_tool() -> Option<&'static str> {
+    PDF_TOOLS.iter().copied().find(|tool| is_on_path(tool))
+}
+
+// External tools the GUI drives, as (name, found). For PDF export this is
+// the browser that would be used, or the preferred one if none is installed
+pub fn availability() -> Vec<(&'static str, bool)> {
+    let pdf = pdf_tool();
+    vec![
+        ("cppcheck", is_on_path("cppcheck")),
+        ("cppcheck-htmlreport", is_on_path("cppcheck-htmlreport")),
+        (pdf.unwrap_or(PDF_TOOLS[0]), pdf.is_some()),
+        ("clang", is_on_path("clang")),
+    ]
+}
+
+// How to get a missing tool onto PATH
+pub fn install_hint(tool: &str) -> String {
+    match tool {
+        "cppcheck-htmlreport" => "cppcheck-htmlreport ships with cppcheck: install it with \
+                                  \"sudo apt-get install cppcheck\" (some distributions \
+                                  package it separately) and make sure it is on PATH. \
+                                  Without it, Export > Open in Browser shows the \
+                                  findings as a simple HTML table."
+            .to_string(),
+        "google-chrome" | "chromium-browser" | "chromium" => {
+            "Install Chrome or Chromium, e.g. \"sudo apt-get install chromium-browser\", \
+             and make sure it is on PATH."
+                .to_string()
+        }
+        tool => format!("Install {} and make sure it is on PATH.", tool),
+    }
+}
@@ -0,0 +1,118 @@
+use crate::config::{Config, UsageStats};
+use crate::i18n::tr;
+use crate::{append_text, elapsed_text};
+use gtk4::prelude::*;
+use gtk4::{
+    Align, ApplicationWindow, Box as GtkBox, Button, Grid, Label, ListBox, Orientation,
+    ScrolledWindow, TextBuffer, Window,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// Days listed, newest first
+const SHOWN_DAYS: usize = 30;
+
+// Totals and per-day counts of the completed scans, with a reset
+pub fn show(parent: &ApplicationWindow, config: &Rc<RefCell<Config>>, log: &TextBuffer) {
+    let window = Window::builder()
+        .title(tr("Usage Statistics"))
+        .transient_for(parent)
+        .modal(true)
+        .default_width(360)
+        .default_height(420)
+        .build();
+
+    let totals = Grid::new();
+    totals.set_row_spacing(4);
+    totals.set_column_spacing(12);
+    let mut values = Vec::new();
+    for (row, name) in [
+        "Scans run:",
+        "Findings reported:",
+        "Findings per scan:",
+        "Average scan duration:",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let label = Label::new(Some(name));
+        label.set_xalign(0.0);
+        let value = Label::new(None);
+        value.set_xalign(0.0);
+        totals.attach(&label, 0, row as i32, 1, 1);
+        totals.attach(&value, 1, row as i32, 1, 1);
+        values.push(value);
+    }
+
+    let days = ListBox::new();
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(&days));
+
+    let update = {
+        let days = days.clone();
+        move |usage: &UsageStats| {
+            let per_scan = |total: u64| match usage.scans {
+                0 => "–".to_string(),
+                n => format!("{:.1}", total as f64 / n as f64),
+            };
+            values[0].set_text(&usage.scans.to_string());
+            values[1].set_text(&usage.findings.to_string());
+            values[2].set_text(&per_scan(usage.findings));
+            values[3].set_text(&match usage.scans {
+                0 => "–".to_string(),
+                n => elapsed_text(usage.seconds / n),
+            });
+            while let Some(row) = days.row_at_index(0) {
+                days.remove(&row);
+            }
+            for (date, (scans, findings)) in usage.days.iter().rev().take(SHOWN_DAYS) {
+                let label = Label::new(Some(&format!(
+                    "{}   {} scan(s), {} finding(s)",
+                    date, scans, findings
+                )));
+                label.set_xalign(0.0);
+                days.append(&label);
+            }
+            if usage.days.is_empty() {
+                days.append(&Label::new(Some(tr("No scans counted yet"))));
+            }
+        }
+    };
+    update(&config.borrow().usage);
+
+    let reset_btn = Button::with_label(tr("Reset"));
+    reset_btn.set_halign(Align::End);
+    {
+        let config = config.clone();
+        let log = log.clone();
+        reset_btn.connect_clicked(move |_| {
+            let mut cfg = config.borrow_mut();
+            cfg.usage = UsageStats::default();
+            if let Err(e) = cfg.save() {
+                append_text(&log, &format!("Failed to save settings: {}\n", e));
+            }
+            update(&cfg.usage);
+        });
+    }
+
+    let note = Label::new(Some(
+        "Counted on this computer and kept in the settings file only; \
+         no data leaves your machine.",
+    ));
+    note.set_wrap(true);
+    note.set_xalign(0.0);
+
+    let vbox = GtkBox::new(Orientation::Vertical, 8);
+    vbox.set_margin_top(12);
+    vbox.set_margin_bottom(12);
+    vbox.set_margin_start(12);
+    vbox.set_margin_end(12);
+    vbox.append(&totals);
+    vbox.append(&Label::new(Some(tr("Recent days"))));
+    vbox.append(&scrolled);
+    vbox.append(&note);
+    vbox.append(&reset_btn);
+    window.set_child(Some(&vbox));
+    window.present();
+}